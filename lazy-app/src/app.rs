@@ -1,41 +1,172 @@
 //! `App` 模块，定义了应用程序的主要结构和逻辑。
 
-use std::error::Error;
+use std::{
+    borrow::Cow,
+    error::Error,
+    io::stdout,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
 
+// 从 crossterm 中导入开启和关闭鼠标捕获的命令
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+};
+// 从 lazy_core 中导入日志缓冲区和播放队列
+use lazy_core::{
+    color::ColorDepth, logger::LogBuffer, publisher::Publisher, queue::Queue, theme::TuiTheme,
+    track::Track,
+};
+// 从 lazy_library 中导入媒体库数据库、索引和扫描器
+use lazy_library::{
+    browse::{audio_files, list_dir},
+    db::{LibraryDb, RescanReport},
+    error::LibraryError,
+    index::Library,
+    playlist::{
+        Playlist, create_playlist, delete_playlist, list_playlists, playlist_path, rename_playlist,
+    },
+    scanner::Scanner,
+    search::{SearchIndex, SearchQuery, SearchResults},
+    tags::read_track,
+    watcher::{DEFAULT_DEBOUNCE, LibraryEvent, LibraryWatcher},
+};
+// 从 lazy_player 中导入播放器句柄、引擎事件和默认输出端
+use lazy_player::{engine::PlayerEvent, player::Player, sink::default_sink};
 // 从 lazy_tui 中导入根 TUI 组件和 RenderTui trait
 use lazy_tui::{
     root::RootTui,
     traits::{RenderTui, TuiEventHandle},
+    types::{Direction, EnqueueMode, Mouse, QueueChange, TuiEnent},
 };
+// 从 log 中导入日志宏
+use log::{debug, error, info, warn};
 // 从 tokio 中导入时间相关的组件
-use tokio::time::{Duration, Interval, MissedTickBehavior, interval};
+use tokio::time::{Duration, Instant, Interval, MissedTickBehavior, interval, interval_at};
 
-// 从当前 crate 的 event 模块中导入事件处理器和按键状态
-use crate::event::{EventHandler, KeyStatus};
+// 从当前 crate 中导入命令、事件处理器和按键状态
+use crate::{
+    bus::{Bus, Message},
+    command::Command,
+    config::{Config, ConfigError},
+    event::{EventHandler, InputAction, KeyAction},
+    theme::{BUNDLED, DEFAULT_THEME, Theme},
+    watcher::{CONFIG_DEBOUNCE, ConfigEvent, ConfigWatcher},
+};
 
 /// `App` 结构体，代表整个应用程序。
 ///
 /// 它包含了应用程序的状态、事件处理器和 TUI。
 pub struct App {
-    running: bool,          // 表示应用程序是否正在运行
-    event: EventHandler,    // 事件处理器，负责处理用户输入
-    tui: RootTui,           // 根 TUI 组件
-    tui_interval: Interval, // TUI 刷新定时器
+    running: bool,                          // 表示应用程序是否正在运行
+    event: EventHandler,                    // 事件处理器，负责处理用户输入
+    tui: RootTui,                           // 根 TUI 组件
+    tui_interval: Interval,                 // 限制 TUI 绘制频率的定时器
+    bus: Bus,                               // 后台服务发布事件的消息总线
+    player: Player,                         // 播放引擎句柄
+    volume: u8,                             // 当前音量，范围 0..=100
+    queue: Queue,                           // 播放队列
+    progress: Duration,                     // 当前播放时间
+    duration: Duration,                     // 当前曲目总时长
+    seek_step: Duration,                    // 快进/快退的步长
+    library: Arc<Library>,                  // 媒体库索引，与后台搜索共享
+    library_db: Option<PathBuf>,            // 媒体库数据库路径，未设置时不持久化
+    library_roots: Vec<PathBuf>,            // 正在扫描和监视的音乐库根目录
+    music_dirs: Vec<PathBuf>,               // 配置文件中的音乐库根目录
+    roots_override: bool,                   // 根目录由命令行指定，忽略配置文件中的 `music_dirs`
+    library_started: bool,                  // 是否已经开始扫描音乐库，之前修改配置不触发扫描
+    watcher: Option<LibraryWatcher>,        // 音乐目录监视器
+    rescan_interval: Option<Interval>,      // 无法监视目录时的定期重新扫描定时器
+    playlists_dir: Option<PathBuf>,         // 播放列表目录，未设置时无法新建播放列表
+    search_index: Option<Arc<SearchIndex>>, // 最近一次搜索使用的索引，媒体库变化后重新构建
+    search_text: String,                    // 最近一次搜索的文本
+    search_generation: Arc<AtomicU64>,      // 搜索的序号，发起新的搜索时取消之前的搜索
+    log_buffer: Option<LogBuffer>,          // 日志缓冲区，新的日志会发送给日志页
+    log_seq: u64,                           // 下一条要发送给 TUI 的日志的序号
+    config_path: Option<PathBuf>,           // 配置文件路径，未设置时使用默认配置
+    config_watcher: Option<ConfigWatcher>,  // 配置文件监视器
+    theme: String,                          // 当前主题（配置文件中 `theme` 的写法）
+    color_depth: ColorDepth,                // 终端的颜色深度，主题的颜色会被映射到这个深度
+}
+
+/// 后台搜索完成的结果。
+pub struct SearchDone {
+    generation: u64,         // 搜索的序号
+    index: Arc<SearchIndex>, // 搜索使用的索引，可以被之后的搜索复用
+    results: SearchResults,  // 搜索结果
 }
 
+/// 无法监视音乐目录时，定期重新扫描的周期
+const RESCAN_PERIOD: Duration = Duration::from_secs(5 * 60);
+
+/// 两次绘制 TUI 之间的默认最小间隔，约每秒 30 帧
+const FRAME_INTERVAL: Duration = Duration::from_millis(33);
+
+/// 快进/快退的默认步长
+const SEEK_STEP: Duration = Duration::from_secs(5);
+
+/// 每次搜索最多返回的曲目数
+const SEARCH_LIMIT: usize = 500;
+
 impl Default for App {
     /// 创建一个默认的 `App` 实例。
     fn default() -> Self {
-        // 创建一个每 100 毫秒触发一次的定时器
-        let mut tui_interval = interval(Duration::from_millis(100));
-        // 如果错过了 tick，则跳过，以防止 UI 刷新堆积
+        // 两次绘制之间的最小间隔，只在界面发生变化时才等待定时器
+        let mut tui_interval = interval(FRAME_INTERVAL);
+        // 如果错过了 tick，则跳过，空闲之后的第一次变化可以立即绘制
         tui_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
+        let bus = Bus::default();
+
+        // 在后台线程中启动播放引擎，引擎事件发布到消息总线上
+        let player = Player::spawn(default_sink, bus.publisher());
+        let volume = 50;
+        player.set_volume(volume);
+
+        // 组件创建时使用当前主题，终端不支持真彩色时先把默认主题映射为终端能显示的颜色
+        let color_depth = ColorDepth::detect();
+        TuiTheme::set_current(TuiTheme::default().adapt(color_depth));
+
+        // 每次启动使用不同的随机种子，随机播放的顺序不会重复
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+
         Self {
             running: Default::default(),
             event: Default::default(),
             tui: Default::default(),
             tui_interval,
+            bus,
+            player,
+            volume,
+            queue: Queue::with_seed(seed),
+            progress: Duration::ZERO,
+            duration: Duration::ZERO,
+            seek_step: SEEK_STEP,
+            library: Default::default(),
+            library_db: None,
+            library_roots: Vec::new(),
+            music_dirs: Vec::new(),
+            roots_override: false,
+            library_started: false,
+            watcher: None,
+            rescan_interval: None,
+            playlists_dir: None,
+            search_index: None,
+            search_text: String::new(),
+            search_generation: Default::default(),
+            log_buffer: None,
+            log_seq: 0,
+            config_path: None,
+            config_watcher: None,
+            theme: DEFAULT_THEME.to_string(),
+            color_depth,
         }
     }
 }
@@ -47,30 +178,47 @@ impl App {
     ///
     /// * `Result<(), Box<dyn Error>>` - 如果成功，返回 `Ok(())`，否则返回一个错误。
     pub async fn run(&mut self) -> Result<(), Box<dyn Error>> {
-        // 初始化终端，开启 TUI 环境
+        // 初始化终端，开启 TUI 环境，并捕获鼠标事件
         let mut terminal = ratatui::init();
+        execute!(stdout(), EnableMouseCapture)?;
 
         self.start(); // 设置程序状态为运行中
 
         // 主循环：程序运行期间不断处理事件和定时器
         while self.running {
             tokio::select! {
-                // 异步等待按键事件
-                key_status = self.event.next_key_status() => {
-                    if let Some(key) = key_status {
+                // 异步等待按键和鼠标事件
+                input = self.event.next_input() => {
+                    match input {
                         // 如果有按键事件，调用事件处理器
-                        self.event_handler(key);
+                        Some(InputAction::Key(key)) => self.event_handler(key),
+                        Some(InputAction::Mouse(mouse)) => self.mouse_handler(mouse),
+                        // 终端大小改变后需要按新的大小重新绘制
+                        Some(InputAction::Resize) => self.tui.mark_dirty(),
+                        None => (),
                     }
                 }
-                // 定时器触发事件，定时器触发更新一次 UI
-                _ = self.tui_interval.tick() => {
-                    // 绘制 TUI
+                // 后台服务发布的事件和命令
+                Some(message) = self.bus.recv() => {
+                    self.message_handler(message);
+                }
+                // 无法监视音乐目录时定期重新扫描
+                _ = Self::tick_rescan(&mut self.rescan_interval) => {
+                    self.spawn_scan();
+                }
+                // 界面发生变化时等待下一个 tick 再绘制，把这段时间内的多次变化合并为一帧；
+                // 界面没有变化时不等待定时器，暂停播放时主循环不会被唤醒
+                _ = self.tui_interval.tick(), if self.tui.is_dirty() => {
+                    self.tui.take_dirty();
                     terminal.draw(|f| self.tui.render(f,f.area()))?;
                 }
             }
+            // 将处理事件时产生的新日志发送给日志页
+            self.sync_logs();
         }
 
-        // 退出主循环后，恢复终端状态
+        // 退出主循环后，关闭鼠标捕获并恢复终端状态
+        execute!(stdout(), DisableMouseCapture)?;
         ratatui::restore();
 
         Ok(())
@@ -91,11 +239,291 @@ impl App {
         self.running = false;
     }
 
-    /// 设置或更新 TUI 刷新定时器。
+    /// 将文件添加到播放队列末尾。
+    ///
+    /// 媒体库中已有的曲目直接使用库中的信息，否则读取文件的标签。
+    pub fn enqueue<I, P>(&mut self, paths: I)
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        let tracks: Vec<_> = paths
+            .into_iter()
+            .map(|path| {
+                let path = path.into();
+                match self.library.get(&path) {
+                    Some(track) => track.clone(),
+                    None => read_track(&path).unwrap_or_else(|_| Track::new(path)),
+                }
+            })
+            .collect();
+        let start = self.queue.len();
+        self.queue.extend(tracks);
+        self.tui
+            .event_handle(TuiEnent::Queue(QueueChange::Appended(Cow::Borrowed(
+                &self.queue.tracks()[start..],
+            ))));
+        self.sync_queue();
+    }
+
+    /// 返回播放队列。
+    pub fn queue(&self) -> &Queue {
+        &self.queue
+    }
+
+    /// 从数据库加载媒体库，之后的扫描结果也会保存到该数据库。
+    pub fn load_library(&mut self, db_path: impl Into<PathBuf>) -> Result<(), LibraryError> {
+        let db_path = db_path.into();
+        self.library = Arc::new(LibraryDb::open(&db_path)?.load()?);
+        info!(
+            "Loaded {} tracks from {}",
+            self.library.len(),
+            db_path.display()
+        );
+        self.library_db = Some(db_path);
+        self.tui
+            .event_handle(TuiEnent::LibraryUpdated(&self.library));
+        self.refresh_search();
+        Ok(())
+    }
+
+    /// 开始扫描音乐库：命令行指定了目录时扫描这些目录，否则扫描配置文件中的 `music_dirs`。
+    ///
+    /// 命令行指定的目录覆盖配置文件，之后修改配置文件中的 `music_dirs` 不会改变扫描的目录。
+    pub fn start_library(&mut self, cli_roots: Vec<PathBuf>) {
+        self.roots_override = !cli_roots.is_empty();
+        let roots = if self.roots_override {
+            cli_roots
+        } else {
+            self.music_dirs.clone()
+        };
+        self.scan_library(roots);
+    }
+
+    /// 在后台线程中扫描音乐目录，完成后合并到媒体库，并开始监视目录的变化。
+    ///
+    /// 加载过数据库时只重新读取发生变化的文件，否则完整扫描一次。
+    /// 再次调用时停止监视之前的目录，改为扫描和监视新的目录。
+    pub fn scan_library<I, P>(&mut self, roots: I)
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.library_started = true;
+        self.watcher = None;
+        self.rescan_interval = None;
+        self.library_roots = roots.into_iter().map(Into::into).collect();
+        self.tui
+            .event_handle(TuiEnent::MusicRoots(Cow::Borrowed(&self.library_roots)));
+        if self.library_roots.is_empty() {
+            return;
+        }
+        self.spawn_scan();
+        self.watch_library();
+    }
+
+    /// 在后台线程中扫描所有音乐库根目录。
+    fn spawn_scan(&self) {
+        let scanner = Scanner::new(self.library_roots.clone());
+        let db_path = self.library_db.clone();
+        let events: Publisher<LibraryEvent> = self.bus.publisher();
+        tokio::task::spawn_blocking(move || {
+            let event = match db_path {
+                Some(db_path) => {
+                    match LibraryDb::open(db_path).and_then(|mut db| db.rescan(&scanner)) {
+                        Ok(report) => LibraryEvent::Scanned(report),
+                        Err(e) => LibraryEvent::Error(e.to_string()),
+                    }
+                }
+                None => {
+                    let report = scanner.scan();
+                    LibraryEvent::Scanned(RescanReport {
+                        added: report.tracks,
+                        errors: report.errors,
+                        ..Default::default()
+                    })
+                }
+            };
+            events.publish(event);
+        });
+    }
+
+    /// 开始监视音乐库根目录；无法监视时退回到定期重新扫描。
+    fn watch_library(&mut self) {
+        match LibraryWatcher::watch(
+            &self.library_roots,
+            self.library_db.clone(),
+            DEFAULT_DEBOUNCE,
+            self.bus.publisher(),
+        ) {
+            Ok(watcher) => self.watcher = Some(watcher),
+            Err(e) => {
+                warn!("Failed to watch music folders: {e}");
+                self.enable_periodic_rescan();
+            }
+        }
+    }
+
+    /// 启用定期重新扫描。
+    fn enable_periodic_rescan(&mut self) {
+        if self.rescan_interval.is_none() {
+            let mut rescan = interval_at(Instant::now() + RESCAN_PERIOD, RESCAN_PERIOD);
+            rescan.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            self.rescan_interval = Some(rescan);
+        }
+    }
+
+    /// 等待下一次定期重新扫描；未启用时永远不会完成。
+    async fn tick_rescan(rescan_interval: &mut Option<Interval>) {
+        match rescan_interval {
+            Some(rescan) => {
+                rescan.tick().await;
+            }
+            None => std::future::pending().await,
+        }
+    }
+
+    /// 设置播放列表目录，并把其中的播放列表发送给 TUI。
+    pub fn set_playlists_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.playlists_dir = Some(dir.into());
+        self.refresh_playlists();
+    }
+
+    /// 读取配置文件并应用其中的设置，之后配置文件或主题文件变化时自动重新读取。
+    pub fn load_config(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        match ConfigWatcher::watch(&path, CONFIG_DEBOUNCE, self.bus.publisher()) {
+            Ok(watcher) => self.config_watcher = Some(watcher),
+            // 配置目录不存在时没有需要监视的文件
+            Err(e) => info!("Not watching {}: {e}", path.display()),
+        }
+        self.config_path = Some(path);
+        self.reload_config();
+    }
+
+    /// 重新读取配置文件；有错误时记录每个错误并保留当前的设置。
+    fn reload_config(&mut self) {
+        let Some(path) = &self.config_path else {
+            return;
+        };
+        match Config::load(path) {
+            Ok(config) => self.apply_config(&config),
+            Err(e) => {
+                log_config_error(&e);
+                warn!("Keeping the current configuration");
+            }
+        }
+    }
+
+    /// 处理配置文件监视器的事件。
+    fn config_event_handler(&mut self, event: ConfigEvent) {
+        match event {
+            ConfigEvent::Changed => {
+                info!("Configuration changed, reloading");
+                self.reload_config();
+            }
+            ConfigEvent::Error(e) => warn!("Failed to watch the configuration: {e}"),
+        }
+    }
+
+    /// 应用配置文件中的设置。
+    ///
+    /// 按键绑定先恢复为默认绑定再添加配置中的绑定，因此从配置中删除的绑定会失效；
+    /// 没有设置主题或找不到主题时使用默认主题；没有设置颜色深度时自动检测；没有设置步长时使用默认步长。
+    /// 开始扫描音乐库之后修改了 `music_dirs` 时改为扫描新的目录，命令行指定了目录时除外。
+    pub fn apply_config(&mut self, config: &Config) {
+        self.color_depth = config.color_depth.unwrap_or_else(ColorDepth::detect);
+        self.set_seek_step(config.seek_step.unwrap_or(SEEK_STEP));
+
+        self.music_dirs = config.music_dirs.clone().unwrap_or_default();
+        if self.library_started && !self.roots_override && self.library_roots != self.music_dirs {
+            info!("Music folders changed, rescanning");
+            self.scan_library(self.music_dirs.clone());
+        }
+
+        self.event.reset_keybindings();
+        self.event.add_keybindings(
+            config
+                .keys
+                .iter()
+                .map(|binding| (binding.layer, binding.keys.clone(), binding.action)),
+        );
+
+        let name = config.theme.as_deref().unwrap_or(DEFAULT_THEME);
+        let config_dir = self.config_path.as_deref().and_then(Path::parent);
+        match Theme::find(name, config_dir) {
+            Ok(theme) => self.set_theme(name, theme),
+            Err(e) => {
+                log_config_error(&e);
+                warn!("Using the default theme");
+                self.set_theme(DEFAULT_THEME, Theme::default());
+            }
+        }
+    }
+
+    /// 切换主题，所有组件用主题的颜色重新设置样式。
+    ///
+    /// 主题的颜色会先被映射到终端的颜色深度，组件不需要关心终端支持多少种颜色。
+    /// `name` 是配置文件中 `theme` 的写法，用于在内置主题之间循环切换。
+    fn set_theme(&mut self, name: &str, theme: Theme) {
+        let colors = theme.colors.adapt(self.color_depth);
+        TuiTheme::set_current(colors);
+        self.tui.event_handle(TuiEnent::Theme(colors));
+        if self.theme != name {
+            info!("Theme: {}", theme.name);
+        }
+        self.theme = name.to_string();
+    }
+
+    /// 切换到下一个内置主题；当前主题不是内置主题时切换到第一个内置主题。
+    fn cycle_theme(&mut self) {
+        let next = BUNDLED
+            .iter()
+            .position(|(name, _)| *name == self.theme)
+            .map_or(0, |i| (i + 1) % BUNDLED.len());
+        let (name, _) = BUNDLED[next];
+        if let Some(theme) = Theme::bundled(name) {
+            self.set_theme(name, theme);
+        }
+    }
+
+    /// 设置日志缓冲区，之后记录的日志会显示在日志页中。
+    pub fn set_log_buffer(&mut self, buffer: LogBuffer) {
+        self.log_buffer = Some(buffer);
+        self.log_seq = 0;
+        self.sync_logs();
+    }
+
+    /// 将日志缓冲区中新的日志发送给 TUI。
+    fn sync_logs(&mut self) {
+        let Some(buffer) = &self.log_buffer else {
+            return;
+        };
+        if buffer.next_seq() == self.log_seq {
+            return;
+        }
+        let (records, next) = buffer.since(self.log_seq);
+        self.log_seq = next;
+        self.tui.event_handle(TuiEnent::Logs(Cow::Owned(records)));
+    }
+
+    /// 返回媒体库索引。
+    pub fn library(&self) -> &Library {
+        &self.library
+    }
+
+    /// 设置快进/快退的步长。
+    pub fn set_seek_step(&mut self, step: Duration) {
+        self.seek_step = step;
+    }
+
+    /// 设置两次绘制 TUI 之间的最小间隔。
+    ///
+    /// 界面只在发生变化时重新绘制，间隔内的多次变化会合并为一次绘制。
     ///
     /// # Arguments
     ///
-    /// * `duration` - 新的刷新周期。
+    /// * `duration` - 新的最小绘制间隔。
     pub fn set_tui_interval(&mut self, duration: Duration) {
         // 创建新的定时器
         let mut new_interval = interval(duration);
@@ -105,30 +533,566 @@ impl App {
         self.tui_interval = new_interval;
     }
 
-    /// 处理按键事件，将 `KeyStatus` 映射为具体操作。
+    /// 处理按键事件，将 `KeyStatus` 转换为命令并按数字前缀重复执行。
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - 从事件处理器接收到的按键操作。
+    fn event_handler(&mut self, key: KeyAction) {
+        if let Some(command) = Command::from_key(key.status) {
+            match command {
+                // 连续跳过多首时只播放最后一首
+                Command::SkipTrack(direction) => self.skip_track(direction, key.count),
+                command if command.is_repeatable() => {
+                    for _ in 0..key.count {
+                        self.dispatch(command.clone());
+                    }
+                }
+                command => self.dispatch(command),
+            }
+        }
+        // 切换页面或开始、结束输入文本后，切换生效的按键映射层
+        self.event.set_focus(self.tui.focus());
+    }
+
+    /// 处理鼠标事件，交给位于鼠标位置的组件。
+    fn mouse_handler(&mut self, mouse: Mouse) {
+        self.dispatch(Command::Mouse(mouse));
+        // 点击导航栏可能切换了页面，点击搜索框可能开始了输入
+        self.event.set_focus(self.tui.focus());
+    }
+
+    /// 执行一条命令，并处理 TUI 在此过程中产生的操作。
     ///
     /// # Arguments
     ///
-    /// * `key_status` - 从事件处理器接收到的按键状态。
-    fn event_handler(&mut self, key_status: KeyStatus) {
-        use crate::event::KeyStatus::*;
-        use lazy_tui::types::{Direction, TuiEnent::*};
-        match key_status {
-            Quit => self.stop(),                                  // q → 退出程序
-            TogglePlay => self.tui.event_handle(Playback),        // p → 播放/暂停
-            VolumeIncrease => self.tui.event_handle(Volume(10)),  // + → 增加音量
-            VolumeDecrease => self.tui.event_handle(Volume(-10)), // - → 减少音量
-            ProgressIncrease => (),                               // l → 快进
-            ProgressDecrease => (),                               // h → 快退
-            PickerNext => (),                                     // j → 选择下一个
-            PickerPrev => (),                                     // k → 选择上一个
-            SwitchMode => self.tui.event_handle(PlaybackMode),    // m → 切换模式
-            NextTrack => (),                                      // ] → 下一首
-            PrevTrack => (),                                      // [ → 上一首
-            PlaySelected => (),                                   // Enter → 播放选中
-            NavbarNext => self.tui.event_handle(Navbar(Direction::Right)),
-            NavbarPrve => self.tui.event_handle(Navbar(Direction::Left)),
-            NoOp => (), // 无操作
+    /// * `command` - 要执行的命令。
+    pub fn dispatch(&mut self, command: Command) {
+        match command {
+            Command::Quit => self.stop(),
+            Command::TogglePlay => self.toggle_play(),
+            Command::Volume(delta) => self.adjust_volume(delta),
+            Command::Seek(direction) => self.seek(direction),
+            Command::SeekTo(position) => self.seek_to(position),
+            Command::SkipTrack(direction) => self.skip_track(direction, 1),
+            Command::Picker(direction) => self.input(TuiEnent::Picker(direction)),
+            Command::PlaySelected => self.input(TuiEnent::PlaySelected),
+            Command::DeleteSelected => self.input(TuiEnent::DeleteSelected),
+            Command::MoveSelected(direction) => self.input(TuiEnent::MoveSelected(direction)),
+            Command::Clear => self.input(TuiEnent::Clear),
+            Command::Parent => self.input(TuiEnent::Parent),
+            Command::AddSelected => self.input(TuiEnent::AddSelected),
+            Command::ReplaceWithSelected => self.input(TuiEnent::ReplaceWithSelected),
+            Command::CycleSort => self.input(TuiEnent::CycleSort),
+            Command::Create => self.input(TuiEnent::Create),
+            Command::Rename => self.input(TuiEnent::Rename),
+            Command::SaveQueue => self.input(TuiEnent::SaveQueue),
+            Command::TextInput(input) => self.input(TuiEnent::TextInput(input)),
+            Command::Mouse(mouse) => {
+                self.tui.event_handle(TuiEnent::Mouse(mouse));
+            }
+            Command::PlayQueueIndex(index) => self.play_queue_index(index),
+            Command::RemoveQueueIndex(index) => self.remove_queue_index(index),
+            Command::MoveQueueIndex(from, to) => {
+                if self.queue.move_track(from, to) {
+                    self.tui
+                        .event_handle(TuiEnent::Queue(QueueChange::Moved(from, to)));
+                    self.sync_queue();
+                }
+            }
+            Command::ClearQueue => self.clear_queue(),
+            Command::ListDirectory(path) => self.list_directory(path),
+            Command::LoadTracks(paths) => self.load_tracks(paths),
+            Command::Enqueue(paths, mode) => self.enqueue_paths(&paths, mode),
+            Command::LoadPlaylist(path) => self.load_playlist(path),
+            Command::CreatePlaylist(name) => self.create_playlist(&name),
+            Command::RenamePlaylist(path, name) => self.rename_playlist(&path, &name),
+            Command::DeletePlaylist(path) => self.delete_playlist(&path),
+            Command::SavePlaylist(playlist) => self.save_playlist(playlist),
+            Command::SaveQueueAsPlaylist(name) => self.save_queue_as_playlist(&name),
+            Command::Find => self.input(TuiEnent::Find),
+            Command::CycleLevel => self.input(TuiEnent::CycleLevel),
+            Command::ToggleFollow => self.input(TuiEnent::ToggleFollow),
+            Command::Search(text) => self.search(text),
+            Command::SwitchMode => self.switch_mode(),
+            Command::Navbar(direction) => {
+                self.tui.event_handle(TuiEnent::Navbar(direction));
+            }
+            Command::SelectView(item) => {
+                self.tui.event_handle(TuiEnent::SelectView(item));
+            }
+            Command::CycleTheme => self.cycle_theme(),
+            Command::Focus(direction) => {
+                self.tui.event_handle(TuiEnent::Focus(direction));
+            }
         }
+
+        // TUI 可能在处理事件时请求新的操作（例如播放选中项）
+        for action in self.tui.take_actions() {
+            self.dispatch(action.into());
+        }
+    }
+
+    /// 把按键产生的输入事件交给当前页面；没有组件处理时记录日志，方便排查不生效的按键。
+    fn input(&mut self, event: TuiEnent) {
+        if !self.tui.event_handle(event).is_handled() {
+            debug!("Input not handled by the {:?} view", self.tui.focus().view);
+        }
+    }
+
+    /// 播放/暂停切换；尚未开始播放时从队列的第一首开始。
+    fn toggle_play(&mut self) {
+        if self.queue.current().is_none() {
+            self.skip_track(Direction::Right, 1);
+        } else {
+            self.player.toggle_pause();
+        }
+    }
+
+    /// 调整音量，同时更新播放引擎和 TUI。
+    fn adjust_volume(&mut self, delta: i8) {
+        self.volume = (self.volume as i16 + delta as i16).clamp(0, 100) as u8;
+        self.player.set_volume(self.volume);
+        self.tui.event_handle(TuiEnent::Volume(delta));
+    }
+
+    /// 按跳转步长快退（`Left`）或快进（`Right`）。
+    fn seek(&mut self, direction: Direction) {
+        let position = match direction {
+            Direction::Right | Direction::Down => self.progress + self.seek_step,
+            Direction::Left | Direction::Up => self.progress.saturating_sub(self.seek_step),
+        };
+        self.seek_to(position);
+    }
+
+    /// 跳转到当前曲目的指定位置，超过总时长时跳到结尾；总时长未知时忽略。
+    fn seek_to(&mut self, position: Duration) {
+        if self.duration.is_zero() {
+            return;
+        }
+        let position = position.min(self.duration);
+        self.progress = position;
+        self.player.seek(position);
+        self.tui.event_handle(TuiEnent::Seek(position));
+    }
+
+    /// 向前（`Left`）或向后（`Right`）切换 `count` 首曲目，只播放最后到达的曲目。
+    fn skip_track(&mut self, direction: Direction, count: usize) {
+        let step = match direction {
+            Direction::Left | Direction::Up => Queue::prev_track,
+            Direction::Right | Direction::Down => Queue::next_track,
+        };
+        let mut path = None;
+        for _ in 0..count {
+            match self.step_queue(step) {
+                Some(next) => path = Some(next),
+                None => break,
+            }
+        }
+        if let Some(path) = path {
+            self.player.play(path);
+            self.tui.event_handle(TuiEnent::SkipTrack(direction));
+        }
+        // 消费模式下即使没有下一首，当前曲目也可能已被移除
+        self.sync_queue();
+    }
+
+    /// 当前曲目播放完毕，按播放模式播放下一首。
+    fn advance(&mut self) {
+        if let Some(path) = self.step_queue(Queue::advance) {
+            self.player.play(path);
+            self.tui.event_handle(TuiEnent::SkipTrack(Direction::Right));
+        }
+        self.sync_queue();
+    }
+
+    /// 播放引擎已经无缝切换到预加载的曲目，队列随之前进。
+    ///
+    /// 预加载之后队列发生了变化（例如用户切换了曲目）时，队列中的下一首不再是这首曲目，
+    /// 这时以用户的操作为准，队列保持不变。
+    fn advanced(&mut self, path: PathBuf) {
+        if self.queue.peek_advance().map(Track::path) != Some(path.as_path()) {
+            return;
+        }
+        self.step_queue(Queue::advance);
+        self.tui.event_handle(TuiEnent::SkipTrack(Direction::Right));
+        self.sync_queue();
+    }
+
+    /// 在队列中切换曲目，返回切换到的曲目的路径。
+    ///
+    /// 消费模式下切换会先移除当前曲目，这时把移除的曲目告诉 TUI。
+    fn step_queue(&mut self, step: fn(&mut Queue) -> Option<&Track>) -> Option<PathBuf> {
+        let (len, current) = (self.queue.len(), self.queue.current_index());
+        let path = step(&mut self.queue).map(|track| track.path.clone());
+        if self.queue.len() < len
+            && let Some(index) = current
+        {
+            self.tui
+                .event_handle(TuiEnent::Queue(QueueChange::Removed(index)));
+        }
+        path
+    }
+
+    /// 让播放引擎预加载当前曲目播放完毕后的下一首，实现无缝播放。
+    fn preload(&self) {
+        let next = self.queue.peek_advance().map(|track| track.path.clone());
+        self.player.preload(next);
+    }
+
+    /// 切换到下一个播放模式，当前曲目保持不变。
+    fn switch_mode(&mut self) {
+        let mode = self.queue.mode().next();
+        self.queue.set_mode(mode);
+        self.tui.event_handle(TuiEnent::PlaybackMode(mode));
+        self.preload();
+    }
+
+    /// 播放队列中指定索引的曲目。
+    fn play_queue_index(&mut self, index: usize) {
+        if let Some(track) = self.queue.select(index) {
+            self.player.play(track.path.clone());
+            self.sync_queue();
+        }
+    }
+
+    /// 从队列中移除指定索引的曲目，移除的是正在播放的曲目时停止播放。
+    fn remove_queue_index(&mut self, index: usize) {
+        let playing = self.queue.current_index() == Some(index);
+        if self.queue.remove(index).is_some() {
+            if playing {
+                self.player.stop();
+            }
+            self.tui
+                .event_handle(TuiEnent::Queue(QueueChange::Removed(index)));
+            self.sync_queue();
+        }
+    }
+
+    /// 清空播放队列并停止播放。
+    fn clear_queue(&mut self) {
+        if self.queue.current().is_some() {
+            self.player.stop();
+        }
+        self.queue.clear();
+        self.tui.event_handle(TuiEnent::Queue(QueueChange::Cleared));
+        self.sync_queue();
+    }
+
+    /// 将文件或目录（递归）按目录页的顺序加入播放队列。
+    fn enqueue_paths(&mut self, paths: &[PathBuf], mode: EnqueueMode) {
+        let files: Vec<_> = paths.iter().flat_map(|path| audio_files(path)).collect();
+        if files.is_empty() {
+            return;
+        }
+        if mode == EnqueueMode::Replace {
+            self.clear_queue();
+        }
+        let start = self.queue.len();
+        self.enqueue(files);
+        if mode != EnqueueMode::Append {
+            self.play_queue_index(start);
+        }
+    }
+
+    /// 读取目录的内容并发送给 TUI，读取失败时发送空列表。
+    fn list_directory(&mut self, path: PathBuf) {
+        let entries = list_dir(&path).unwrap_or_else(|e| {
+            warn!("Failed to read {}: {e}", path.display());
+            Vec::new()
+        });
+        self.tui.event_handle(TuiEnent::DirectoryListing(
+            Cow::Borrowed(&path),
+            Cow::Owned(entries),
+        ));
+    }
+
+    /// 从媒体库中读取曲目并发送给 TUI，媒体库中没有的曲目会被跳过。
+    fn load_tracks(&mut self, paths: Vec<PathBuf>) {
+        let tracks = paths
+            .iter()
+            .filter_map(|path| self.library.get(path).cloned())
+            .collect();
+        self.tui.event_handle(TuiEnent::LibraryTracks(
+            Cow::Owned(paths),
+            Cow::Owned(tracks),
+        ));
+    }
+
+    /// 将播放列表目录中的播放列表发送给 TUI。
+    fn refresh_playlists(&mut self) {
+        let Some(dir) = &self.playlists_dir else {
+            return;
+        };
+        let playlists = list_playlists(dir).unwrap_or_else(|e| {
+            warn!("Failed to read playlists in {}: {e}", dir.display());
+            Vec::new()
+        });
+        self.tui
+            .event_handle(TuiEnent::Playlists(Cow::Owned(playlists)));
+    }
+
+    /// 读取播放列表并连同不存在的条目发送给 TUI，读取失败时发送空的播放列表。
+    fn load_playlist(&mut self, path: PathBuf) {
+        let playlist = Playlist::load(&path).unwrap_or_else(|e| {
+            error!("Failed to load playlist {}: {e}", path.display());
+            Playlist::new(path)
+        });
+        let missing = playlist.missing();
+        if !missing.is_empty() {
+            warn!(
+                "Playlist {}: {} missing entries",
+                playlist.path.display(),
+                missing.len()
+            );
+        }
+        self.tui.event_handle(TuiEnent::PlaylistLoaded(
+            Cow::Owned(playlist),
+            Cow::Owned(missing),
+        ));
+    }
+
+    /// 在播放列表目录中新建空的播放列表。
+    fn create_playlist(&mut self, name: &str) {
+        let Some(dir) = &self.playlists_dir else {
+            warn!("No playlist folder configured");
+            return;
+        };
+        if let Err(e) = create_playlist(dir, name) {
+            error!("Failed to create playlist {name:?}: {e}");
+        }
+        self.refresh_playlists();
+    }
+
+    /// 重命名播放列表。
+    fn rename_playlist(&mut self, path: &Path, name: &str) {
+        if let Err(e) = rename_playlist(path, name) {
+            error!("Failed to rename {} to {name:?}: {e}", path.display());
+        }
+        self.refresh_playlists();
+    }
+
+    /// 删除播放列表。
+    fn delete_playlist(&mut self, path: &Path) {
+        if let Err(e) = delete_playlist(path) {
+            error!("Failed to delete {}: {e}", path.display());
+        }
+        self.refresh_playlists();
+    }
+
+    /// 保存修改后的播放列表，并重新发送其内容。
+    fn save_playlist(&mut self, playlist: Playlist) {
+        if let Err(e) = playlist.save() {
+            error!("Failed to save {}: {e}", playlist.path.display());
+        }
+        self.load_playlist(playlist.path);
+    }
+
+    /// 将播放队列保存为播放列表，同名的播放列表会被覆盖。
+    fn save_queue_as_playlist(&mut self, name: &str) {
+        let Some(dir) = &self.playlists_dir else {
+            warn!("No playlist folder configured");
+            return;
+        };
+        let result = playlist_path(dir, name).and_then(|path| {
+            Playlist::from_tracks(&path, self.queue.tracks()).save()?;
+            Ok(path)
+        });
+        self.refresh_playlists();
+        match result {
+            // 覆盖了正在显示的播放列表时需要重新读取
+            Ok(path) => self.load_playlist(path),
+            Err(e) => error!("Failed to save queue as {name:?}: {e}"),
+        }
+    }
+
+    /// 在后台线程中搜索媒体库，并取消尚未完成的搜索。
+    ///
+    /// 索引与当前媒体库不一致时在后台线程中重新构建，搜索不会阻塞界面的刷新和按键处理。
+    fn search(&mut self, text: String) {
+        let generation = self.search_generation.fetch_add(1, Ordering::Relaxed) + 1;
+        self.search_text = text;
+        let query = match SearchQuery::parse(&self.search_text) {
+            Ok(query) if !query.is_empty() => query,
+            // 页面会自行显示解析错误，这里只清空结果
+            _ => {
+                let results = SearchResults {
+                    query: self.search_text.clone(),
+                    ..Default::default()
+                };
+                self.tui
+                    .event_handle(TuiEnent::SearchResults(Cow::Owned(results)));
+                return;
+            }
+        };
+        let index = self
+            .search_index
+            .clone()
+            .filter(|index| Arc::ptr_eq(index.library(), &self.library));
+        let library = self.library.clone();
+        let text = self.search_text.clone();
+        let current = self.search_generation.clone();
+        let done: Publisher<SearchDone> = self.bus.publisher();
+        tokio::task::spawn_blocking(move || {
+            let cancelled = || current.load(Ordering::Relaxed) != generation;
+            let index = index.unwrap_or_else(|| Arc::new(SearchIndex::new(library)));
+            if let Some(results) = index.search_with_cancel(&text, &query, SEARCH_LIMIT, cancelled)
+            {
+                done.publish(SearchDone {
+                    generation,
+                    index,
+                    results,
+                });
+            }
+        });
+    }
+
+    /// 媒体库变化后重新执行最近一次搜索。
+    fn refresh_search(&mut self) {
+        if !self.search_text.is_empty() {
+            self.search(self.search_text.clone());
+        }
+    }
+
+    /// 处理消息总线上的消息，把后台服务的状态变化合并到应用程序和 TUI 的状态中。
+    fn message_handler(&mut self, message: Message) {
+        match message {
+            // 播放引擎事件，将真实的播放状态同步到 TUI
+            Message::Player(event) => self.player_event_handler(event),
+            // 后台扫描完成或音乐目录发生变化，合并到媒体库
+            Message::Library(event) => self.library_event_handler(event),
+            // 后台搜索完成，只显示最新一次搜索的结果
+            Message::Search(done) => self.search_finished(done),
+            // 配置文件或主题文件发生变化，重新应用主题和按键绑定
+            Message::Config(event) => self.config_event_handler(event),
+            // 其他服务发送的命令，与按键产生的命令一样执行
+            Message::Command(command) => {
+                self.dispatch(command);
+                self.event.set_focus(self.tui.focus());
+            }
+        }
+    }
+
+    /// 返回向应用程序发布 `T` 类型消息的发布端，例如发送 `Command` 的远程控制服务。
+    pub fn publisher<T: 'static>(&self) -> Publisher<T>
+    where
+        Message: From<T>,
+    {
+        self.bus.publisher()
+    }
+
+    /// 处理后台搜索的结果，忽略已被新的搜索取代的结果。
+    fn search_finished(&mut self, done: SearchDone) {
+        if done.generation != self.search_generation.load(Ordering::Relaxed) {
+            return;
+        }
+        if Arc::ptr_eq(done.index.library(), &self.library) {
+            self.search_index = Some(done.index);
+        }
+        self.tui
+            .event_handle(TuiEnent::SearchResults(Cow::Owned(done.results)));
+    }
+
+    /// 把当前曲目的索引发送给 TUI，并更新播放引擎预加载的下一首曲目。
+    ///
+    /// 队列的其他变化在修改队列时单独发送。
+    fn sync_queue(&mut self) {
+        self.tui.event_handle(TuiEnent::Queue(QueueChange::Current(
+            self.queue.current_index(),
+        )));
+        self.preload();
+    }
+
+    /// 处理播放引擎事件，将其转换为 `TuiEnent` 广播给 TUI。
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - 播放引擎发出的事件。
+    fn player_event_handler(&mut self, event: PlayerEvent) {
+        match event {
+            PlayerEvent::State(state) => {
+                self.tui.event_handle(TuiEnent::PlaybackState(state));
+            }
+            PlayerEvent::Progress(progress, duration) => {
+                self.progress = progress;
+                self.duration = duration;
+                self.tui
+                    .event_handle(TuiEnent::PlaybackProgress(progress, duration));
+            }
+            PlayerEvent::TrackStarted(path) => {
+                // 媒体库中没有这首曲目时，用文件名作为曲目名称
+                let track = self
+                    .library
+                    .get(&path)
+                    .cloned()
+                    .unwrap_or_else(|| Track::new(path));
+                self.tui
+                    .event_handle(TuiEnent::Track(track.display_title().into()));
+                if let Some(artist) = track.artist {
+                    self.tui.event_handle(TuiEnent::Artist(artist.into()));
+                }
+                self.preload();
+            }
+            // 当前曲目播放完毕，按播放模式自动播放下一首
+            PlayerEvent::TrackFinished(_) => self.advance(),
+            PlayerEvent::TrackAdvanced(path) => self.advanced(path),
+            PlayerEvent::Error(message) => error!("Player error: {message}"),
+        }
+    }
+
+    /// 处理媒体库事件。
+    ///
+    /// 扫描结果合并到媒体库并通知 TUI 刷新。
+    fn library_event_handler(&mut self, event: LibraryEvent) {
+        match event {
+            LibraryEvent::Scanned(report) => {
+                let changed = !(report.added.is_empty()
+                    && report.updated.is_empty()
+                    && report.removed.is_empty());
+                // 页面不持有媒体库，缓存的搜索索引是唯一的另一个引用，先丢弃它，
+                // 使下面直接修改媒体库而不是复制全部曲目（只有后台搜索正在进行时才会复制）
+                if changed {
+                    self.search_index = None;
+                    info!(
+                        "Library updated: {} added, {} updated, {} removed",
+                        report.added.len(),
+                        report.updated.len(),
+                        report.removed.len()
+                    );
+                }
+                for error in report.apply_to(Arc::make_mut(&mut self.library)) {
+                    warn!("Scan error: {}: {}", error.path.display(), error.error);
+                }
+                // 标签发生变化时刷新队列中显示的曲目信息
+                let library = &self.library;
+                let updated: Vec<_> = self
+                    .queue
+                    .refresh(|path| library.get(path).cloned())
+                    .into_iter()
+                    .map(|index| (index, self.queue.tracks()[index].clone()))
+                    .collect();
+                if !updated.is_empty() {
+                    self.tui
+                        .event_handle(TuiEnent::Queue(QueueChange::Updated(Cow::Owned(updated))));
+                }
+                if changed {
+                    self.tui
+                        .event_handle(TuiEnent::LibraryUpdated(&self.library));
+                    self.refresh_search();
+                }
+            }
+            LibraryEvent::WatchLimitReached => {
+                warn!("File watch limit reached, falling back to periodic rescans");
+                self.enable_periodic_rescan();
+            }
+            LibraryEvent::Error(message) => error!("Library error: {message}"),
+        }
+    }
+}
+
+/// 记录配置错误，每个错误占一行。
+fn log_config_error(error: &ConfigError) {
+    for line in error.to_string().lines() {
+        error!("{line}");
     }
 }
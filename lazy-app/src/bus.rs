@@ -0,0 +1,113 @@
+//! 消息总线模块。
+//!
+//! 播放引擎、媒体库扫描器、目录监视器和配置文件监视器等后台服务在各自的线程中运行，
+//! 通过 `Publisher` 把状态变化发布到总线上；`App` 在主循环中逐条取出消息，更新自己的状态
+//! 并转换为 TUI 事件。后台服务只知道自己的事件类型，不需要知道 `ratatui` 和 TUI 的类型。
+//! 命令也可以通过总线发送给 `App`，例如远程控制服务收到的请求。
+
+use lazy_core::publisher::Publisher;
+use lazy_library::watcher::LibraryEvent;
+use lazy_player::engine::PlayerEvent;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+use crate::{app::SearchDone, command::Command, watcher::ConfigEvent};
+
+/// 总线上传递的消息。
+pub enum Message {
+    Player(PlayerEvent),   // 播放引擎事件
+    Library(LibraryEvent), // 后台扫描和目录监视的事件
+    Search(SearchDone),    // 后台搜索完成
+    Config(ConfigEvent),   // 配置文件监视的事件
+    Command(Command),      // 要执行的命令
+}
+
+impl From<PlayerEvent> for Message {
+    fn from(event: PlayerEvent) -> Self {
+        Self::Player(event)
+    }
+}
+
+impl From<LibraryEvent> for Message {
+    fn from(event: LibraryEvent) -> Self {
+        Self::Library(event)
+    }
+}
+
+impl From<SearchDone> for Message {
+    fn from(done: SearchDone) -> Self {
+        Self::Search(done)
+    }
+}
+
+impl From<ConfigEvent> for Message {
+    fn from(event: ConfigEvent) -> Self {
+        Self::Config(event)
+    }
+}
+
+impl From<Command> for Message {
+    fn from(command: Command) -> Self {
+        Self::Command(command)
+    }
+}
+
+/// 消息总线，所有发布端共享同一个通道，消息按发布的顺序被取出。
+pub struct Bus {
+    tx: UnboundedSender<Message>,   // 消息发送端，发布端都由它克隆而来
+    rx: UnboundedReceiver<Message>, // 消息接收端，由主循环读取
+}
+
+impl Default for Bus {
+    /// 创建一条空的消息总线。
+    fn default() -> Self {
+        let (tx, rx) = unbounded_channel();
+        Self { tx, rx }
+    }
+}
+
+impl Bus {
+    /// 返回发布 `T` 类型消息的发布端，消息被转换为 `Message` 后发送到总线上。
+    pub fn publisher<T: 'static>(&self) -> Publisher<T>
+    where
+        Message: From<T>,
+    {
+        Publisher::from(self.tx.clone()).map(Message::from)
+    }
+
+    /// 异步等待下一条消息。
+    ///
+    /// 总线自身持有发送端，因此永远不会因为发布端全部被丢弃而结束。
+    pub async fn recv(&mut self) -> Option<Message> {
+        self.rx.recv().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lazy_tui::types::Direction;
+
+    #[tokio::test]
+    async fn test_bus_delivers_in_order() {
+        let mut bus = Bus::default();
+        let config = bus.publisher::<ConfigEvent>();
+        let commands = bus.publisher::<Command>();
+
+        // 发布端可以在其他线程中使用
+        std::thread::spawn(move || {
+            assert!(config.publish(ConfigEvent::Changed));
+            assert!(commands.publish(Command::Seek(Direction::Right)));
+        })
+        .join()
+        .unwrap();
+
+        assert!(matches!(
+            bus.recv().await,
+            Some(Message::Config(ConfigEvent::Changed))
+        ));
+        assert!(matches!(
+            bus.recv().await,
+            Some(Message::Command(Command::Seek(Direction::Right)))
+        ));
+    }
+}
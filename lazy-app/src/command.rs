@@ -0,0 +1,253 @@
+//! `Command` 模块，定义了应用程序可以执行的所有操作。
+//!
+//! 按键（`KeyStatus`）和 TUI 请求（`TuiAction`）都会先被转换为 `Command`，
+//! 再由 `App::dispatch` 统一执行。
+
+use std::{path::PathBuf, time::Duration};
+
+use lazy_library::playlist::Playlist;
+use lazy_tui::{
+    navbar::NavbarItem,
+    types::{Direction, EnqueueMode, Mouse, TextInput, TuiAction},
+};
+
+use crate::event::KeyStatus;
+
+/// 应用程序命令
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// 退出程序
+    Quit,
+    /// 播放/暂停切换
+    TogglePlay,
+    /// 调整音量
+    Volume(i8),
+    /// 快退（`Left`）或快进（`Right`）一个跳转步长
+    Seek(Direction),
+    /// 跳转到当前曲目的指定位置
+    SeekTo(Duration),
+    /// 切换到上一首（`Left`）或下一首（`Right`）
+    SkipTrack(Direction),
+    /// 在当前视图中向上（`Up`）或向下（`Down`）移动选中项，向右（`Right`）展开或向左（`Left`）折叠
+    Picker(Direction),
+    /// 跳到选中项的上一级
+    Parent,
+    /// 播放当前视图中的选中项
+    PlaySelected,
+    /// 删除当前视图中的选中项
+    DeleteSelected,
+    /// 将当前视图中的选中项上移（`Up`）或下移（`Down`）
+    MoveSelected(Direction),
+    /// 清空当前视图的内容
+    Clear,
+    /// 将当前视图中的选中项添加到播放队列
+    AddSelected,
+    /// 用当前视图中的选中项替换播放队列
+    ReplaceWithSelected,
+    /// 切换当前视图的排序方式
+    CycleSort,
+    /// 在当前视图中新建一项
+    Create,
+    /// 重命名当前视图中的选中项
+    Rename,
+    /// 将播放队列保存为播放列表
+    SaveQueue,
+    /// 开始输入搜索文本
+    Find,
+    /// 切换当前视图显示的最低日志级别
+    CycleLevel,
+    /// 切换当前视图是否跟随最新的内容
+    ToggleFollow,
+    /// 正在输入文本时的按键
+    TextInput(TextInput),
+    /// 鼠标操作，交给位于鼠标位置的组件处理
+    Mouse(Mouse),
+    /// 播放队列中指定索引的曲目
+    PlayQueueIndex(usize),
+    /// 从队列中移除指定索引的曲目
+    RemoveQueueIndex(usize),
+    /// 将队列中的曲目从第一个索引移动到第二个索引
+    MoveQueueIndex(usize, usize),
+    /// 清空播放队列
+    ClearQueue,
+    /// 读取目录的内容并发送给 TUI
+    ListDirectory(PathBuf),
+    /// 从媒体库中读取指定路径的曲目并发送给 TUI
+    LoadTracks(Vec<PathBuf>),
+    /// 将文件或目录（递归）加入播放队列
+    Enqueue(Vec<PathBuf>, EnqueueMode),
+    /// 读取播放列表并发送给 TUI
+    LoadPlaylist(PathBuf),
+    /// 新建空的播放列表
+    CreatePlaylist(String),
+    /// 重命名播放列表
+    RenamePlaylist(PathBuf, String),
+    /// 删除播放列表
+    DeletePlaylist(PathBuf),
+    /// 保存修改后的播放列表
+    SavePlaylist(Playlist),
+    /// 将播放队列保存为播放列表
+    SaveQueueAsPlaylist(String),
+    /// 在后台搜索媒体库
+    Search(String),
+    /// 切换播放模式
+    SwitchMode,
+    /// 切换导航栏选中项
+    Navbar(Direction),
+    /// 切换到指定的页面
+    SelectView(NavbarItem),
+    /// 切换到下一个内置主题
+    CycleTheme,
+    /// 把焦点移到下一个（`Right`）或上一个（`Left`）组件
+    Focus(Direction),
+}
+
+impl Command {
+    /// 将按键状态转换为命令，`NoOp` 没有对应的命令。
+    pub fn from_key(key_status: KeyStatus) -> Option<Self> {
+        use KeyStatus::*;
+        let command = match key_status {
+            Quit => Command::Quit,                                      // q → 退出程序
+            TogglePlay => Command::TogglePlay,                          // p → 播放/暂停
+            VolumeIncrease => Command::Volume(10),                      // + → 增加音量
+            VolumeDecrease => Command::Volume(-10),                     // - → 减少音量
+            ProgressIncrease => Command::Seek(Direction::Right),        // l → 快进
+            ProgressDecrease => Command::Seek(Direction::Left),         // h → 快退
+            PickerNext => Command::Picker(Direction::Down),             // j → 选择下一个
+            PickerPrev => Command::Picker(Direction::Up),               // k → 选择上一个
+            SwitchMode => Command::SwitchMode,                          // m → 切换模式
+            NextTrack => Command::SkipTrack(Direction::Right),          // ] → 下一首
+            PrevTrack => Command::SkipTrack(Direction::Left),           // [ → 上一首
+            PlaySelected => Command::PlaySelected,                      // Enter → 播放选中
+            NavbarNext => Command::Navbar(Direction::Right),            // L → 下一个页面
+            NavbarPrve => Command::Navbar(Direction::Left),             // H → 上一个页面
+            DeleteSelected => Command::DeleteSelected,                  // d → 删除选中
+            MoveSelectedUp => Command::MoveSelected(Direction::Up),     // K → 上移选中
+            MoveSelectedDown => Command::MoveSelected(Direction::Down), // J → 下移选中
+            Clear => Command::Clear,                                    // C → 清空
+            PickerExpand => Command::Picker(Direction::Right),          // → → 展开
+            PickerCollapse => Command::Picker(Direction::Left),         // ← → 折叠
+            Parent => Command::Parent,                                  // Backspace → 上一级
+            AddSelected => Command::AddSelected,                        // a → 添加到队列
+            ReplaceQueue => Command::ReplaceWithSelected,               // R → 替换队列
+            CycleSort => Command::CycleSort,                            // s → 切换排序
+            Create => Command::Create,                                  // n → 新建
+            Rename => Command::Rename,                                  // r → 重命名
+            SaveQueue => Command::SaveQueue,                            // S → 保存队列
+            Find => Command::Find,                                      // / → 搜索
+            CycleLevel => Command::CycleLevel,                          // v → 切换日志级别
+            ToggleFollow => Command::ToggleFollow,                      // f → 跟随最新日志
+            CycleTheme => Command::CycleTheme,                          // T → 切换主题
+            FocusNext => Command::Focus(Direction::Right),              // Tab → 下一个组件
+            FocusPrev => Command::Focus(Direction::Left),               // Shift-Tab → 上一个组件
+            TextInput(input) => Command::TextInput(input),              // 输入文本
+            NoOp => return None,                                        // 无操作
+        };
+        Some(command)
+    }
+
+    /// 返回按键的数字前缀是否表示重复执行命令（例如 `5j` 向下移动 5 项）。
+    pub fn is_repeatable(&self) -> bool {
+        matches!(
+            self,
+            Command::Volume(_)
+                | Command::Seek(_)
+                | Command::SkipTrack(_)
+                | Command::Picker(_)
+                | Command::DeleteSelected
+                | Command::MoveSelected(_)
+                | Command::Navbar(_)
+        )
+    }
+}
+
+impl From<TuiAction> for Command {
+    fn from(action: TuiAction) -> Self {
+        match action {
+            TuiAction::PlayQueueIndex(index) => Command::PlayQueueIndex(index),
+            TuiAction::RemoveQueueIndex(index) => Command::RemoveQueueIndex(index),
+            TuiAction::MoveQueueIndex(from, to) => Command::MoveQueueIndex(from, to),
+            TuiAction::ClearQueue => Command::ClearQueue,
+            TuiAction::ListDirectory(path) => Command::ListDirectory(path),
+            TuiAction::LoadTracks(paths) => Command::LoadTracks(paths),
+            TuiAction::Enqueue(paths, mode) => Command::Enqueue(paths, mode),
+            TuiAction::LoadPlaylist(path) => Command::LoadPlaylist(path),
+            TuiAction::CreatePlaylist(name) => Command::CreatePlaylist(name),
+            TuiAction::RenamePlaylist(path, name) => Command::RenamePlaylist(path, name),
+            TuiAction::DeletePlaylist(path) => Command::DeletePlaylist(path),
+            TuiAction::SavePlaylist(playlist) => Command::SavePlaylist(playlist),
+            TuiAction::SaveQueueAsPlaylist(name) => Command::SaveQueueAsPlaylist(name),
+            TuiAction::Search(text) => Command::Search(text),
+            TuiAction::SelectView(item) => Command::SelectView(item),
+            TuiAction::SeekTo(position) => Command::SeekTo(position),
+            TuiAction::Seek(direction) => Command::Seek(direction),
+            TuiAction::Volume(delta) => Command::Volume(delta),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_from_key() {
+        assert_eq!(
+            Command::from_key(KeyStatus::ProgressIncrease),
+            Some(Command::Seek(Direction::Right))
+        );
+        assert_eq!(
+            Command::from_key(KeyStatus::PrevTrack),
+            Some(Command::SkipTrack(Direction::Left))
+        );
+        assert_eq!(
+            Command::from_key(KeyStatus::PickerNext),
+            Some(Command::Picker(Direction::Down))
+        );
+        assert_eq!(
+            Command::from_key(KeyStatus::TextInput(TextInput::Char('q'))),
+            Some(Command::TextInput(TextInput::Char('q')))
+        );
+        assert_eq!(
+            Command::from_key(KeyStatus::CycleTheme),
+            Some(Command::CycleTheme)
+        );
+        assert_eq!(
+            Command::from_key(KeyStatus::FocusPrev),
+            Some(Command::Focus(Direction::Left))
+        );
+        assert_eq!(Command::from_key(KeyStatus::NoOp), None);
+        assert!(Command::Picker(Direction::Down).is_repeatable());
+        assert!(!Command::Quit.is_repeatable());
+        assert!(!Command::CycleTheme.is_repeatable());
+    }
+
+    #[test]
+    fn test_command_from_tui_action() {
+        assert_eq!(
+            Command::from(TuiAction::PlayQueueIndex(3)),
+            Command::PlayQueueIndex(3)
+        );
+        assert_eq!(
+            Command::from(TuiAction::MoveQueueIndex(1, 0)),
+            Command::MoveQueueIndex(1, 0)
+        );
+        assert_eq!(Command::from(TuiAction::ClearQueue), Command::ClearQueue);
+        assert_eq!(
+            Command::from(TuiAction::SelectView(NavbarItem::Albums)),
+            Command::SelectView(NavbarItem::Albums)
+        );
+        assert_eq!(
+            Command::from(TuiAction::SeekTo(Duration::from_secs(3))),
+            Command::SeekTo(Duration::from_secs(3))
+        );
+        assert_eq!(
+            Command::from(TuiAction::Seek(Direction::Right)),
+            Command::Seek(Direction::Right)
+        );
+        assert_eq!(
+            Command::from(TuiAction::Enqueue(vec!["a".into()], EnqueueMode::Play)),
+            Command::Enqueue(vec!["a".into()], EnqueueMode::Play)
+        );
+    }
+}
@@ -0,0 +1,565 @@
+//! 配置文件模块。
+//!
+//! 配置文件位于 `$XDG_CONFIG_HOME/lazymusic/config.toml`（默认为 `~/.config/lazymusic/config.toml`）。
+//! `theme` 设置主题（见 `theme` 模块）。`color_depth` 设置终端的颜色深度（`"auto"`、`"truecolor"`、
+//! `"256"` 或 `"16"`），默认自动检测，颜色深度不足时主题的颜色会被映射为最接近的颜色。
+//! `seek_step` 设置快进/快退的步长（秒，默认 5 秒）。`music_dirs` 列出在后台扫描和监视的音乐库根目录，
+//! 必须是绝对路径，可以用 `~` 表示主目录；启动时在命令行中传入目录会代替这个设置。
+//! `[keys]` 表把按键映射到操作，未绑定的按键保持默认；
+//! `[keys.<页面>]` 中的绑定只在该页面生效，`[keys.input]` 中的绑定只在输入文本时生效，
+//! 它们会覆盖 `[keys]` 中的同名绑定：
+//!
+//! ```toml
+//! theme = "gruvbox-dark"
+//! color_depth = "256"
+//! seek_step = 10
+//! music_dirs = ["~/Music", "/mnt/media/music"]
+//!
+//! [keys]
+//! "ctrl-n" = "next_track"
+//! "space" = "toggle_play"
+//! "g g" = "picker_prev"
+//! "q" = "none"            # 取消默认的绑定
+//!
+//! [keys.logs]
+//! "c" = "clear"
+//!
+//! [keys.input]
+//! "ctrl-n" = "picker_next"
+//! ```
+//!
+//! 加载时会检查整个文件，错误带有行号和列号；文件有错误时使用默认配置。
+
+use std::{
+    collections::HashMap,
+    env, fmt, fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use lazy_core::color::ColorDepth;
+use toml_edit::{Array, Document, Item, TableLike};
+
+use crate::{
+    event::{KeyLayer, KeyStatus},
+    keys::{KeyChord, parse_sequence},
+    theme::BUNDLED,
+};
+
+/// 一个按键绑定。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyBinding {
+    /// 绑定所在的层
+    pub layer: KeyLayer,
+    /// 按键序列，通常只有一个按键
+    pub keys: Vec<KeyChord>,
+    /// 按下后执行的操作，`NoOp` 表示取消默认的绑定
+    pub action: KeyStatus,
+}
+
+/// 配置文件中的一个错误。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 行号，从 1 开始
+    pub line: usize,
+    /// 列号，从 1 开始
+    pub column: usize,
+    /// 错误描述
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// 在 `text` 中字节偏移 `offset` 处创建错误。
+    pub(crate) fn at(text: &str, offset: usize, message: impl Into<String>) -> Self {
+        let before = &text[..offset.min(text.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    /// 在 `span` 的起始位置创建错误，没有位置信息时指向文件开头。
+    pub(crate) fn spanned(
+        text: &str,
+        span: Option<Range<usize>>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self::at(text, span.map_or(0, |span| span.start), message)
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// 加载配置文件失败。
+#[derive(Debug)]
+pub enum ConfigError {
+    /// 无法读取配置文件
+    Read(PathBuf, io::Error),
+    /// 配置文件中有错误
+    Invalid(PathBuf, Vec<Diagnostic>),
+    /// 找不到配置的主题
+    UnknownTheme(String),
+}
+
+impl fmt::Display for ConfigError {
+    /// 每个错误占一行，格式为 `路径:行:列: 描述`。
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read(path, e) => write!(f, "failed to read {}: {e}", path.display()),
+            ConfigError::Invalid(path, diagnostics) => {
+                for (i, diagnostic) in diagnostics.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}:{diagnostic}", path.display())?;
+                }
+                Ok(())
+            }
+            ConfigError::UnknownTheme(name) => write!(
+                f,
+                "unknown theme {name:?}, bundled themes: {}",
+                BUNDLED
+                    .iter()
+                    .map(|(name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// 应用程序配置。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    /// 用户自定义的按键绑定，每一层的绑定按在文件中出现的顺序排列
+    pub keys: Vec<KeyBinding>,
+    /// 主题的名称或主题文件的路径，见 `theme` 模块
+    pub theme: Option<String>,
+    /// 终端的颜色深度，`None` 表示自动检测
+    pub color_depth: Option<ColorDepth>,
+    /// 快进/快退的步长，`None` 表示使用默认步长
+    pub seek_step: Option<Duration>,
+    /// 音乐库根目录，`None` 表示没有设置
+    pub music_dirs: Option<Vec<PathBuf>>,
+}
+
+impl Config {
+    /// 返回默认的配置文件路径，无法确定用户目录时返回 `None`。
+    pub fn default_path() -> Option<PathBuf> {
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_home.join("lazymusic").join("config.toml"))
+    }
+
+    /// 读取配置文件，文件不存在时返回默认配置。
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(ConfigError::Read(path.to_path_buf(), e)),
+        };
+        Self::parse(&text)
+            .map_err(|diagnostics| ConfigError::Invalid(path.to_path_buf(), diagnostics))
+    }
+
+    /// 解析配置文件的内容，返回所有发现的错误。
+    pub fn parse(text: &str) -> Result<Self, Vec<Diagnostic>> {
+        let document = parse_document(text)?;
+        let mut config = Self::default();
+        let mut diagnostics = Vec::new();
+        for (name, item) in document.iter() {
+            let (key, _) = document.get_key_value(name).expect("遍历得到的键一定存在");
+            match name {
+                "keys" => match item.as_table_like() {
+                    Some(table) => parse_keys(
+                        text,
+                        table,
+                        KeyLayer::Global,
+                        &mut config.keys,
+                        &mut diagnostics,
+                    ),
+                    None => diagnostics.push(Diagnostic::spanned(
+                        text,
+                        item.span(),
+                        "`keys` must be a table",
+                    )),
+                },
+                "theme" => match item.as_str() {
+                    Some(theme) => config.theme = Some(theme.to_string()),
+                    None => diagnostics.push(Diagnostic::spanned(
+                        text,
+                        item.span(),
+                        "`theme` must be a string",
+                    )),
+                },
+                "color_depth" => match parse_color_depth(item) {
+                    Some(depth) => config.color_depth = depth,
+                    None => diagnostics.push(Diagnostic::spanned(
+                        text,
+                        item.span(),
+                        "`color_depth` must be \"auto\", \"truecolor\", \"256\" or \"16\"",
+                    )),
+                },
+                "seek_step" => match parse_seek_step(item) {
+                    Some(step) => config.seek_step = Some(step),
+                    None => diagnostics.push(Diagnostic::spanned(
+                        text,
+                        item.span(),
+                        "`seek_step` must be a positive number of seconds",
+                    )),
+                },
+                "music_dirs" => match item.as_array() {
+                    Some(dirs) => {
+                        config.music_dirs = Some(parse_music_dirs(text, dirs, &mut diagnostics))
+                    }
+                    None => diagnostics.push(Diagnostic::spanned(
+                        text,
+                        item.span(),
+                        "`music_dirs` must be an array of paths",
+                    )),
+                },
+                _ => diagnostics.push(Diagnostic::spanned(
+                    text,
+                    key.span(),
+                    format!("unknown setting `{name}`"),
+                )),
+            }
+        }
+        if diagnostics.is_empty() {
+            Ok(config)
+        } else {
+            Err(diagnostics)
+        }
+    }
+}
+
+/// 解析 TOML 文档，语法错误带有位置。
+pub(crate) fn parse_document(text: &str) -> Result<Document<&str>, Vec<Diagnostic>> {
+    Document::parse(text)
+        .map_err(|e| vec![Diagnostic::spanned(text, e.span(), e.message().trim_end())])
+}
+
+/// 解析 `[keys]` 表或其中某一层的子表，并检查同一层中重复或冲突的绑定。
+fn parse_keys(
+    text: &str,
+    table: &dyn TableLike,
+    layer: KeyLayer,
+    bindings: &mut Vec<KeyBinding>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    // 已解析的按键序列及其在文件中的写法和行号
+    let mut seen: HashMap<Vec<KeyChord>, (String, usize)> = HashMap::new();
+    for (name, item) in table.iter() {
+        let (key, _) = table.get_key_value(name).expect("遍历得到的键一定存在");
+        let key_error = |message: String| Diagnostic::spanned(text, key.span(), message);
+        // `[keys]` 中的子表是页面层或输入层
+        if layer == KeyLayer::Global
+            && let Some(sub_table) = item.as_table_like()
+        {
+            match KeyLayer::from_name(name) {
+                Some(layer) => parse_keys(text, sub_table, layer, bindings, diagnostics),
+                None => diagnostics.push(key_error(format!("unknown key layer `{name}`"))),
+            }
+            continue;
+        }
+        let keys = match parse_sequence(name) {
+            Ok(keys) => keys,
+            Err(e) => {
+                diagnostics.push(key_error(format!("invalid key {name:?}: {e}")));
+                continue;
+            }
+        };
+        let action = match item.as_str() {
+            Some(action) => match KeyStatus::from_name(action) {
+                Some(action) => action,
+                None => {
+                    diagnostics.push(Diagnostic::spanned(
+                        text,
+                        item.span(),
+                        format!("unknown action {action:?} for key {name:?}"),
+                    ));
+                    continue;
+                }
+            },
+            None => {
+                diagnostics.push(Diagnostic::spanned(
+                    text,
+                    item.span(),
+                    format!("action for key {name:?} must be a string"),
+                ));
+                continue;
+            }
+        };
+
+        let line = key_error(String::new()).line;
+        if let Some((other, other_line)) = seen.get(&keys) {
+            diagnostics.push(key_error(format!(
+                "key {name:?} is already bound as {other:?} on line {other_line}"
+            )));
+            continue;
+        }
+        // 一个按键序列不能是另一个的前缀，否则较长的序列永远无法触发
+        if let Some((prefix, (other, other_line))) = seen
+            .iter()
+            .find(|(other, _)| keys.starts_with(other) || other.starts_with(&keys))
+        {
+            let (short, long) = if prefix.len() < keys.len() {
+                (other.as_str(), name)
+            } else {
+                (name, other.as_str())
+            };
+            diagnostics.push(key_error(format!(
+                "key {name:?} conflicts with {other:?} on line {other_line}: \
+                 {long:?} starts with {short:?}"
+            )));
+            continue;
+        }
+        seen.insert(keys.clone(), (name.to_string(), line));
+        bindings.push(KeyBinding {
+            layer,
+            keys,
+            action,
+        });
+    }
+}
+
+/// 解析 `color_depth`，`"auto"` 解析为 `None`；也可以写为整数 `256` 或 `16`。
+fn parse_color_depth(item: &Item) -> Option<Option<ColorDepth>> {
+    let text = match (item.as_str(), item.as_integer()) {
+        (Some(text), _) => text.to_string(),
+        (_, Some(n)) => n.to_string(),
+        _ => return None,
+    };
+    if text.eq_ignore_ascii_case("auto") {
+        return Some(None);
+    }
+    text.parse().ok().map(Some)
+}
+
+/// 解析 `seek_step` 的值，整数或小数秒数，必须大于零。
+fn parse_seek_step(item: &Item) -> Option<Duration> {
+    let seconds = match (item.as_integer(), item.as_float()) {
+        (Some(n), _) => n as f64,
+        (_, Some(n)) => n,
+        _ => return None,
+    };
+    Duration::try_from_secs_f64(seconds)
+        .ok()
+        .filter(|step| !step.is_zero())
+}
+
+/// 解析 `music_dirs` 中的每个目录，`~` 开头的路径相对于主目录。
+fn parse_music_dirs(text: &str, dirs: &Array, diagnostics: &mut Vec<Diagnostic>) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for dir in dirs.iter() {
+        let Some(path) = dir.as_str() else {
+            diagnostics.push(Diagnostic::spanned(
+                text,
+                dir.span(),
+                "`music_dirs` entries must be strings",
+            ));
+            continue;
+        };
+        let expanded = match path.strip_prefix('~') {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => env::var_os("HOME")
+                .map(|home| PathBuf::from(home).join(rest.trim_start_matches('/'))),
+            _ => Some(PathBuf::from(path)),
+        };
+        match expanded {
+            Some(expanded) if expanded.is_absolute() => paths.push(expanded),
+            _ => diagnostics.push(Diagnostic::spanned(
+                text,
+                dir.span(),
+                format!("music directory {path:?} must be an absolute path"),
+            )),
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lazy_tui::navbar::NavbarItem;
+
+    fn keys(text: &str) -> Vec<KeyChord> {
+        parse_sequence(text).unwrap()
+    }
+
+    fn errors(text: &str) -> Vec<String> {
+        Config::parse(text)
+            .unwrap_err()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    fn binding(layer: KeyLayer, text: &str, action: KeyStatus) -> KeyBinding {
+        KeyBinding {
+            layer,
+            keys: keys(text),
+            action,
+        }
+    }
+
+    #[test]
+    fn test_parse_key_bindings() {
+        let config = Config::parse(
+            r#"
+[keys]
+"ctrl-n" = "next_track"
+space = "toggle_play"
+"g g" = "picker_prev"
+q = "none"
+
+[keys.logs]
+c = "clear"
+"g g" = "toggle_follow"
+
+[keys.input]
+"ctrl-n" = "picker_next"
+"#,
+        )
+        .unwrap();
+        let logs = KeyLayer::View(NavbarItem::Logs);
+        assert_eq!(
+            config.keys,
+            vec![
+                binding(KeyLayer::Global, "ctrl-n", KeyStatus::NextTrack),
+                binding(KeyLayer::Global, "space", KeyStatus::TogglePlay),
+                binding(KeyLayer::Global, "g g", KeyStatus::PickerPrev),
+                binding(KeyLayer::Global, "q", KeyStatus::NoOp),
+                binding(logs, "c", KeyStatus::Clear),
+                binding(logs, "g g", KeyStatus::ToggleFollow),
+                binding(KeyLayer::Input, "ctrl-n", KeyStatus::PickerNext),
+            ]
+        );
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+        assert_eq!(
+            Config::parse("keys = { j = \"picker_next\", search = { j = \"find\" } }")
+                .unwrap()
+                .keys,
+            [
+                binding(KeyLayer::Global, "j", KeyStatus::PickerNext),
+                binding(KeyLayer::View(NavbarItem::Search), "j", KeyStatus::Find),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_config_errors_have_positions() {
+        assert_eq!(
+            errors(
+                r#"[keys]
+j = "jump"
+"hyper-x" = "quit"
+k = 1
+"C-n" = "quit"
+"ctrl-n" = "next_track"
+"g" = "quit"
+"g g" = "quit"
+"#
+            ),
+            [
+                "2:5: unknown action \"jump\" for key \"j\"",
+                "3:1: invalid key \"hyper-x\": unknown modifier \"hyper\" in \"hyper-x\"",
+                "4:5: action for key \"k\" must be a string",
+                "6:1: key \"ctrl-n\" is already bound as \"C-n\" on line 5",
+                "8:1: key \"g g\" conflicts with \"g\" on line 7: \"g g\" starts with \"g\"",
+            ]
+        );
+        assert_eq!(
+            errors("[colors]\nbg = 1\n"),
+            ["1:2: unknown setting `colors`"]
+        );
+        assert_eq!(errors("theme = 1"), ["1:9: `theme` must be a string"]);
+        assert_eq!(
+            errors("color_depth = 88"),
+            ["1:15: `color_depth` must be \"auto\", \"truecolor\", \"256\" or \"16\""]
+        );
+        assert_eq!(errors("keys = 1"), ["1:8: `keys` must be a table"]);
+        assert_eq!(
+            errors("seek_step = 0\nseek_step2 = 1"),
+            [
+                "1:13: `seek_step` must be a positive number of seconds",
+                "2:1: unknown setting `seek_step2`"
+            ]
+        );
+        assert_eq!(
+            errors("music_dirs = \"/m\"\nmusic_dirs2 = []"),
+            [
+                "1:14: `music_dirs` must be an array of paths",
+                "2:1: unknown setting `music_dirs2`"
+            ]
+        );
+        assert_eq!(
+            errors("music_dirs = [\"/m\", 1, \"music\"]"),
+            [
+                "1:21: `music_dirs` entries must be strings",
+                "1:24: music directory \"music\" must be an absolute path"
+            ]
+        );
+        assert_eq!(
+            errors("seek_step = \"5\""),
+            ["1:13: `seek_step` must be a positive number of seconds"]
+        );
+        assert_eq!(
+            errors("[keys.player]\nj = \"quit\"\n[keys.logs.input]\n"),
+            [
+                "1:7: unknown key layer `player`",
+                "3:12: invalid key \"input\": unknown key \"input\""
+            ]
+        );
+        let syntax = errors("[keys\n");
+        assert_eq!(syntax.len(), 1);
+        assert!(syntax[0].starts_with("1:6: "), "{syntax:?}");
+    }
+
+    #[test]
+    fn test_load_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        assert_eq!(Config::load(&path).unwrap(), Config::default());
+
+        fs::write(&path, "color_depth = 256\n").unwrap();
+        assert_eq!(
+            Config::load(&path).unwrap().color_depth,
+            Some(ColorDepth::Ansi256)
+        );
+        fs::write(&path, "color_depth = \"auto\"\n").unwrap();
+        assert_eq!(Config::load(&path).unwrap().color_depth, None);
+        fs::write(&path, "seek_step = 2.5\n[keys]\nx = \"quit\"\n").unwrap();
+        assert_eq!(
+            Config::load(&path).unwrap().seek_step,
+            Some(Duration::from_millis(2_500))
+        );
+
+        fs::write(&path, "music_dirs = [\"/srv/music\", \"~/Music\"]\n").unwrap();
+        let home = PathBuf::from(env::var_os("HOME").unwrap());
+        assert_eq!(
+            Config::load(&path).unwrap().music_dirs,
+            Some(vec![PathBuf::from("/srv/music"), home.join("Music")])
+        );
+
+        fs::write(&path, "[keys]\nx = \"nope\"\n").unwrap();
+        let error = Config::load(&path).unwrap_err().to_string();
+        assert_eq!(
+            error,
+            format!(
+                "{}:2:5: unknown action \"nope\" for key \"x\"",
+                path.display()
+            )
+        );
+    }
+}
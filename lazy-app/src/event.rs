@@ -1,38 +1,366 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
-use crossterm::event::{Event, EventStream, KeyCode, KeyEventKind};
+use crossterm::event::{
+    Event, EventStream, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
+use lazy_tui::{
+    navbar::NavbarItem,
+    types::{Focus, Mouse, MouseKind, TextInput},
+};
 use tokio_stream::StreamExt;
 
+use crate::keys::KeyChord;
+
 /// 定义按键状态枚举，用于表示用户操作
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum KeyStatus {
-    Quit,             // 退出程序
-    TogglePlay,       // 播放/暂停切换
-    VolumeIncrease,   // 增加音量
-    VolumeDecrease,   // 减少音量
-    ProgressIncrease, // 快进
-    ProgressDecrease, // 快退
-    PickerNext,       // 选择下一个项目
-    PickerPrev,       // 选择上一个项目
-    SwitchMode,       // 切换模式
-    NextTrack,        // 下一首
-    PrevTrack,        // 上一首
-    PlaySelected,     // 播放当前选中的项目
+    Quit,                 // 退出程序
+    TogglePlay,           // 播放/暂停切换
+    VolumeIncrease,       // 增加音量
+    VolumeDecrease,       // 减少音量
+    ProgressIncrease,     // 快进
+    ProgressDecrease,     // 快退
+    PickerNext,           // 选择下一个项目
+    PickerPrev,           // 选择上一个项目
+    SwitchMode,           // 切换模式
+    NextTrack,            // 下一首
+    PrevTrack,            // 上一首
+    PlaySelected,         // 播放当前选中的项目
+    DeleteSelected,       // 删除当前选中的项目
+    MoveSelectedUp,       // 上移当前选中的项目
+    MoveSelectedDown,     // 下移当前选中的项目
+    Clear,                // 清空当前页面（例如播放队列）
+    PickerExpand,         // 展开选中的项目
+    PickerCollapse,       // 折叠选中的项目或跳到上一级
+    Parent,               // 跳到选中项目的上一级
+    AddSelected,          // 将选中的项目添加到播放队列
+    ReplaceQueue,         // 用选中的项目替换播放队列
+    CycleSort,            // 切换当前页面的排序方式
+    Create,               // 在当前页面新建（例如新建播放列表）
+    Rename,               // 重命名选中的项目
+    SaveQueue,            // 将播放队列保存为播放列表
+    CycleLevel,           // 切换日志页显示的最低日志级别
+    ToggleFollow,         // 切换日志页是否跟随最新的日志
+    Find,                 // 开始输入搜索文本
+    CycleTheme,           // 切换到下一个内置主题
+    FocusNext,            // 把焦点移到下一个组件
+    FocusPrev,            // 把焦点移到上一个组件
+    TextInput(TextInput), // 页面正在输入文本时的按键
     NavbarNext,
     NavbarPrve,
-    NoOp, // 无操作
+    #[default]
+    NoOp, // 无操作（默认按键状态）
 }
 
-impl Default for KeyStatus {
-    fn default() -> Self {
-        Self::NoOp // 默认按键状态为无操作
+impl KeyStatus {
+    /// 可以在配置文件中绑定的操作及其名称，`none` 用于取消默认的绑定。
+    pub const ACTIONS: &'static [(&'static str, KeyStatus)] = &[
+        ("quit", KeyStatus::Quit),
+        ("toggle_play", KeyStatus::TogglePlay),
+        ("volume_increase", KeyStatus::VolumeIncrease),
+        ("volume_decrease", KeyStatus::VolumeDecrease),
+        ("progress_increase", KeyStatus::ProgressIncrease),
+        ("progress_decrease", KeyStatus::ProgressDecrease),
+        ("picker_next", KeyStatus::PickerNext),
+        ("picker_prev", KeyStatus::PickerPrev),
+        ("switch_mode", KeyStatus::SwitchMode),
+        ("next_track", KeyStatus::NextTrack),
+        ("prev_track", KeyStatus::PrevTrack),
+        ("play_selected", KeyStatus::PlaySelected),
+        ("delete_selected", KeyStatus::DeleteSelected),
+        ("move_selected_up", KeyStatus::MoveSelectedUp),
+        ("move_selected_down", KeyStatus::MoveSelectedDown),
+        ("clear", KeyStatus::Clear),
+        ("picker_expand", KeyStatus::PickerExpand),
+        ("picker_collapse", KeyStatus::PickerCollapse),
+        ("parent", KeyStatus::Parent),
+        ("add_selected", KeyStatus::AddSelected),
+        ("replace_queue", KeyStatus::ReplaceQueue),
+        ("cycle_sort", KeyStatus::CycleSort),
+        ("create", KeyStatus::Create),
+        ("rename", KeyStatus::Rename),
+        ("save_queue", KeyStatus::SaveQueue),
+        ("find", KeyStatus::Find),
+        ("cycle_level", KeyStatus::CycleLevel),
+        ("toggle_follow", KeyStatus::ToggleFollow),
+        ("cycle_theme", KeyStatus::CycleTheme),
+        ("focus_next", KeyStatus::FocusNext),
+        ("focus_prev", KeyStatus::FocusPrev),
+        ("navbar_next", KeyStatus::NavbarNext),
+        ("navbar_prev", KeyStatus::NavbarPrve),
+        ("none", KeyStatus::NoOp),
+    ];
+
+    /// 根据配置文件中的名称查找操作。
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ACTIONS
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|&(_, action)| action)
+    }
+}
+
+/// 等待按键序列下一个按键的最长时间，超时后已按下的前缀和数字前缀被丢弃
+pub const SEQUENCE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 数字前缀的最大重复次数
+pub const MAX_COUNT: usize = 999;
+
+/// 在同一位置两次按下左键被视为双击的最长间隔
+pub const DOUBLE_CLICK_TIMEOUT: Duration = Duration::from_millis(400);
+
+/// 一次完整的按键操作：按键状态和数字前缀给出的重复次数。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyAction {
+    pub status: KeyStatus, // 按键状态
+    pub count: usize,      // 重复次数，没有数字前缀时为 1
+}
+
+impl KeyAction {
+    /// 不重复的按键操作。
+    pub fn once(status: KeyStatus) -> Self {
+        Self { status, count: 1 }
+    }
+}
+
+/// 从终端读取到的一次输入：按键操作或鼠标操作。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputAction {
+    Key(KeyAction), // 按键操作
+    Mouse(Mouse),   // 鼠标操作
+    Resize,         // 终端大小改变，需要重新绘制
+}
+
+/// 按键绑定所在的层。
+///
+/// 查找按键时从上到下依次查找生效的层：输入文本时的输入层、当前页面的页面层和全局层，
+/// 上层的绑定会覆盖下层的同名绑定。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyLayer {
+    Global,           // 所有页面都可用的绑定
+    View(NavbarItem), // 只在指定页面可用的绑定
+    Input,            // 页面正在输入文本时可用的绑定
+}
+
+impl KeyLayer {
+    /// 配置文件中各页面的名称。
+    pub const VIEWS: &'static [(&'static str, NavbarItem)] = &[
+        ("queue", NavbarItem::Queue),
+        ("logs", NavbarItem::Logs),
+        ("directories", NavbarItem::Directories),
+        ("artists", NavbarItem::Artists),
+        ("album_artists", NavbarItem::AlbumArtists),
+        ("albums", NavbarItem::Albums),
+        ("playlists", NavbarItem::Playlists),
+        ("search", NavbarItem::Search),
+    ];
+
+    /// 根据配置文件中的名称查找页面层或输入层（`input`）。
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name == "input" {
+            return Some(KeyLayer::Input);
+        }
+        Self::VIEWS
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|&(_, view)| KeyLayer::View(view))
+    }
+}
+
+/// 在映射表中查找按键序列的结果。
+enum Lookup {
+    Bound(KeyStatus), // 序列绑定了操作
+    Prefix,           // 序列是某个更长的绑定序列的前缀
+    Unbound,          // 序列没有绑定
+}
+
+/// 按键映射表，把按键序列解析为 `KeyStatus`。
+///
+/// 按键带有修饰键（`ctrl-n`），可以由多个按键组成序列（`g g`），
+/// 序列前还可以输入数字前缀（`5 j`），作为操作的重复次数。
+/// 绑定分为多层（见 `KeyLayer`），生效的层由获得焦点的位置决定。
+#[derive(Debug, Default)]
+pub struct Keymap {
+    layers: HashMap<KeyLayer, HashMap<Vec<KeyChord>, KeyStatus>>, // 每一层的按键绑定
+    focus: Focus,                                                 // 获得焦点的位置
+    pending: Vec<KeyChord>,                                       // 已按下但尚未完成的按键序列
+    count: Option<usize>,                                         // 已输入的数字前缀
+    last_key: Option<Instant>,                                    // 上一次按键的时间
+}
+
+impl Keymap {
+    /// 用给定的绑定创建映射表。
+    pub fn new(bindings: impl IntoIterator<Item = (KeyLayer, Vec<KeyChord>, KeyStatus)>) -> Self {
+        let mut keymap = Self::default();
+        for (layer, keys, status) in bindings {
+            keymap.bind(layer, keys, status);
+        }
+        keymap
+    }
+
+    /// 在指定的层中绑定按键序列，`NoOp` 表示在这一层中取消绑定，同时屏蔽下层的绑定。
+    ///
+    /// 同一层中与新序列互为前缀的旧绑定会使其中一个永远无法触发，因此会被一并移除。
+    pub fn bind(&mut self, layer: KeyLayer, keys: Vec<KeyChord>, status: KeyStatus) {
+        let bindings = self.layers.entry(layer).or_default();
+        bindings.retain(|other, _| !other.starts_with(&keys) && !keys.starts_with(other));
+        bindings.insert(keys, status);
+        self.reset();
+    }
+
+    /// 返回指定层中按键序列绑定的按键状态。
+    pub fn get(&self, layer: KeyLayer, keys: &[KeyChord]) -> Option<KeyStatus> {
+        self.layers.get(&layer)?.get(keys).copied()
+    }
+
+    /// 设置获得焦点的位置，焦点改变时丢弃尚未完成的按键序列。
+    pub fn set_focus(&mut self, focus: Focus) {
+        if focus != self.focus {
+            self.focus = focus;
+            self.reset();
+        }
+    }
+
+    /// 返回是否有尚未完成的按键序列或数字前缀。
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty() || self.count.is_some()
+    }
+
+    /// 丢弃尚未完成的按键序列和数字前缀。
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.count = None;
+    }
+
+    /// 处理在 `now` 时刻按下的按键，按键序列完成时返回对应的操作。
+    pub fn feed(&mut self, key: KeyChord, now: Instant) -> Option<KeyAction> {
+        if self
+            .last_key
+            .is_some_and(|last| now.saturating_duration_since(last) > SEQUENCE_TIMEOUT)
+        {
+            self.reset();
+        }
+        self.last_key = Some(now);
+
+        // 序列开始前的数字是重复次数，`0` 只能跟在其他数字后面，已绑定的数字仍按绑定处理
+        if !self.focus.editing
+            && self.pending.is_empty()
+            && key.modifiers.is_empty()
+            && let KeyCode::Char(c) = key.code
+            && let Some(digit) = c.to_digit(10)
+            && (self.count.is_some()
+                || (digit != 0 && matches!(self.lookup(&[key]), Lookup::Unbound)))
+        {
+            let count = self.count.unwrap_or(0) * 10 + digit as usize;
+            self.count = Some(count.min(MAX_COUNT));
+            return None;
+        }
+
+        self.pending.push(key);
+        match self.lookup(&self.pending) {
+            Lookup::Bound(status) => {
+                let count = self.count.unwrap_or(1);
+                self.reset();
+                (status != KeyStatus::NoOp).then_some(KeyAction { status, count })
+            }
+            Lookup::Prefix => None,
+            // 序列无法继续时丢弃已按下的按键，把这个按键当作新序列的开始
+            Lookup::Unbound => {
+                let restart = self.pending.len() > 1;
+                self.reset();
+                if restart { self.feed(key, now) } else { None }
+            }
+        }
+    }
+
+    /// 从上到下在生效的层中查找按键序列，找到绑定或前缀即停止。
+    fn lookup(&self, keys: &[KeyChord]) -> Lookup {
+        let mut layers = Vec::with_capacity(3);
+        if self.focus.editing {
+            layers.push(KeyLayer::Input);
+        }
+        layers.extend([KeyLayer::View(self.focus.view), KeyLayer::Global]);
+
+        for layer in layers {
+            if let Some(bindings) = self.layers.get(&layer) {
+                if let Some(&status) = bindings.get(keys) {
+                    return Lookup::Bound(status);
+                }
+                if bindings
+                    .keys()
+                    .any(|other| other.len() > keys.len() && other.starts_with(keys))
+                {
+                    return Lookup::Prefix;
+                }
+            }
+            // 输入层没有绑定的字符键、退格、回车和 Esc 用于编辑文本，不会落到下层
+            if layer == KeyLayer::Input
+                && let [key] = keys
+                && let Some(input) = text_input(*key)
+            {
+                return Lookup::Bound(KeyStatus::TextInput(input));
+            }
+        }
+        Lookup::Unbound
+    }
+}
+
+/// 返回输入文本时按键对应的编辑操作。
+fn text_input(key: KeyChord) -> Option<TextInput> {
+    match key.code {
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some(TextInput::Char(c))
+        }
+        KeyCode::Backspace => Some(TextInput::Backspace),
+        KeyCode::Enter => Some(TextInput::Submit),
+        KeyCode::Esc => Some(TextInput::Cancel),
+        _ => None,
+    }
+}
+
+/// 鼠标点击的识别状态，将终端的鼠标事件转换为鼠标操作。
+#[derive(Debug, Default)]
+pub struct Clicks {
+    last: Option<(Instant, u16, u16)>, // 上一次按下左键的时间和位置，用于识别双击
+}
+
+impl Clicks {
+    /// 将终端的鼠标事件转换为鼠标操作，只关心左键和滚轮。
+    ///
+    /// 在同一位置两次按下左键的间隔不超过 `DOUBLE_CLICK_TIMEOUT` 时，第二次是双击。
+    pub fn feed(&mut self, event: &MouseEvent, now: Instant) -> Option<Mouse> {
+        let (column, row) = (event.column, event.row);
+        let kind = match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => match self.last.take() {
+                Some((time, c, r))
+                    if (c, r) == (column, row)
+                        && now.duration_since(time) <= DOUBLE_CLICK_TIMEOUT =>
+                {
+                    MouseKind::DoubleClick
+                }
+                _ => {
+                    self.last = Some((now, column, row));
+                    MouseKind::Click
+                }
+            },
+            MouseEventKind::Drag(MouseButton::Left) => MouseKind::Drag,
+            MouseEventKind::ScrollUp => MouseKind::ScrollUp,
+            MouseEventKind::ScrollDown => MouseKind::ScrollDown,
+            _ => return None,
+        };
+        Some(Mouse { kind, column, row })
     }
 }
 
-/// 事件处理器结构体，用于异步读取终端事件并映射为 KeyStatus
+/// 事件处理器结构体，用于异步读取终端事件并映射为 KeyStatus 或鼠标操作
 pub struct EventHandler {
-    events: EventStream,                 // 异步事件流，用于监听终端事件
-    keymap: HashMap<KeyCode, KeyStatus>, // 按键映射表，将 KeyCode 映射为 KeyStatus
+    events: EventStream, // 异步事件流，用于监听终端事件
+    keymap: Keymap,      // 按键映射表，将按键序列映射为 KeyStatus
+    clicks: Clicks,      // 鼠标点击的识别状态，用于识别双击
 }
 
 impl EventHandler {
@@ -41,62 +369,118 @@ impl EventHandler {
         Self {
             events: EventStream::new(),          // 初始化异步事件流
             keymap: Self::default_keybindings(), // 初始化默认按键映射
+            clicks: Clicks::default(),           // 初始化鼠标点击状态
         }
     }
 
-    /// 默认按键绑定
-    fn default_keybindings() -> HashMap<KeyCode, KeyStatus> {
+    /// 默认按键绑定，只对某个页面有意义的操作绑定在该页面的层中
+    fn default_keybindings() -> Keymap {
         use KeyCode::*;
+        use KeyLayer::*;
         use KeyStatus::*;
+        use lazy_tui::navbar::NavbarItem::{
+            AlbumArtists, Albums, Artists, Logs, Playlists, Search,
+        };
+
+        let bindings = [
+            (Global, Char('q'), Quit),             // q → 退出
+            (Global, Char('p'), TogglePlay),       // p → 播放/暂停
+            (Global, Char('+'), VolumeIncrease),   // + → 增加音量
+            (Global, Char('-'), VolumeDecrease),   // - → 减少音量
+            (Global, Char('l'), ProgressIncrease), // l → 快进
+            (Global, Char('h'), ProgressDecrease), // h → 快退
+            (Global, Char('j'), PickerNext),       // j → 选择下一个
+            (Global, Char('k'), PickerPrev),       // k → 选择上一个
+            (Global, Char('m'), SwitchMode),       // m → 切换模式
+            (Global, Char(']'), NextTrack),        // ] → 下一首
+            (Global, Char('['), PrevTrack),        // [ → 上一首
+            (Global, Char('L'), NavbarNext),
+            (Global, Char('H'), NavbarPrve),
+            (Global, Char('d'), DeleteSelected), // d → 删除选中项目
+            (Global, Char('K'), MoveSelectedUp), // K → 上移选中项目
+            (Global, Char('J'), MoveSelectedDown), // J → 下移选中项目
+            (Global, Char('C'), Clear),          // C → 清空
+            (Global, Right, PickerExpand),       // → → 展开
+            (Global, Left, PickerCollapse),      // ← → 折叠或跳到上一级
+            (Global, Backspace, Parent),         // Backspace → 跳到上一级
+            (Global, Char('a'), AddSelected),    // a → 添加到播放队列
+            (Global, Char('R'), ReplaceQueue),   // R → 替换播放队列
+            (Global, Down, PickerNext),          // ↓ → 选择下一个（输入文本时也可用）
+            (Global, Up, PickerPrev),            // ↑ → 选择上一个（输入文本时也可用）
+            (Global, Enter, PlaySelected),       // Enter → 播放选中项目
+            (Global, Char('T'), CycleTheme),     // T → 切换主题
+            (Global, Tab, FocusNext),            // Tab → 焦点移到下一个组件
+            (Global, BackTab, FocusPrev),        // Shift-Tab → 焦点移到上一个组件
+            (View(Artists), Char('s'), CycleSort), // s → 切换排序方式
+            (View(AlbumArtists), Char('s'), CycleSort),
+            (View(Albums), Char('s'), CycleSort),
+            (View(Playlists), Char('n'), Create),    // n → 新建
+            (View(Playlists), Char('r'), Rename),    // r → 重命名
+            (View(Playlists), Char('S'), SaveQueue), // S → 将播放队列保存为播放列表
+            (View(Search), Char('/'), Find),         // / → 搜索
+            (View(Logs), Char('v'), CycleLevel),     // v → 切换日志级别
+            (View(Logs), Char('f'), ToggleFollow),   // f → 切换跟随最新日志
+        ];
+        Keymap::new(
+            bindings
+                .into_iter()
+                .map(|(layer, code, status)| (layer, vec![KeyChord::plain(code)], status)),
+        )
+    }
 
-        HashMap::from([
-            (Char('q'), Quit),             // q → 退出
-            (Char('p'), TogglePlay),       // p → 播放/暂停
-            (Char('+'), VolumeIncrease),   // + → 增加音量
-            (Char('-'), VolumeDecrease),   // - → 减少音量
-            (Char('l'), ProgressIncrease), // l → 快进
-            (Char('h'), ProgressDecrease), // h → 快退
-            (Char('j'), PickerNext),       // j → 选择下一个
-            (Char('k'), PickerPrev),       // k → 选择上一个
-            (Char('m'), SwitchMode),       // m → 切换模式
-            (Char(']'), NextTrack),        // ] → 下一首
-            (Char('['), PrevTrack),        // [ → 上一首
-            (Char('L'), NavbarNext),
-            (Char('H'), NavbarPrve),
-            (Enter, PlaySelected), // Enter → 播放选中项目
-        ])
-    }
-
-    /// 异步读取下一个按键事件，并返回对应的 KeyStatus
-    pub async fn next_key_status(&mut self) -> Option<KeyStatus> {
+    /// 异步读取下一个终端事件，并返回对应的按键操作或鼠标操作
+    pub async fn next_input(&mut self) -> Option<InputAction> {
         self.events.next().await.and_then(|maybe_result| {
             maybe_result
-                // 如果事件流出错，打印错误信息
-                .map_err(|e| eprintln!("Event stream error: {:?}", e))
+                // 如果事件流出错，记录错误日志
+                .map_err(|e| log::error!("Event stream error: {e:?}"))
                 .ok()
-                .map(|event| self.handle_event(&event)) // 将 Event 转换为 KeyStatus
+                .map(|event| self.handle_event(&event)) // 将 Event 转换为 InputAction
         })
     }
 
-    /// 处理单个事件，将 Event 映射为 KeyStatus
-    pub fn handle_event(&mut self, event: &Event) -> KeyStatus {
-        if let Event::Key(key) = event {
-            // 如果事件是按键事件
-            if key.kind == KeyEventKind::Press {
-                // 只处理按下事件（忽略释放/重复）
-                return self
-                    .keymap
-                    .get(&key.code) // 查找按键映射表
-                    .copied() // 将 &KeyStatus 转为 KeyStatus
-                    .unwrap_or(KeyStatus::NoOp); // 未绑定按键返回 NoOp
+    /// 处理单个事件，将 Event 映射为 InputAction；按键序列尚未完成时返回 NoOp
+    pub fn handle_event(&mut self, event: &Event) -> InputAction {
+        let now = Instant::now();
+        match event {
+            // 只处理按下事件（忽略释放/重复）
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                if let Some(action) = self.keymap.feed(KeyChord::from(*key), now) {
+                    return InputAction::Key(action); // 查找按键映射表
+                }
             }
+            Event::Mouse(mouse) => {
+                if let Some(mouse) = self.clicks.feed(mouse, now) {
+                    return InputAction::Mouse(mouse);
+                }
+            }
+            Event::Resize(..) => return InputAction::Resize,
+            _ => (),
         }
-        KeyStatus::NoOp // 非按键事件返回 NoOp
+        // 未绑定或未完成的按键以及其他事件返回 NoOp
+        InputAction::Key(KeyAction::once(KeyStatus::NoOp))
+    }
+
+    /// 设置获得焦点的位置，决定生效的按键映射层。
+    pub fn set_focus(&mut self, focus: Focus) {
+        self.keymap.set_focus(focus);
     }
 
-    /// 添加或扩展自定义按键绑定
-    pub fn add_keybindings(&mut self, key_bindings: HashMap<KeyCode, KeyStatus>) {
-        self.keymap.extend(key_bindings); // 合并新的按键映射
+    /// 恢复默认的按键绑定，获得焦点的位置保持不变
+    pub fn reset_keybindings(&mut self) {
+        let focus = self.keymap.focus;
+        self.keymap = Self::default_keybindings();
+        self.keymap.set_focus(focus);
+    }
+
+    /// 添加或覆盖按键绑定，`NoOp` 取消对应的绑定
+    pub fn add_keybindings(
+        &mut self,
+        key_bindings: impl IntoIterator<Item = (KeyLayer, Vec<KeyChord>, KeyStatus)>,
+    ) {
+        for (layer, keys, status) in key_bindings {
+            self.keymap.bind(layer, keys, status); // 合并新的按键映射
+        }
     }
 }
 
@@ -106,3 +490,242 @@ impl Default for EventHandler {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(text: &str) -> Vec<KeyChord> {
+        crate::keys::parse_sequence(text).unwrap()
+    }
+
+    /// 从 `start` 开始每隔 100 毫秒按下一个按键，返回完成的操作。
+    fn press(keymap: &mut Keymap, text: &str, start: Instant) -> Vec<KeyAction> {
+        keys(text)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, key)| keymap.feed(key, start + Duration::from_millis(100 * i as u64)))
+            .collect()
+    }
+
+    fn keymap() -> Keymap {
+        Keymap::new(
+            [
+                ("j", KeyStatus::PickerNext),
+                ("ctrl-n", KeyStatus::NextTrack),
+                ("G", KeyStatus::NavbarNext),
+                ("g g", KeyStatus::NavbarPrve),
+                ("d d", KeyStatus::DeleteSelected),
+                ("]", KeyStatus::NextTrack),
+            ]
+            .map(|(text, status)| (KeyLayer::Global, keys(text), status)),
+        )
+    }
+
+    #[test]
+    fn test_keymap_chords_and_sequences() {
+        let now = Instant::now();
+        let mut keymap = keymap();
+        assert_eq!(
+            press(&mut keymap, "ctrl-n n shift-g", now),
+            [
+                KeyAction::once(KeyStatus::NextTrack),
+                KeyAction::once(KeyStatus::NavbarNext)
+            ]
+        );
+        assert_eq!(
+            press(&mut keymap, "g g d d", now),
+            [
+                KeyAction::once(KeyStatus::NavbarPrve),
+                KeyAction::once(KeyStatus::DeleteSelected)
+            ]
+        );
+        // 无法继续的序列被丢弃，最后的按键重新开始
+        assert_eq!(
+            press(&mut keymap, "g d j", now),
+            [KeyAction::once(KeyStatus::PickerNext)]
+        );
+        assert!(!keymap.is_pending());
+
+        // 超时后已按下的前缀被丢弃
+        assert_eq!(keymap.feed(keys("g")[0], now), None);
+        assert!(keymap.is_pending());
+        let later = now + SEQUENCE_TIMEOUT + Duration::from_millis(1);
+        assert_eq!(keymap.feed(keys("g")[0], later), None);
+        assert!(keymap.is_pending());
+        assert_eq!(
+            keymap.feed(keys("g")[0], later),
+            Some(KeyAction::once(KeyStatus::NavbarPrve))
+        );
+    }
+
+    #[test]
+    fn test_keymap_count_prefix() {
+        let now = Instant::now();
+        let mut keymap = keymap();
+        assert_eq!(
+            press(&mut keymap, "5 j 3 ] 1 0 d d", now),
+            [
+                KeyAction {
+                    status: KeyStatus::PickerNext,
+                    count: 5
+                },
+                KeyAction {
+                    status: KeyStatus::NextTrack,
+                    count: 3
+                },
+                KeyAction {
+                    status: KeyStatus::DeleteSelected,
+                    count: 10
+                },
+            ]
+        );
+        assert_eq!(
+            press(&mut keymap, "0 j 9 9 9 9 j", now),
+            [
+                KeyAction::once(KeyStatus::PickerNext),
+                KeyAction {
+                    status: KeyStatus::PickerNext,
+                    count: MAX_COUNT
+                }
+            ]
+        );
+        // 未绑定的按键丢弃数字前缀
+        assert_eq!(
+            press(&mut keymap, "4 esc j", now),
+            [KeyAction::once(KeyStatus::PickerNext)]
+        );
+
+        // 绑定了的数字不再作为数字前缀
+        keymap.bind(KeyLayer::Global, keys("1"), KeyStatus::Quit);
+        assert_eq!(
+            press(&mut keymap, "1 2 j", now),
+            [
+                KeyAction::once(KeyStatus::Quit),
+                KeyAction {
+                    status: KeyStatus::PickerNext,
+                    count: 2
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keymap_bind_replaces_conflicts() {
+        let mut keymap = keymap();
+        keymap.bind(KeyLayer::Global, keys("d"), KeyStatus::Clear);
+        assert_eq!(keymap.get(KeyLayer::Global, &keys("d d")), None);
+        keymap.bind(KeyLayer::Global, keys("g"), KeyStatus::NoOp);
+        assert_eq!(keymap.get(KeyLayer::Global, &keys("g g")), None);
+        keymap.bind(KeyLayer::Global, keys("j j"), KeyStatus::Quit);
+        assert_eq!(keymap.get(KeyLayer::Global, &keys("j")), None);
+        assert_eq!(
+            press(&mut keymap, "d g j j", Instant::now()),
+            [
+                KeyAction::once(KeyStatus::Clear),
+                KeyAction::once(KeyStatus::Quit)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keymap_layers_follow_focus() {
+        let now = Instant::now();
+        let mut keymap = keymap();
+        let logs = KeyLayer::View(NavbarItem::Logs);
+        keymap.bind(logs, keys("j"), KeyStatus::ToggleFollow);
+        keymap.bind(logs, keys("G"), KeyStatus::NoOp);
+        keymap.bind(logs, keys("g"), KeyStatus::CycleLevel);
+        keymap.bind(KeyLayer::Input, keys("ctrl-n"), KeyStatus::PickerNext);
+
+        // 其他页面只使用全局层
+        assert_eq!(
+            press(&mut keymap, "j G", now),
+            [
+                KeyAction::once(KeyStatus::PickerNext),
+                KeyAction::once(KeyStatus::NavbarNext)
+            ]
+        );
+        // 默认用 Tab 和 Shift-Tab 切换焦点，终端报告的 BackTab 等同于 Shift-Tab
+        let mut defaults = EventHandler::default_keybindings();
+        assert_eq!(
+            press(&mut defaults, "tab shift-tab", now),
+            [
+                KeyAction::once(KeyStatus::FocusNext),
+                KeyAction::once(KeyStatus::FocusPrev)
+            ]
+        );
+
+        // 页面层覆盖全局层，`none` 屏蔽全局的绑定，没有覆盖的按键仍使用全局层
+        keymap.set_focus(Focus {
+            view: NavbarItem::Logs,
+            editing: false,
+        });
+        assert_eq!(
+            press(&mut keymap, "j G g ]", now),
+            [
+                KeyAction::once(KeyStatus::ToggleFollow),
+                KeyAction::once(KeyStatus::CycleLevel),
+                KeyAction::once(KeyStatus::NextTrack)
+            ]
+        );
+
+        // 输入文本时字符键作为文本，其余按键依次查找输入层、页面层和全局层
+        keymap.set_focus(Focus {
+            view: NavbarItem::Search,
+            editing: true,
+        });
+        assert_eq!(
+            press(&mut keymap, "q 5 ctrl-n esc", now),
+            [
+                KeyAction::once(KeyStatus::TextInput(TextInput::Char('q'))),
+                KeyAction::once(KeyStatus::TextInput(TextInput::Char('5'))),
+                KeyAction::once(KeyStatus::PickerNext),
+                KeyAction::once(KeyStatus::TextInput(TextInput::Cancel))
+            ]
+        );
+        keymap.bind(KeyLayer::Global, keys("down"), KeyStatus::PickerNext);
+        assert_eq!(
+            press(&mut keymap, "down", now),
+            [KeyAction::once(KeyStatus::PickerNext)]
+        );
+    }
+
+    #[test]
+    fn test_clicks() {
+        let mut clicks = Clicks::default();
+        let start = Instant::now();
+        let event = |kind, column| MouseEvent {
+            kind,
+            column,
+            row: 2,
+            modifiers: KeyModifiers::NONE,
+        };
+        let left = MouseEventKind::Down(MouseButton::Left);
+        let mut kind = |kind, column, ms| {
+            clicks
+                .feed(&event(kind, column), start + Duration::from_millis(ms))
+                .map(|mouse| mouse.kind)
+        };
+
+        assert_eq!(kind(left, 5, 0), Some(MouseKind::Click));
+        assert_eq!(kind(left, 5, 200), Some(MouseKind::DoubleClick));
+        // 双击之后的点击重新开始计算
+        assert_eq!(kind(left, 5, 300), Some(MouseKind::Click));
+        assert_eq!(kind(left, 6, 400), Some(MouseKind::Click), "位置不同");
+        assert_eq!(kind(left, 6, 1000), Some(MouseKind::Click), "间隔太长");
+        assert_eq!(
+            kind(MouseEventKind::Drag(MouseButton::Left), 7, 1100),
+            Some(MouseKind::Drag)
+        );
+        assert_eq!(
+            kind(MouseEventKind::ScrollDown, 7, 1200),
+            Some(MouseKind::ScrollDown)
+        );
+        assert_eq!(kind(MouseEventKind::Up(MouseButton::Left), 7, 1300), None);
+        assert_eq!(
+            kind(MouseEventKind::Down(MouseButton::Right), 7, 1400),
+            None
+        );
+    }
+}
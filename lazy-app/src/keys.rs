@@ -0,0 +1,204 @@
+//! 按键描述模块。
+//!
+//! 配置文件用字符串描述按键：`"j"`、`"ctrl-n"`、`"alt-shift-left"`、`"space"`，
+//! 以空格分隔的多个按键表示按键序列，例如 `"g g"`。`KeyChord` 是一次按键（键和修饰键），
+//! 同一个按键的不同写法（`"shift-a"` 和 `"A"`、`"C-n"` 和 `"ctrl-n"`）解析后相等。
+
+use std::{fmt, str::FromStr};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// 一次按键：键和同时按下的修饰键。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    /// 键
+    pub code: KeyCode,
+    /// 修饰键（Ctrl、Alt、Shift）
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    /// 创建一个按键，字符键的 Shift 会被合并到字符中（`shift-a` 等同于 `A`）。
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            // 终端把 Shift-Tab 报告为 BackTab
+            KeyCode::BackTab => {
+                modifiers.insert(KeyModifiers::SHIFT);
+                KeyCode::Tab
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+
+    /// 没有修饰键的按键。
+    pub fn plain(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(event: KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = String;
+
+    /// 解析一个按键，修饰键和键以 `-` 分隔，不区分修饰键和键名的大小写。
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        // 最后一段是键，`-` 本身也可以作为键（例如 `ctrl--`）
+        let (prefix, key) = match text.strip_suffix("--") {
+            Some(prefix) => (prefix, "-"),
+            None => match text.rsplit_once('-') {
+                Some((prefix, key)) if !key.is_empty() => (prefix, key),
+                _ if text == "-" => ("", "-"),
+                Some(_) => return Err(format!("missing key in {text:?}")),
+                None => ("", text),
+            },
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('-').filter(|m| !m.is_empty()) {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" | "c" => KeyModifiers::CONTROL,
+                "alt" | "meta" | "m" | "a" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier {modifier:?} in {text:?}")),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" | "bs" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "minus" => KeyCode::Char('-'),
+                "plus" => KeyCode::Char('+'),
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=24) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key {key:?}")),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyChord {
+    /// 按配置文件中的写法输出，例如 `ctrl-n`、`space`、`G`。
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "ctrl-"),
+            (KeyModifiers::ALT, "alt-"),
+            (KeyModifiers::SHIFT, "shift-"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            KeyCode::PageUp => f.write_str("pageup"),
+            KeyCode::PageDown => f.write_str("pagedown"),
+            code => f.write_str(&format!("{code:?}").to_ascii_lowercase()),
+        }
+    }
+}
+
+/// 解析以空格分隔的按键序列，例如 `"g g"`。
+pub fn parse_sequence(text: &str) -> Result<Vec<KeyChord>, String> {
+    let keys = text
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<KeyChord>, _>>()?;
+    if keys.is_empty() {
+        return Err("empty key".to_string());
+    }
+    Ok(keys)
+}
+
+/// 将按键序列格式化为配置文件中的写法。
+pub fn format_sequence(keys: &[KeyChord]) -> String {
+    keys.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(text: &str) -> KeyChord {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_key_chord() {
+        assert_eq!(chord("j"), KeyChord::plain(KeyCode::Char('j')));
+        assert_eq!(
+            chord("ctrl-n"),
+            KeyChord::new(KeyCode::Char('n'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(chord("C-n"), chord("ctrl-n"));
+        assert_eq!(chord("shift-a"), chord("A"));
+        assert_eq!(chord("space"), KeyChord::plain(KeyCode::Char(' ')));
+        assert_eq!(chord("-"), KeyChord::plain(KeyCode::Char('-')));
+        assert_eq!(
+            chord("ctrl--"),
+            KeyChord::new(KeyCode::Char('-'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(chord("shift-tab"), chord("backtab"));
+        assert_eq!(
+            chord("alt-shift-Left"),
+            KeyChord::new(KeyCode::Left, KeyModifiers::ALT | KeyModifiers::SHIFT)
+        );
+        assert_eq!(chord("F5"), KeyChord::plain(KeyCode::F(5)));
+        for invalid in ["hyper-a", "f25", "nope", "ctrl-", ""] {
+            assert!(invalid.parse::<KeyChord>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_key_sequence_round_trip() {
+        let keys = parse_sequence("g  g").unwrap();
+        assert_eq!(keys, [chord("g"), chord("g")]);
+        for text in [
+            "g g",
+            "ctrl-n",
+            "space",
+            "G",
+            "alt-shift-left",
+            "f5",
+            "pageup",
+        ] {
+            assert_eq!(format_sequence(&parse_sequence(text).unwrap()), text);
+        }
+        assert!(parse_sequence("  ").is_err());
+    }
+}
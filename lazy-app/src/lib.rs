@@ -1,2 +1,8 @@
 pub mod app;
-mod event;
+pub mod bus;
+pub mod command;
+pub mod config;
+pub mod event;
+pub mod keys;
+pub mod theme;
+pub mod watcher;
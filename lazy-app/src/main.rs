@@ -1,8 +1,51 @@
-use lazy_app::app::App;
-use std::error::Error;
+use lazy_app::{app::App, config::Config};
+use lazy_core::logger::{LevelFilter, LogBuffer, Logger};
+use std::{error::Error, path::PathBuf};
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
+    // 日志保存在内存中供日志页显示；LAZYMUSIC_LOG 设置日志级别，LAZYMUSIC_LOG_FILE 同时写入文件
+    let log_buffer = LogBuffer::default();
+    let level = std::env::var("LAZYMUSIC_LOG")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(LevelFilter::Info);
+    let mut logger = Logger::new(log_buffer.clone()).with_level(level);
+    let log_file_error = std::env::var_os("LAZYMUSIC_LOG_FILE")
+        .map(PathBuf::from)
+        .and_then(|path| {
+            logger
+                .set_file(&path)
+                .err()
+                .map(|e| format!("Failed to open log file {}: {e}", path.display()))
+        });
+    logger.install()?;
+    if let Some(message) = log_file_error {
+        log::error!("{message}");
+    }
+
     let mut app = App::default();
+    app.set_log_buffer(log_buffer);
+    // 读取配置文件（有错误时记录每个错误并使用默认配置），之后配置文件变化时自动重新读取
+    if let Some(path) = Config::default_path() {
+        app.load_config(path);
+    }
+    // 命令行参数中的目录代替配置文件中的 `music_dirs` 作为音乐库根目录扫描，文件加入播放队列，按 p 开始播放
+    let (roots, files): (Vec<PathBuf>, Vec<PathBuf>) = std::env::args_os()
+        .skip(1)
+        .map(PathBuf::from)
+        .partition(|path| path.is_dir());
+    app.enqueue(files);
+    // 先从数据库加载上次扫描的媒体库，再在后台增量扫描
+    if let Some(db_path) = lazy_library::db::default_path()
+        && let Err(e) = app.load_library(db_path)
+    {
+        log::error!("Failed to load library database: {e}");
+    }
+    if let Some(dir) = lazy_library::playlist::default_dir() {
+        app.set_playlists_dir(dir);
+    }
+    app.start_library(roots);
     app.run().await?;
     Ok(())
 }
@@ -0,0 +1,279 @@
+//! 主题文件模块。
+//!
+//! 主题文件是 TOML 格式，`[colors]` 表按名称（见 `TuiTheme::SLOTS`）设置颜色。颜色可以是
+//! `"#rrggbb"`、16 色的名称（`"light-blue"`、`"reset"`）或 256 色的索引（`244`）：
+//!
+//! ```toml
+//! name = "My Night"
+//! base = "tokyonight-night" # 未设置的颜色取自这个内置主题，默认为 tokyonight-moon
+//!
+//! [colors]
+//! bg = "#16161e"
+//! comment = 244
+//! ```
+//!
+//! 配置文件中的 `theme` 可以是内置主题的名称、配置目录下 `themes/` 中主题文件的名称（不含扩展名）
+//! 或主题文件的路径，相对路径相对于配置文件所在的目录。
+
+use std::{fs, io, path::Path, str::FromStr};
+
+use lazy_core::theme::TuiTheme;
+use ratatui::style::Color;
+use toml_edit::Item;
+
+use crate::config::{ConfigError, Diagnostic, parse_document};
+
+/// 默认主题的名称
+pub const DEFAULT_THEME: &str = "tokyonight-moon";
+
+/// 内置主题的名称和主题文件的内容
+pub const BUNDLED: &[(&str, &str)] = &[
+    (
+        "tokyonight-moon",
+        include_str!("../themes/tokyonight-moon.toml"),
+    ),
+    (
+        "tokyonight-night",
+        include_str!("../themes/tokyonight-night.toml"),
+    ),
+    (
+        "tokyonight-storm",
+        include_str!("../themes/tokyonight-storm.toml"),
+    ),
+    (
+        "tokyonight-day",
+        include_str!("../themes/tokyonight-day.toml"),
+    ),
+    ("gruvbox-dark", include_str!("../themes/gruvbox-dark.toml")),
+    (
+        "catppuccin-mocha",
+        include_str!("../themes/catppuccin-mocha.toml"),
+    ),
+    ("ansi-16", include_str!("../themes/ansi-16.toml")),
+];
+
+/// 一个主题。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// 主题的名称
+    pub name: String,
+    /// 主题的调色板
+    pub colors: TuiTheme,
+}
+
+impl Theme {
+    /// 返回指定名称的内置主题。
+    pub fn bundled(name: &str) -> Option<Self> {
+        let (_, text) = BUNDLED.iter().find(|(n, _)| *n == name)?;
+        Some(Self::parse(text).expect("内置主题一定有效"))
+    }
+
+    /// 按配置文件中的 `theme` 查找主题，`config_dir` 是配置文件所在的目录。
+    pub fn find(theme: &str, config_dir: Option<&Path>) -> Result<Self, ConfigError> {
+        let config_dir = config_dir.unwrap_or(Path::new(""));
+        if theme.ends_with(".toml") || theme.contains(std::path::MAIN_SEPARATOR) {
+            return Self::load(&config_dir.join(theme));
+        }
+        let path = config_dir.join("themes").join(format!("{theme}.toml"));
+        match Self::load(&path) {
+            Err(ConfigError::Read(_, e)) if e.kind() == io::ErrorKind::NotFound => {
+                Self::bundled(theme).ok_or_else(|| ConfigError::UnknownTheme(theme.to_string()))
+            }
+            result => result,
+        }
+    }
+
+    /// 读取主题文件，文件中没有设置名称时使用文件名。
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let text =
+            fs::read_to_string(path).map_err(|e| ConfigError::Read(path.to_path_buf(), e))?;
+        let mut theme = Self::parse(&text)
+            .map_err(|diagnostics| ConfigError::Invalid(path.to_path_buf(), diagnostics))?;
+        if theme.name.is_empty() {
+            theme.name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        }
+        Ok(theme)
+    }
+
+    /// 解析主题文件的内容，返回所有发现的错误。
+    pub fn parse(text: &str) -> Result<Self, Vec<Diagnostic>> {
+        let document = parse_document(text)?;
+        let mut diagnostics = Vec::new();
+        let error = |item: &Item, message: String| Diagnostic::spanned(text, item.span(), message);
+
+        let mut name = String::new();
+        let mut colors = TuiTheme::default();
+        // 先确定基础主题，再用 `[colors]` 覆盖其中的颜色
+        if let Some(item) = document.get("base") {
+            match item.as_str().map(|base| (base, Self::bundled(base))) {
+                Some((_, Some(base))) => colors = base.colors,
+                Some((base, None)) => {
+                    diagnostics.push(error(item, format!("unknown base theme {base:?}")))
+                }
+                None => diagnostics.push(error(item, "`base` must be a string".to_string())),
+            }
+        }
+
+        for (setting, item) in document.iter() {
+            match setting {
+                "base" => (),
+                "name" => match item.as_str() {
+                    Some(text) => name = text.to_string(),
+                    None => diagnostics.push(error(item, "`name` must be a string".to_string())),
+                },
+                "colors" => match item.as_table_like() {
+                    Some(table) => {
+                        for (slot, value) in table.iter() {
+                            let (key, _) = table.get_key_value(slot).expect("遍历得到的键一定存在");
+                            let Some(color) = colors.slot_mut(slot) else {
+                                diagnostics.push(Diagnostic::spanned(
+                                    text,
+                                    key.span(),
+                                    format!("unknown color `{slot}`"),
+                                ));
+                                continue;
+                            };
+                            match parse_color(value) {
+                                Some(value) => *color = value,
+                                None => diagnostics.push(error(
+                                    value,
+                                    format!(
+                                        "invalid color for `{slot}`: expected \"#rrggbb\", \
+                                         a color name or an index from 0 to 255"
+                                    ),
+                                )),
+                            }
+                        }
+                    }
+                    None => diagnostics.push(error(item, "`colors` must be a table".to_string())),
+                },
+                _ => {
+                    let (key, _) = document.get_key_value(setting).expect("遍历得到的键一定存在");
+                    diagnostics.push(Diagnostic::spanned(
+                        text,
+                        key.span(),
+                        format!("unknown setting `{setting}`"),
+                    ));
+                }
+            }
+        }
+
+        if diagnostics.is_empty() {
+            Ok(Self { name, colors })
+        } else {
+            Err(diagnostics)
+        }
+    }
+}
+
+impl Default for Theme {
+    /// 默认主题（Tokyo Night Moon）。
+    fn default() -> Self {
+        Self::bundled(DEFAULT_THEME).expect("默认主题是内置主题")
+    }
+}
+
+/// 解析颜色：字符串是 `#rrggbb` 或颜色名称，整数是 256 色的索引。
+fn parse_color(item: &Item) -> Option<Color> {
+    if let Some(text) = item.as_str() {
+        return Color::from_str(text).ok();
+    }
+    item.as_integer()
+        .and_then(|index| u8::try_from(index).ok())
+        .map(Color::Indexed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_themes() {
+        for (name, _) in BUNDLED {
+            let theme = Theme::bundled(name).unwrap();
+            assert!(!theme.name.is_empty(), "{name}");
+        }
+        // 默认主题与 `TuiTheme` 的默认调色板一致
+        assert_eq!(Theme::default().colors, TuiTheme::default());
+        assert_eq!(Theme::default().name, "Tokyo Night Moon");
+        assert_eq!(
+            Theme::bundled("gruvbox-dark").unwrap().colors.bg,
+            Color::Rgb(0x28, 0x28, 0x28)
+        );
+        assert_eq!(Theme::bundled("ansi-16").unwrap().colors.fg, Color::Reset);
+        assert_eq!(Theme::bundled("nope"), None);
+    }
+
+    #[test]
+    fn test_parse_theme() {
+        let theme = Theme::parse(
+            r##"
+name = "Custom"
+base = "tokyonight-night"
+
+[colors]
+bg = "#000000"
+blue = "light-blue"
+comment = 244
+"##,
+        )
+        .unwrap();
+        let night = Theme::bundled("tokyonight-night").unwrap().colors;
+        assert_eq!(theme.name, "Custom");
+        assert_eq!(theme.colors.bg, Color::Rgb(0, 0, 0));
+        assert_eq!(theme.colors.blue, Color::LightBlue);
+        assert_eq!(theme.colors.comment, Color::Indexed(244));
+        assert_eq!(theme.colors.fg, night.fg);
+
+        let errors: Vec<_> = Theme::parse(
+            "base = \"nope\"\ncolor = 1\n[colors]\nbg = \"#12\"\nblu = \"red\"\nfg = 256\n",
+        )
+        .unwrap_err()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        assert_eq!(
+            errors,
+            [
+                "1:8: unknown base theme \"nope\"",
+                "2:1: unknown setting `color`",
+                "4:6: invalid color for `bg`: expected \"#rrggbb\", a color name or an index from 0 to 255",
+                "5:1: unknown color `blu`",
+                "6:6: invalid color for `fg`: expected \"#rrggbb\", a color name or an index from 0 to 255",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_theme() {
+        let dir = tempfile::tempdir().unwrap();
+        let themes = dir.path().join("themes");
+        fs::create_dir(&themes).unwrap();
+        fs::write(themes.join("mine.toml"), "[colors]\nbg = \"red\"\n").unwrap();
+        fs::write(dir.path().join("other.toml"), "name = \"Other\"\n").unwrap();
+
+        let mine = Theme::find("mine", Some(dir.path())).unwrap();
+        assert_eq!(mine.name, "mine");
+        assert_eq!(mine.colors.bg, Color::Red);
+        assert_eq!(
+            Theme::find("other.toml", Some(dir.path())).unwrap().name,
+            "Other"
+        );
+        assert_eq!(
+            Theme::find("gruvbox-dark", Some(dir.path())).unwrap(),
+            Theme::bundled("gruvbox-dark").unwrap()
+        );
+        assert_eq!(Theme::find("ansi-16", None).unwrap().name, "ANSI 16 colors");
+        assert!(matches!(
+            Theme::find("nope", Some(dir.path())),
+            Err(ConfigError::UnknownTheme(_))
+        ));
+        assert!(matches!(
+            Theme::find("missing.toml", Some(dir.path())),
+            Err(ConfigError::Read(..))
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+//! 配置文件监视模块。
+//!
+//! `ConfigWatcher` 监视配置文件所在的目录（包括 `themes/`），配置文件或主题文件变化时
+//! 通知应用程序重新读取配置。编辑器保存文件时通常会先写入临时文件再重命名，
+//! 因此监视的是目录而不是文件本身。
+
+use std::{path::Path, time::Duration};
+
+use lazy_core::publisher::Publisher;
+use notify_debouncer_full::{
+    DebounceEventResult, Debouncer, RecommendedCache, new_debouncer,
+    notify::{self, EventKind, RecommendedWatcher, RecursiveMode},
+};
+
+/// 默认的去抖时间，保存文件时的多个事件会被合并为一次重新读取
+pub const CONFIG_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// 配置目录中发生的事件。
+#[derive(Debug)]
+pub enum ConfigEvent {
+    /// 配置文件或主题文件发生变化
+    Changed,
+    /// 监视过程中发生的错误
+    Error(String),
+}
+
+/// 配置文件监视器，被丢弃时停止监视。
+pub struct ConfigWatcher {
+    /// 去抖后的文件系统监视器
+    _debouncer: Debouncer<RecommendedWatcher, RecommendedCache>,
+}
+
+impl ConfigWatcher {
+    /// 开始监视配置文件所在的目录。
+    ///
+    /// 只有配置文件本身和目录中的 `.toml` 文件（主题文件）的变化会发送 `ConfigEvent::Changed`。
+    pub fn watch(
+        config_path: &Path,
+        debounce: Duration,
+        events: impl Into<Publisher<ConfigEvent>>,
+    ) -> notify::Result<Self> {
+        let events = events.into();
+        let dir = config_path
+            .parent()
+            .ok_or_else(|| notify::Error::path_not_found().add_path(config_path.to_path_buf()))?
+            .to_path_buf();
+        let config_path = config_path.to_path_buf();
+        let mut debouncer = new_debouncer(debounce, None, move |result: DebounceEventResult| {
+            let event = match result {
+                Ok(debounced) => {
+                    if !debounced
+                        .iter()
+                        .any(|e| is_relevant(&e.event, &config_path))
+                    {
+                        return;
+                    }
+                    ConfigEvent::Changed
+                }
+                Err(errors) => match errors.into_iter().next() {
+                    Some(error) => ConfigEvent::Error(error.to_string()),
+                    None => return,
+                },
+            };
+            events.publish(event);
+        })?;
+        debouncer.watch(&dir, RecursiveMode::Recursive)?;
+
+        Ok(Self {
+            _debouncer: debouncer,
+        })
+    }
+}
+
+/// 判断文件系统事件是否修改了配置文件或主题文件。
+fn is_relevant(event: &notify::Event, config_path: &Path) -> bool {
+    let changed = matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    );
+    changed
+        && event
+            .paths
+            .iter()
+            .any(|path| path == config_path || path.extension().is_some_and(|ext| ext == "toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn event(kind: EventKind, path: &Path) -> notify::Event {
+        notify::Event::new(kind).add_path(path.to_path_buf())
+    }
+
+    #[test]
+    fn test_is_relevant() {
+        let config = PathBuf::from("/config/lazymusic/config.toml");
+        let theme = Path::new("/config/lazymusic/themes/mine.toml");
+        let modify = EventKind::Modify(ModifyKind::Any);
+
+        assert!(is_relevant(&event(modify, &config), &config));
+        assert!(is_relevant(
+            &event(EventKind::Create(CreateKind::File), theme),
+            &config
+        ));
+        // 编辑器的临时文件和只读访问不会触发重新读取
+        assert!(!is_relevant(
+            &event(modify, Path::new("/config/lazymusic/.config.toml.swp")),
+            &config
+        ));
+        assert!(!is_relevant(
+            &event(EventKind::Access(AccessKind::Any), &config),
+            &config
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_watcher_reports_config_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.toml");
+        let (tx, mut rx) = unbounded_channel();
+        let _watcher = ConfigWatcher::watch(&config, Duration::from_millis(100), tx).unwrap();
+
+        std::fs::write(&config, "theme = \"gruvbox-dark\"\n").unwrap();
+
+        let event = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+            .await
+            .expect("等待文件系统事件超时")
+            .unwrap();
+        assert!(matches!(event, ConfigEvent::Changed), "{event:?}");
+    }
+}
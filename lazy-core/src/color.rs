@@ -0,0 +1,360 @@
+//! 终端颜色模块。
+//!
+//! `ColorDepth` 是终端能显示的颜色数量。`ColorDepth::detect` 依次根据 `COLORTERM`、
+//! terminfo 中的 `colors` 和 `TERM` 的名称判断终端的颜色深度；`ColorDepth::adapt`
+//! 把真彩色映射为终端能显示的最接近的 256 色或 16 色。
+
+use std::{
+    env,
+    fmt::{self, Display},
+    fs,
+    path::PathBuf,
+    str::FromStr,
+};
+
+use ratatui::style::Color;
+
+/// 终端的颜色深度。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// 24 位真彩色
+    #[default]
+    TrueColor,
+    /// xterm 256 色
+    Ansi256,
+    /// 16 色（8 种颜色及其高亮版本）
+    Ansi16,
+}
+
+/// 16 色调色板中每种颜色的近似值（xterm 的默认值），按颜色编号排列
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// 256 色中 6×6×6 颜色立方每个分量的取值
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    /// 检测当前终端的颜色深度。
+    pub fn detect() -> Self {
+        Self::from_env(
+            env::var("COLORTERM").ok().as_deref(),
+            env::var("TERM").ok().as_deref(),
+            terminfo_colors,
+        )
+    }
+
+    /// 根据环境变量判断颜色深度，`colors` 从 terminfo 中读取终端名称对应的颜色数量。
+    fn from_env(
+        colorterm: Option<&str>,
+        term: Option<&str>,
+        colors: impl FnOnce(&str) -> Option<i32>,
+    ) -> Self {
+        if let Some(colorterm) = colorterm
+            && matches!(
+                colorterm.to_ascii_lowercase().as_str(),
+                "truecolor" | "24bit"
+            )
+        {
+            return Self::TrueColor;
+        }
+        let Some(term) = term.filter(|term| !term.is_empty()) else {
+            return Self::Ansi16;
+        };
+        match colors(term) {
+            Some(n) if n >= 1 << 24 => return Self::TrueColor,
+            Some(n) if n >= 256 => return Self::Ansi256,
+            Some(_) => return Self::Ansi16,
+            None => (),
+        }
+        // 没有 terminfo 时根据终端名称猜测
+        if term.contains("truecolor") || term.contains("direct") {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    /// 将颜色映射为终端能显示的最接近的颜色，命名的 16 色和 `Reset` 保持不变。
+    pub fn adapt(self, color: Color) -> Color {
+        match (self, color) {
+            (Self::TrueColor, _) => color,
+            (Self::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_256((r, g, b))),
+            (Self::Ansi16, Color::Rgb(r, g, b)) => nearest_16((r, g, b)),
+            (Self::Ansi16, Color::Indexed(index)) => match ANSI16.get(usize::from(index)) {
+                Some(&(named, _)) => named,
+                None => nearest_16(indexed_rgb(index)),
+            },
+            _ => color,
+        }
+    }
+}
+
+impl FromStr for ColorDepth {
+    type Err = String;
+
+    /// 解析颜色深度：`truecolor`（或 `24bit`）、`256` 或 `16`。
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.to_ascii_lowercase().as_str() {
+            "truecolor" | "24bit" => Ok(Self::TrueColor),
+            "256" => Ok(Self::Ansi256),
+            "16" => Ok(Self::Ansi16),
+            _ => Err(format!("unknown color depth {text:?}")),
+        }
+    }
+}
+
+impl Display for ColorDepth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::TrueColor => "truecolor",
+            Self::Ansi256 => "256",
+            Self::Ansi16 => "16",
+        })
+    }
+}
+
+/// 两种颜色之间的距离（按人眼对各分量的敏感程度加权的平方距离）。
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let dr = u32::from(r1.abs_diff(r2));
+    let dg = u32::from(g1.abs_diff(g2));
+    let db = u32::from(b1.abs_diff(b2));
+    2 * dr * dr + 4 * dg * dg + 3 * db * db
+}
+
+/// 返回 256 色中最接近的颜色的编号，只在颜色立方和灰阶中查找，
+/// 因为前 16 种颜色的实际值由终端的配色决定。
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |c: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| CUBE_LEVELS[i].abs_diff(c))
+            .unwrap_or(0)
+    };
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = 16 + 36 * r + 6 * g + b;
+
+    // 灰阶 232..=255 的值为 8、18、…、238
+    let average = (u16::from(rgb.0) + u16::from(rgb.1) + u16::from(rgb.2)) / 3;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23) as usize;
+
+    [cube, gray]
+        .into_iter()
+        .min_by_key(|&index| distance(rgb, indexed_rgb(index as u8)))
+        .unwrap_or(cube) as u8
+}
+
+/// 返回 16 色中最接近的颜色。
+fn nearest_16(rgb: (u8, u8, u8)) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, value)| distance(rgb, *value))
+        .map_or(Color::Reset, |&(color, _)| color)
+}
+
+/// 返回 256 色中的颜色的近似值。
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..16 => ANSI16[usize::from(index)].1,
+        16..232 => {
+            let i = usize::from(index - 16);
+            (
+                CUBE_LEVELS[i / 36],
+                CUBE_LEVELS[i / 6 % 6],
+                CUBE_LEVELS[i % 6],
+            )
+        }
+        _ => {
+            let value = 8 + 10 * (index - 232);
+            (value, value, value)
+        }
+    }
+}
+
+/// 从 terminfo 数据库中读取终端的 `colors`，找不到或无法解析时返回 `None`。
+fn terminfo_colors(term: &str) -> Option<i32> {
+    let first = term.chars().next()?;
+    terminfo_dirs().into_iter().find_map(|dir| {
+        // Linux 上按首字母分目录，macOS 上按首字母的十六进制编码分目录
+        [first.to_string(), format!("{:x}", u32::from(first))]
+            .into_iter()
+            .find_map(|sub| fs::read(dir.join(sub).join(term)).ok())
+            .and_then(|data| parse_max_colors(&data))
+    })
+}
+
+/// terminfo 数据库的搜索路径，顺序与 ncurses 相同。
+fn terminfo_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = env::var_os("TERMINFO") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(home) = env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".terminfo"));
+    }
+    if let Some(list) = env::var_os("TERMINFO_DIRS") {
+        dirs.extend(env::split_paths(&list).filter(|dir| !dir.as_os_str().is_empty()));
+    }
+    dirs.extend(
+        [
+            "/etc/terminfo",
+            "/lib/terminfo",
+            "/usr/share/terminfo",
+            "/usr/lib/terminfo",
+        ]
+        .map(PathBuf::from),
+    );
+    dirs
+}
+
+/// 从编译后的 terminfo 条目中读取 `colors`（第 13 个数值项）。
+///
+/// 支持传统格式（16 位数值）和 ncurses 6 的扩展格式（32 位数值）。
+fn parse_max_colors(data: &[u8]) -> Option<i32> {
+    const MAX_COLORS: usize = 13;
+    let short = |offset: usize| -> Option<i32> {
+        let bytes = data.get(offset..offset + 2)?;
+        Some(i32::from(i16::from_le_bytes([bytes[0], bytes[1]])))
+    };
+    let width = match short(0)? {
+        0o432 => 2,
+        0o1036 => 4,
+        _ => return None,
+    };
+    let names = usize::try_from(short(2)?).ok()?;
+    let bools = usize::try_from(short(4)?).ok()?;
+    let numbers = usize::try_from(short(6)?).ok()?;
+    if MAX_COLORS >= numbers {
+        return None;
+    }
+    // 数值项从偶数偏移开始
+    let start = (12 + names + bools).next_multiple_of(2) + MAX_COLORS * width;
+    let bytes = data.get(start..start + width)?;
+    let colors = match width {
+        2 => i32::from(i16::from_le_bytes([bytes[0], bytes[1]])),
+        _ => i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+    };
+    // 负数表示没有这一项
+    (colors >= 0).then_some(colors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_from_env() {
+        let none = |_: &str| None;
+        assert_eq!(
+            ColorDepth::from_env(Some("truecolor"), Some("xterm"), none),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, Some("tmux-256color"), none),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, Some("linux"), none),
+            ColorDepth::Ansi16
+        );
+        assert_eq!(ColorDepth::from_env(None, None, none), ColorDepth::Ansi16);
+        // terminfo 中的颜色数量优先于终端名称
+        assert_eq!(
+            ColorDepth::from_env(None, Some("xterm-256color"), |_| Some(8)),
+            ColorDepth::Ansi16
+        );
+        assert_eq!(
+            ColorDepth::from_env(None, Some("xterm-direct"), |_| Some(1 << 24)),
+            ColorDepth::TrueColor
+        );
+    }
+
+    #[test]
+    fn test_parse_color_depth() {
+        assert_eq!("256".parse(), Ok(ColorDepth::Ansi256));
+        assert_eq!("TrueColor".parse(), Ok(ColorDepth::TrueColor));
+        assert_eq!("16".parse(), Ok(ColorDepth::Ansi16));
+        assert!("88".parse::<ColorDepth>().is_err());
+        for depth in [
+            ColorDepth::TrueColor,
+            ColorDepth::Ansi256,
+            ColorDepth::Ansi16,
+        ] {
+            assert_eq!(depth.to_string().parse(), Ok(depth));
+        }
+    }
+
+    #[test]
+    fn test_adapt_colors() {
+        let rgb = Color::Rgb(130, 170, 255);
+        assert_eq!(ColorDepth::TrueColor.adapt(rgb), rgb);
+        // #82aaff 最接近颜色立方中的 (135, 175, 255)
+        assert_eq!(ColorDepth::Ansi256.adapt(rgb), Color::Indexed(111));
+        assert_eq!(ColorDepth::Ansi16.adapt(rgb), Color::LightBlue);
+
+        // 灰色映射到灰阶而不是颜色立方
+        assert_eq!(
+            ColorDepth::Ansi256.adapt(Color::Rgb(34, 36, 54)),
+            Color::Indexed(235)
+        );
+        assert_eq!(
+            ColorDepth::Ansi256.adapt(Color::Rgb(0, 0, 0)),
+            Color::Indexed(16)
+        );
+
+        // 命名的颜色和 16 色以内的编号保持不变或转换为对应的名称
+        assert_eq!(ColorDepth::Ansi256.adapt(Color::Red), Color::Red);
+        assert_eq!(ColorDepth::Ansi16.adapt(Color::Reset), Color::Reset);
+        assert_eq!(ColorDepth::Ansi16.adapt(Color::Indexed(9)), Color::LightRed);
+        assert_eq!(
+            ColorDepth::Ansi16.adapt(Color::Indexed(196)),
+            Color::LightRed
+        );
+    }
+
+    #[test]
+    fn test_parse_terminfo_max_colors() {
+        // 名称 "x|y\0"、2 个布尔项、14 个数值项，`colors` 为 256
+        let mut entry = Vec::new();
+        for value in [0o432_i16, 4, 2, 14, 0, 0] {
+            entry.extend(value.to_le_bytes());
+        }
+        entry.extend(b"x|y\0");
+        entry.extend([1, 0]);
+        for i in 0..14 {
+            entry.extend(if i == 13 { 256_i16 } else { -1 }.to_le_bytes());
+        }
+        assert_eq!(parse_max_colors(&entry), Some(256));
+
+        // 扩展格式的数值是 32 位，名称和布尔项的总长度为奇数时数值项前有一个字节的填充
+        let mut entry = Vec::new();
+        for value in [0o1036_i16, 3, 0, 14, 0, 0] {
+            entry.extend(value.to_le_bytes());
+        }
+        entry.extend(b"xy\0");
+        entry.push(0);
+        for i in 0..14 {
+            entry.extend(if i == 13 { 1_i32 << 24 } else { -1 }.to_le_bytes());
+        }
+        assert_eq!(parse_max_colors(&entry), Some(1 << 24));
+        assert_eq!(parse_max_colors(&entry[..entry.len() - 4]), None);
+
+        assert_eq!(parse_max_colors(b"not terminfo"), None);
+    }
+}
@@ -1,3 +1,9 @@
+pub mod color;
+pub mod logger;
+pub mod publisher;
+pub mod queue;
 pub mod structs;
 pub mod theme;
+pub mod track;
 pub mod traits;
+pub mod types;
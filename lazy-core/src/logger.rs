@@ -0,0 +1,337 @@
+//! 日志模块。
+//!
+//! `Logger` 实现了 `log` crate 的 `Log` trait：各个 crate 通过 `log::info!` 等宏记录的日志
+//! 被保存到固定容量的环形缓冲区 `LogBuffer` 中供日志页显示，也可以同时追加写入日志文件。
+//! TUI 运行时向标准错误输出写入会破坏界面，因此程序中的所有日志都应通过这里记录。
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub use log::{Level, LevelFilter};
+use log::{Log, Metadata, Record};
+
+/// 环形缓冲区默认保存的日志条数
+pub const DEFAULT_CAPACITY: usize = 1000;
+
+/// 一条日志。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogRecord {
+    /// 记录的时间
+    pub time: SystemTime,
+    /// 日志级别
+    pub level: Level,
+    /// 记录日志的模块，例如 `lazy_app::app`
+    pub target: String,
+    /// 日志内容
+    pub message: String,
+}
+
+impl LogRecord {
+    /// 创建一条时间为当前时间的日志。
+    pub fn new(level: Level, target: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            time: SystemTime::now(),
+            level,
+            target: target.into(),
+            message: message.into(),
+        }
+    }
+
+    /// 将时间格式化为 `HH:MM:SS.mmm`（UTC）。
+    pub fn clock(&self) -> String {
+        let (_, time) = self.timestamp_parts();
+        time
+    }
+
+    /// 将时间格式化为 `YYYY-MM-DDTHH:MM:SS.mmmZ`。
+    pub fn timestamp(&self) -> String {
+        let (date, time) = self.timestamp_parts();
+        format!("{date}T{time}Z")
+    }
+
+    /// 将时间拆分为日期和时间两部分。
+    fn timestamp_parts(&self) -> (String, String) {
+        let since_epoch = self.time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let seconds = since_epoch.as_secs();
+        let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+        let seconds_of_day = seconds % 86_400;
+        (
+            format!("{year:04}-{month:02}-{day:02}"),
+            format!(
+                "{:02}:{:02}:{:02}.{:03}",
+                seconds_of_day / 3600,
+                seconds_of_day / 60 % 60,
+                seconds_of_day % 60,
+                since_epoch.subsec_millis()
+            ),
+        )
+    }
+}
+
+impl fmt::Display for LogRecord {
+    /// 按日志文件中的格式输出：`时间 级别 模块: 内容`。
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:<5} {}: {}",
+            self.timestamp(),
+            self.level,
+            self.target,
+            self.message
+        )
+    }
+}
+
+/// 将 1970-01-01 以来的天数转换为公历的年、月、日。
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// 保存最近日志的环形缓冲区，可以在线程间共享。
+///
+/// 每条日志都有一个递增的序号，读取方保存已读取的序号，之后只取出新的日志。
+#[derive(Clone, Debug)]
+pub struct LogBuffer {
+    inner: Arc<Mutex<BufferInner>>,
+}
+
+#[derive(Debug)]
+struct BufferInner {
+    records: VecDeque<LogRecord>, // 最近的日志
+    capacity: usize,              // 最多保存的日志条数
+    next: u64,                    // 下一条日志的序号，即累计记录的日志条数
+}
+
+impl LogBuffer {
+    /// 创建最多保存 `capacity` 条日志的缓冲区。
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(BufferInner {
+                records: VecDeque::with_capacity(capacity),
+                capacity,
+                next: 0,
+            })),
+        }
+    }
+
+    /// 添加一条日志，缓冲区已满时丢弃最早的日志。
+    pub fn push(&self, record: LogRecord) {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if inner.records.len() == inner.capacity {
+            inner.records.pop_front();
+        }
+        if inner.capacity > 0 {
+            inner.records.push_back(record);
+        }
+        inner.next += 1;
+    }
+
+    /// 返回下一条日志的序号。
+    pub fn next_seq(&self) -> u64 {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner()).next
+    }
+
+    /// 取出序号不小于 `since` 的日志，返回这些日志和下一条日志的序号。
+    ///
+    /// 已被丢弃的日志不会返回。
+    pub fn since(&self, since: u64) -> (Vec<LogRecord>, u64) {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let first = inner.next - inner.records.len() as u64;
+        let skip = since.saturating_sub(first) as usize;
+        let records = inner.records.iter().skip(skip).cloned().collect();
+        (records, inner.next)
+    }
+}
+
+impl Default for LogBuffer {
+    /// 创建容量为 `DEFAULT_CAPACITY` 的缓冲区。
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+/// 日志记录器，把日志保存到 `LogBuffer`，并可以同时追加写入日志文件。
+///
+/// 本程序（`lazy_*`）的日志按设置的级别过滤，依赖库的日志只记录警告和错误。
+#[derive(Debug)]
+pub struct Logger {
+    buffer: LogBuffer,         // 保存日志的环形缓冲区
+    level: LevelFilter,        // 本程序日志的最低级别
+    file: Option<Mutex<File>>, // 日志文件
+}
+
+impl Logger {
+    /// 创建把日志保存到 `buffer` 的记录器，默认记录 `Info` 及以上级别的日志。
+    pub fn new(buffer: LogBuffer) -> Self {
+        Self {
+            buffer,
+            level: LevelFilter::Info,
+            file: None,
+        }
+    }
+
+    /// 设置本程序日志的最低级别。
+    pub fn with_level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// 同时把日志追加写入文件，文件所在的目录不存在时自动创建。
+    pub fn set_file(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        self.file = Some(Mutex::new(file));
+        Ok(())
+    }
+
+    /// 将记录器设为 `log` crate 的全局记录器，整个程序只能设置一次。
+    pub fn install(self) -> Result<(), log::SetLoggerError> {
+        let level = self.level.max(LevelFilter::Warn);
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(level);
+        Ok(())
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        if metadata.target().starts_with("lazy_") {
+            metadata.level() <= self.level
+        } else {
+            metadata.level() <= Level::Warn
+        }
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let record = LogRecord::new(record.level(), record.target(), record.args().to_string());
+        if let Some(file) = &self.file {
+            let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+            // 写入失败时无法再记录日志，只能忽略
+            let _ = writeln!(file, "{record}");
+        }
+        self.buffer.push(record);
+    }
+
+    fn flush(&self) {
+        if let Some(file) = &self.file {
+            let _ = file.lock().unwrap_or_else(|e| e.into_inner()).flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn record(message: &str) -> LogRecord {
+        LogRecord::new(Level::Info, "lazy_core::logger", message)
+    }
+
+    #[test]
+    fn test_log_record_format() {
+        let record = LogRecord {
+            time: UNIX_EPOCH + Duration::from_millis(951_782_400_000 + 3_723_045),
+            ..record("hello")
+        };
+        assert_eq!(record.clock(), "01:02:03.045");
+        assert_eq!(
+            record.to_string(),
+            "2000-02-29T01:02:03.045Z INFO  lazy_core::logger: hello"
+        );
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn test_log_buffer_ring() {
+        let buffer = LogBuffer::new(3);
+        for i in 0..2 {
+            buffer.push(record(&i.to_string()));
+        }
+        let (records, next) = buffer.since(0);
+        assert_eq!(records.len(), 2);
+        assert_eq!(next, 2);
+
+        for i in 2..5 {
+            buffer.push(record(&i.to_string()));
+        }
+        let messages = |records: Vec<LogRecord>| -> Vec<String> {
+            records.into_iter().map(|r| r.message).collect()
+        };
+        // 只保留最近的 3 条，已丢弃的日志不会返回
+        assert_eq!(messages(buffer.since(next).0), ["2", "3", "4"]);
+        assert_eq!(messages(buffer.since(0).0), ["2", "3", "4"]);
+        assert_eq!(messages(buffer.since(4).0), ["4"]);
+        assert!(buffer.since(5).0.is_empty());
+    }
+
+    #[test]
+    fn test_logger_filters_and_mirrors_to_file() {
+        let dir = std::env::temp_dir().join(format!("lazy-core-logger-{}", std::process::id()));
+        let path = dir.join("logs/lazymusic.log");
+        let buffer = LogBuffer::default();
+        let mut logger = Logger::new(buffer.clone()).with_level(LevelFilter::Debug);
+        logger.set_file(&path).unwrap();
+
+        let log = |level, target, message| {
+            logger.log(
+                &Record::builder()
+                    .level(level)
+                    .target(target)
+                    .args(format_args!("{message}"))
+                    .build(),
+            )
+        };
+        log(Level::Debug, "lazy_app::app", "scanning");
+        log(Level::Trace, "lazy_app::app", "too verbose");
+        log(Level::Info, "symphonia_core", "dependency info");
+        log(Level::Warn, "symphonia_core", "dependency warning");
+        logger.flush();
+
+        let (records, _) = buffer.since(0);
+        let targets: Vec<_> = records
+            .iter()
+            .map(|r| (r.level, r.message.as_str()))
+            .collect();
+        assert_eq!(
+            targets,
+            [
+                (Level::Debug, "scanning"),
+                (Level::Warn, "dependency warning")
+            ]
+        );
+        let file = fs::read_to_string(&path).unwrap();
+        assert_eq!(file.lines().count(), 2);
+        assert!(
+            file.lines()
+                .next()
+                .unwrap()
+                .ends_with("DEBUG lazy_app::app: scanning")
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,80 @@
+//! 消息发布模块。
+//!
+//! 播放引擎、媒体库扫描器和监视器等后台服务通过 `Publisher` 发布自己的事件，
+//! 不需要知道事件最终被发送到哪个通道，也不需要知道应用程序的消息类型和 TUI 的类型。
+//! 应用程序把各个服务的 `Publisher` 映射到同一条消息总线上，在主循环中统一处理。
+
+use std::{fmt, sync::Arc};
+
+use tokio::sync::mpsc::UnboundedSender;
+
+/// 类型化的消息发布端，可以被克隆并发送到其他线程。
+pub struct Publisher<T> {
+    send: Arc<dyn Fn(T) -> bool + Send + Sync>, // 发送一条消息，接收端已关闭时返回 `false`
+}
+
+impl<T> Publisher<T> {
+    /// 用发送函数创建发布端，函数在接收端已关闭时应返回 `false`。
+    pub fn new(send: impl Fn(T) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            send: Arc::new(send),
+        }
+    }
+
+    /// 发布一条消息；接收端已关闭时返回 `false`。
+    pub fn publish(&self, message: T) -> bool {
+        (self.send)(message)
+    }
+
+    /// 返回一个发布 `U` 类型消息的发布端，消息经过 `f` 转换后由当前发布端发送。
+    pub fn map<U>(&self, f: impl Fn(U) -> T + Send + Sync + 'static) -> Publisher<U>
+    where
+        T: 'static,
+    {
+        let send = self.send.clone();
+        Publisher::new(move |message| send(f(message)))
+    }
+}
+
+impl<T> Clone for Publisher<T> {
+    fn clone(&self) -> Self {
+        Self {
+            send: self.send.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Publisher<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Publisher").finish_non_exhaustive()
+    }
+}
+
+impl<T: Send + 'static> From<UnboundedSender<T>> for Publisher<T> {
+    /// 把消息发送到 tokio 通道。
+    fn from(sender: UnboundedSender<T>) -> Self {
+        Self::new(move |message| sender.send(message).is_ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    #[test]
+    fn test_publisher_maps_to_channel() {
+        let (tx, mut rx) = unbounded_channel::<String>();
+        let strings = Publisher::from(tx);
+        let numbers = strings.map(|n: u32| n.to_string());
+
+        assert!(strings.publish("a".to_string()));
+        assert!(numbers.clone().publish(1));
+        assert_eq!(rx.try_recv().unwrap(), "a");
+        assert_eq!(rx.try_recv().unwrap(), "1");
+
+        // 接收端关闭后发布失败
+        drop(rx);
+        assert!(!numbers.publish(2));
+    }
+}
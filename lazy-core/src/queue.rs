@@ -0,0 +1,644 @@
+//! 播放队列模块。
+//!
+//! 队列独立于 TUI，由应用程序持有，播放引擎和各个前端都通过它决定播放顺序。
+//! 队列同时负责执行 `PlaybackMode`：切换曲目时根据当前模式决定下一首。
+
+use std::{path::Path, time::Duration};
+
+use crate::{track::Track, types::PlaybackMode};
+
+/// 播放队列，保存待播放的曲目、当前播放位置和播放模式。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Queue {
+    /// 队列中的曲目
+    tracks: Vec<Track>,
+    /// 当前播放曲目的索引
+    current: Option<usize>,
+    /// 播放模式
+    mode: PlaybackMode,
+    /// 随机模式下本轮的播放顺序（曲目索引），其他模式下为空
+    shuffle: Vec<usize>,
+    /// 本轮中已经播放过的曲目数量（包括当前曲目），即 `shuffle` 中已播放部分的长度
+    played: usize,
+    /// 生成随机顺序的伪随机数生成器
+    rng: SplitMix64,
+}
+
+impl Queue {
+    /// 创建一个空队列。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建一个使用指定随机种子的空队列，相同的种子和操作总是得到相同的随机顺序。
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            rng: SplitMix64(seed),
+            ..Self::default()
+        }
+    }
+
+    /// 在队尾添加一首曲目。
+    pub fn push(&mut self, track: Track) {
+        self.extend([track]);
+    }
+
+    /// 在队尾添加多首曲目。
+    ///
+    /// 随机模式下新曲目会被打乱到本轮尚未播放的部分中。
+    pub fn extend(&mut self, tracks: impl IntoIterator<Item = Track>) {
+        let start = self.tracks.len();
+        self.tracks.extend(tracks);
+        if self.mode == PlaybackMode::Random {
+            self.shuffle.extend(start..self.tracks.len());
+            self.rng.shuffle(&mut self.shuffle[self.played..]);
+        }
+    }
+
+    /// 返回队列中的所有曲目。
+    pub fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+
+    /// 返回队列长度。
+    pub fn len(&self) -> usize {
+        self.tracks.len()
+    }
+
+    /// 队列是否为空。
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    /// 返回队列中所有曲目的总时长，时长未知的曲目不计入。
+    pub fn total_duration(&self) -> Duration {
+        self.tracks.iter().filter_map(|t| t.duration).sum()
+    }
+
+    /// 返回当前曲目的索引。
+    pub fn current_index(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// 返回当前曲目。
+    pub fn current(&self) -> Option<&Track> {
+        self.current.map(|i| &self.tracks[i])
+    }
+
+    /// 返回播放模式。
+    pub fn mode(&self) -> PlaybackMode {
+        self.mode
+    }
+
+    /// 设置播放模式，当前曲目保持不变。
+    ///
+    /// 切换到随机模式时以当前曲目为起点重新生成本轮的随机顺序。
+    pub fn set_mode(&mut self, mode: PlaybackMode) {
+        if self.mode == mode {
+            return;
+        }
+        self.mode = mode;
+        self.shuffle.clear();
+        self.played = 0;
+        if mode == PlaybackMode::Random {
+            self.shuffle = (0..self.tracks.len())
+                .filter(|&i| Some(i) != self.current)
+                .collect();
+            self.rng.shuffle(&mut self.shuffle);
+            if let Some(current) = self.current {
+                self.shuffle.insert(0, current);
+                self.played = 1;
+            }
+        }
+    }
+
+    /// 将指定索引设为当前曲目，索引越界时返回 `None`。
+    ///
+    /// 随机模式下被选中的曲目计为本轮已播放，不会在本轮中再次出现。
+    pub fn select(&mut self, index: usize) -> Option<&Track> {
+        if index < self.tracks.len() {
+            self.current = Some(index);
+            if self.mode == PlaybackMode::Random {
+                self.mark_played(index);
+            }
+        }
+        self.tracks.get(index)
+    }
+
+    /// 切换到下一首曲目（用户主动切换）。
+    ///
+    /// 尚未开始播放时从第一首开始。到达队尾时，`Repeat` 和 `Single` 回到第一首，
+    /// `Sequential` 和 `Consume` 返回 `None`；`Random` 按本轮的随机顺序前进，
+    /// 一轮播放完后重新打乱；`Consume` 会先移除当前曲目。
+    pub fn next_track(&mut self) -> Option<&Track> {
+        if self.tracks.is_empty() {
+            return None;
+        }
+        let len = self.tracks.len();
+        match self.mode {
+            PlaybackMode::Repeat | PlaybackMode::Single => {
+                let index = self.current.map_or(0, |i| (i + 1) % len);
+                self.select(index)
+            }
+            PlaybackMode::Sequential => {
+                let index = self.current.map_or(0, |i| i + 1);
+                self.select(index)
+            }
+            PlaybackMode::Consume => {
+                let index = self.current.unwrap_or(0);
+                if self.current.is_some() {
+                    self.remove(index);
+                }
+                self.select(index)
+            }
+            PlaybackMode::Random => self.next_shuffled(),
+        }
+    }
+
+    /// 当前曲目播放完毕时前进到下一首。
+    ///
+    /// `Single` 模式下重复当前曲目，其余模式与 `next_track` 相同。
+    pub fn advance(&mut self) -> Option<&Track> {
+        if self.mode == PlaybackMode::Single && self.current.is_some() {
+            return self.current();
+        }
+        self.next_track()
+    }
+
+    /// 返回 `advance` 将要前进到的曲目，不改变队列，用于预加载下一首曲目。
+    ///
+    /// 随机模式下本轮已经播放完时，新一轮的顺序在前进时才生成，此时返回 `None`。
+    pub fn peek_advance(&self) -> Option<&Track> {
+        let index = match (self.mode, self.current) {
+            (PlaybackMode::Single, Some(current)) => current,
+            (PlaybackMode::Repeat | PlaybackMode::Single, current) => {
+                current.map_or(0, |i| (i + 1) % self.tracks.len().max(1))
+            }
+            // 消费模式下当前曲目被移除后，下一首移到它的位置上
+            (PlaybackMode::Sequential | PlaybackMode::Consume, current) => {
+                current.map_or(0, |i| i + 1)
+            }
+            (PlaybackMode::Random, _) => *self.shuffle.get(self.played)?,
+        };
+        self.tracks.get(index)
+    }
+
+    /// 后退到上一首曲目。
+    ///
+    /// `Repeat` 和 `Single` 在队首时回到最后一首，`Random` 按本轮已播放的顺序后退，
+    /// 其余模式在队首时返回 `None`。
+    pub fn prev_track(&mut self) -> Option<&Track> {
+        let current = self.current?;
+        match self.mode {
+            PlaybackMode::Repeat | PlaybackMode::Single => {
+                let len = self.tracks.len();
+                self.select((current + len - 1) % len)
+            }
+            PlaybackMode::Sequential | PlaybackMode::Consume => {
+                self.select(current.checked_sub(1)?)
+            }
+            PlaybackMode::Random => {
+                if self.played < 2 {
+                    return None;
+                }
+                self.played -= 1;
+                let index = self.shuffle[self.played - 1];
+                self.current = Some(index);
+                self.tracks.get(index)
+            }
+        }
+    }
+
+    /// 移除指定索引的曲目。
+    ///
+    /// 移除的是当前曲目时，队列回到未播放状态；移除的曲目在当前曲目之前时，
+    /// 当前位置随之前移，保持指向同一首曲目。
+    pub fn remove(&mut self, index: usize) -> Option<Track> {
+        if index >= self.tracks.len() {
+            return None;
+        }
+        self.current = match self.current {
+            Some(current) if current == index => None,
+            Some(current) if current > index => Some(current - 1),
+            current => current,
+        };
+        if let Some(position) = self.shuffle.iter().position(|&i| i == index) {
+            self.shuffle.remove(position);
+            if position < self.played {
+                self.played -= 1;
+            }
+            self.shuffle
+                .iter_mut()
+                .filter(|i| **i > index)
+                .for_each(|i| *i -= 1);
+        }
+        Some(self.tracks.remove(index))
+    }
+
+    /// 将曲目从 `from` 移动到 `to`，其余曲目依次顺移，任一索引越界时返回 `false`。
+    ///
+    /// 当前位置和随机顺序始终指向同一首曲目。
+    pub fn move_track(&mut self, from: usize, to: usize) -> bool {
+        let len = self.tracks.len();
+        if from >= len || to >= len {
+            return false;
+        }
+        let track = self.tracks.remove(from);
+        self.tracks.insert(to, track);
+        let moved = |index: usize| match index {
+            i if i == from => to,
+            i if from < i && i <= to => i - 1,
+            i if to <= i && i < from => i + 1,
+            i => i,
+        };
+        self.current = self.current.map(moved);
+        self.shuffle.iter_mut().for_each(|i| *i = moved(*i));
+        true
+    }
+
+    /// 清空队列。
+    pub fn clear(&mut self) {
+        self.tracks.clear();
+        self.current = None;
+        self.shuffle.clear();
+        self.played = 0;
+    }
+
+    /// 随机模式下按本轮的顺序前进，一轮播放完后重新打乱。
+    fn next_shuffled(&mut self) -> Option<&Track> {
+        if self.played >= self.shuffle.len() {
+            self.shuffle = (0..self.tracks.len()).collect();
+            self.rng.shuffle(&mut self.shuffle);
+            // 避免新一轮的第一首与刚播放完的曲目相同
+            if self.shuffle.len() > 1 && self.shuffle.first() == self.current.as_ref() {
+                let last = self.shuffle.len() - 1;
+                self.shuffle.swap(0, last);
+            }
+            self.played = 0;
+        }
+        let index = self.shuffle[self.played];
+        self.played += 1;
+        self.current = Some(index);
+        self.tracks.get(index)
+    }
+
+    /// 将曲目移到本轮已播放部分的末尾，使其成为本轮的当前曲目。
+    fn mark_played(&mut self, index: usize) {
+        let Some(position) = self.shuffle.iter().position(|&i| i == index) else {
+            return;
+        };
+        if position >= self.played {
+            self.shuffle.swap(position, self.played);
+            self.played += 1;
+        } else {
+            self.shuffle.remove(position);
+            self.shuffle.insert(self.played - 1, index);
+        }
+    }
+
+    /// 用 `lookup` 返回的最新信息替换队列中的曲目，返回发生变化的曲目的索引。
+    ///
+    /// 用于媒体库重新读取标签后刷新队列中显示的标题、艺术家等信息。
+    pub fn refresh(&mut self, mut lookup: impl FnMut(&Path) -> Option<Track>) -> Vec<usize> {
+        let mut changed = Vec::new();
+        for (index, track) in self.tracks.iter_mut().enumerate() {
+            if let Some(updated) = lookup(&track.path)
+                && updated != *track
+            {
+                *track = updated;
+                changed.push(index);
+            }
+        }
+        changed
+    }
+}
+
+/// 生成随机播放顺序的伪随机数生成器（SplitMix64），相同的种子总是得到相同的序列。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    /// 生成下一个随机数。
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// 使用 Fisher-Yates 算法原地打乱切片。
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next_u64() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue() -> Queue {
+        let mut queue = Queue::new();
+        queue.extend(["a.flac", "b.flac", "c.flac"].map(Track::new));
+        queue
+    }
+
+    /// 创建包含 `len` 首曲目的随机模式队列。
+    fn random_queue(len: usize, seed: u64) -> Queue {
+        let mut queue = Queue::with_seed(seed);
+        queue.extend((0..len).map(|i| Track::new(format!("{i}.flac"))));
+        queue.set_mode(PlaybackMode::Random);
+        queue
+    }
+
+    /// 连续调用 `advance` 并返回播放的曲目索引。
+    fn advance(queue: &mut Queue, times: usize) -> Vec<usize> {
+        (0..times)
+            .map(|_| {
+                queue.advance().unwrap();
+                queue.current_index().unwrap()
+            })
+            .collect()
+    }
+
+    fn path(track: Option<&Track>) -> Option<&Path> {
+        track.map(Track::path)
+    }
+
+    fn paths(queue: &Queue) -> Vec<&str> {
+        queue
+            .tracks()
+            .iter()
+            .map(|t| t.path.to_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_queue_next_prev() {
+        let mut queue = queue();
+        queue.set_mode(PlaybackMode::Sequential);
+        assert_eq!(queue.current(), None);
+        assert_eq!(queue.prev_track(), None);
+
+        assert_eq!(path(queue.next_track()), Some(Path::new("a.flac")));
+        assert_eq!(path(queue.next_track()), Some(Path::new("b.flac")));
+        assert_eq!(path(queue.next_track()), Some(Path::new("c.flac")));
+        assert_eq!(queue.next_track(), None, "队尾没有下一首");
+        assert_eq!(queue.current_index(), Some(2));
+
+        assert_eq!(path(queue.prev_track()), Some(Path::new("b.flac")));
+        assert_eq!(path(queue.prev_track()), Some(Path::new("a.flac")));
+        assert_eq!(queue.prev_track(), None, "队首没有上一首");
+        assert_eq!(queue.current_index(), Some(0));
+    }
+
+    #[test]
+    fn test_queue_select() {
+        let mut queue = queue();
+        assert_eq!(path(queue.select(1)), Some(Path::new("b.flac")));
+        assert_eq!(queue.current_index(), Some(1));
+
+        assert_eq!(queue.select(10), None);
+        assert_eq!(queue.current_index(), Some(1), "越界选择不改变当前位置");
+    }
+
+    #[test]
+    fn test_queue_empty() {
+        let mut queue = Queue::new();
+        assert!(queue.is_empty());
+        assert_eq!(queue.next_track(), None);
+        assert_eq!(queue.current(), None);
+
+        queue.push(Track::new("a.flac"));
+        assert_eq!(queue.len(), 1);
+        assert_eq!(path(queue.next_track()), Some(Path::new("a.flac")));
+    }
+
+    #[test]
+    fn test_queue_remove() {
+        let mut queue = queue();
+        queue.select(1);
+
+        assert_eq!(queue.remove(0).unwrap().path, Path::new("a.flac"));
+        assert_eq!(queue.current_index(), Some(0), "当前曲目仍是 b.flac");
+        assert_eq!(queue.remove(1).unwrap().path, Path::new("c.flac"));
+        assert_eq!(queue.current_index(), Some(0));
+        assert_eq!(queue.remove(5), None);
+
+        queue.remove(0);
+        assert_eq!(queue.current(), None, "移除当前曲目后回到未播放状态");
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_queue_move_track() {
+        let mut queue = queue();
+        queue.select(0);
+
+        assert!(queue.move_track(0, 2));
+        assert_eq!(paths(&queue), vec!["b.flac", "c.flac", "a.flac"]);
+        assert_eq!(path(queue.current()), Some(Path::new("a.flac")));
+
+        assert!(queue.move_track(1, 0));
+        assert_eq!(paths(&queue), vec!["c.flac", "b.flac", "a.flac"]);
+        assert_eq!(queue.current_index(), Some(2));
+
+        queue.select(1);
+        assert!(queue.move_track(2, 0));
+        assert_eq!(paths(&queue), vec!["a.flac", "c.flac", "b.flac"]);
+        assert_eq!(path(queue.current()), Some(Path::new("b.flac")));
+
+        assert!(!queue.move_track(0, 3));
+    }
+
+    #[test]
+    fn test_queue_clear_and_duration() {
+        let mut queue = queue();
+        queue.push(Track {
+            duration: Some(Duration::from_secs(90)),
+            ..Track::new("d.flac")
+        });
+        queue.push(Track {
+            duration: Some(Duration::from_secs(30)),
+            ..Track::new("e.flac")
+        });
+        assert_eq!(queue.total_duration(), Duration::from_secs(120));
+
+        queue.select(2);
+        queue.clear();
+        assert!(queue.is_empty());
+        assert_eq!(queue.current(), None);
+        assert_eq!(queue.total_duration(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_queue_refresh() {
+        let mut queue = queue();
+        let changed = queue.refresh(|path| {
+            (path == Path::new("b.flac")).then(|| Track {
+                title: Some("B".to_string()),
+                ..Track::new(path)
+            })
+        });
+        assert_eq!(changed, [1]);
+        assert_eq!(queue.tracks()[1].title.as_deref(), Some("B"));
+        assert!(queue.refresh(|_| None).is_empty());
+    }
+
+    #[test]
+    fn test_queue_repeat_wraps() {
+        let mut queue = queue();
+        assert_eq!(queue.mode(), PlaybackMode::Repeat);
+        assert_eq!(advance(&mut queue, 4), vec![0, 1, 2, 0]);
+        assert_eq!(path(queue.prev_track()), Some(Path::new("c.flac")));
+    }
+
+    #[test]
+    fn test_queue_single_loops() {
+        let mut queue = queue();
+        queue.set_mode(PlaybackMode::Single);
+        queue.select(2);
+        assert_eq!(advance(&mut queue, 2), vec![2, 2], "播放结束后重复当前曲目");
+        assert_eq!(path(queue.next_track()), Some(Path::new("a.flac")));
+    }
+
+    #[test]
+    fn test_queue_sequential_stops_at_end() {
+        let mut queue = queue();
+        queue.set_mode(PlaybackMode::Sequential);
+        queue.select(2);
+        assert_eq!(queue.advance(), None);
+        assert_eq!(queue.current_index(), Some(2));
+    }
+
+    #[test]
+    fn test_queue_consume_removes_played() {
+        let mut queue = queue();
+        queue.set_mode(PlaybackMode::Consume);
+        assert_eq!(path(queue.advance()), Some(Path::new("a.flac")));
+        assert_eq!(path(queue.advance()), Some(Path::new("b.flac")));
+        assert_eq!(paths(&queue), vec!["b.flac", "c.flac"]);
+        assert_eq!(path(queue.advance()), Some(Path::new("c.flac")));
+        assert_eq!(queue.advance(), None);
+        assert!(queue.is_empty());
+        assert_eq!(queue.current(), None);
+    }
+
+    #[test]
+    fn test_queue_random_without_repeats() {
+        let mut queue = random_queue(8, 42);
+        let mut round = advance(&mut queue, 8);
+        let last = *round.last().unwrap();
+        round.sort();
+        assert_eq!(
+            round,
+            (0..8).collect::<Vec<_>>(),
+            "一轮中每首曲目只播放一次"
+        );
+
+        let next_round = advance(&mut queue, 8);
+        assert_ne!(next_round[0], last, "新一轮不会马上重复刚播放的曲目");
+
+        // 相同的种子得到相同的顺序
+        let mut a = random_queue(8, 7);
+        let mut b = random_queue(8, 7);
+        assert_eq!(advance(&mut a, 16), advance(&mut b, 16));
+    }
+
+    #[test]
+    fn test_queue_peek_advance() {
+        for mode in [
+            PlaybackMode::Repeat,
+            PlaybackMode::Consume,
+            PlaybackMode::Single,
+            PlaybackMode::Sequential,
+        ] {
+            let mut queue = queue();
+            queue.set_mode(mode);
+            for _ in 0..4 {
+                let peeked = queue.peek_advance().cloned();
+                assert_eq!(peeked.as_ref(), queue.advance(), "{mode:?}");
+            }
+        }
+
+        // 随机模式下一轮结束时还不知道新一轮的第一首
+        let mut queue = random_queue(3, 5);
+        for _ in 0..3 {
+            let peeked = queue.peek_advance().cloned();
+            assert_eq!(peeked.as_ref(), queue.advance());
+        }
+        assert_eq!(queue.peek_advance(), None);
+        assert!(Queue::new().peek_advance().is_none());
+    }
+
+    #[test]
+    fn test_queue_random_prev_follows_history() {
+        let mut queue = random_queue(5, 1);
+        let played = advance(&mut queue, 3);
+        queue.prev_track();
+        assert_eq!(queue.current_index(), Some(played[1]));
+        queue.prev_track();
+        assert_eq!(queue.current_index(), Some(played[0]));
+        assert_eq!(queue.prev_track(), None);
+        assert_eq!(advance(&mut queue, 2), played[1..]);
+    }
+
+    #[test]
+    fn test_queue_mode_change_keeps_current() {
+        let mut queue = Queue::with_seed(3);
+        queue.extend((0..6).map(|i| Track::new(format!("{i}.flac"))));
+        queue.select(4);
+
+        queue.set_mode(PlaybackMode::Random);
+        assert_eq!(queue.current_index(), Some(4));
+        let mut rest = advance(&mut queue, 5);
+        rest.sort();
+        assert_eq!(rest, vec![0, 1, 2, 3, 5], "当前曲目计为本轮已播放");
+
+        let current = queue.current_index();
+        queue.set_mode(PlaybackMode::Repeat);
+        assert_eq!(queue.current_index(), current);
+    }
+
+    #[test]
+    fn test_queue_random_tracks_edits() {
+        let mut queue = random_queue(5, 9);
+        let first = advance(&mut queue, 1)[0];
+        let name = |queue: &Queue, i: usize| queue.tracks()[i].path.clone();
+
+        // 选择、移动、删除和添加曲目后，本轮剩余的曲目仍然不重复且不包含已播放的曲目
+        let picked = (first + 1) % 5;
+        queue.select(picked);
+        let played = [name(&queue, first), name(&queue, picked)];
+        let removed = (first + 2) % 5;
+        let removed_path = name(&queue, removed);
+        queue.move_track(0, 4);
+        queue.remove(
+            queue
+                .tracks()
+                .iter()
+                .position(|t| t.path == removed_path)
+                .unwrap(),
+        );
+        queue.push(Track::new("new.flac"));
+
+        let remaining = queue.len() - 2;
+        let mut rest: Vec<_> = advance(&mut queue, remaining)
+            .into_iter()
+            .map(|i| name(&queue, i))
+            .collect();
+        rest.sort();
+        let mut expected: Vec<_> = queue
+            .tracks()
+            .iter()
+            .map(|t| t.path.clone())
+            .filter(|p| !played.contains(p))
+            .collect();
+        expected.sort();
+        assert_eq!(rest, expected);
+        assert!(rest.contains(&"new.flac".into()));
+    }
+}
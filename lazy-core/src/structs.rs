@@ -5,6 +5,8 @@ use ratatui::{
     widgets::Borders,
 };
 
+use crate::theme::TuiTheme;
+
 //////////////////////////////
 /// 标题样式
 //////////////////////////////
@@ -32,12 +34,13 @@ pub struct TitleStyle {
 
 impl Default for TitleStyle {
     fn default() -> Self {
+        let theme = TuiTheme::current();
         Self {
             text: Default::default(),
             alignment: Alignment::Center,
             modifier: Modifier::ITALIC,
-            fg: Color::Rgb(130, 170, 255), // 默认前景色 #82aaff
-            bg: Color::Rgb(34, 36, 54),    // 默认背景色 #222436
+            fg: theme.blue, // 默认前景色取自当前主题
+            bg: theme.bg,   // 默认背景色取自当前主题
         }
     }
 }
@@ -62,10 +65,11 @@ pub struct BorderStyle {
 
 impl Default for BorderStyle {
     fn default() -> Self {
+        let theme = TuiTheme::current();
         Self {
             border: Borders::ALL,
-            fg: Color::Rgb(130, 170, 255), // 默认前景色 #82aaff
-            bg: Color::Rgb(34, 36, 54),    // 默认背景色 #222436
+            fg: theme.blue, // 默认前景色取自当前主题
+            bg: theme.bg,   // 默认背景色取自当前主题
         }
     }
 }
@@ -94,9 +98,10 @@ pub struct TuiStyle {
 
 impl Default for TuiStyle {
     fn default() -> Self {
+        let theme = TuiTheme::current();
         Self {
-            fg: Color::Rgb(130, 170, 255), // 默认前景色 #82aaff
-            bg: Color::Rgb(34, 36, 54),    // 默认背景色 #222436
+            fg: theme.blue, // 默认前景色取自当前主题
+            bg: theme.bg,   // 默认背景色取自当前主题
             alignment: Alignment::Center,
             modifier: Modifier::ITALIC,
         }
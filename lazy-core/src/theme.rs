@@ -1,5 +1,21 @@
+//! 主题模块。
+//!
+//! `TuiTheme` 是一组命名的颜色（`fg`、`bg_highlight`、`blue` 等，名称见 `TuiTheme::SLOTS`），
+//! 各组件的默认样式都取自当前主题 `TuiTheme::current()`。运行时切换主题时，
+//! 组件通过 `HasTuiStyleSetter::apply_tui_theme` 等方法用新主题的颜色重新设置样式。
+//! 默认主题是 Tokyo Night Moon。
+
+use std::sync::RwLock;
+
 use ratatui::style::Color;
 
+use crate::color::ColorDepth;
+
+/// 当前主题，未设置时为默认主题
+static CURRENT: RwLock<Option<TuiTheme>> = RwLock::new(None);
+
+/// 主题的调色板。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TuiTheme {
     pub bg: Color,           // 背景主色
     pub bg_dark: Color,      // 背景深色
@@ -42,6 +58,7 @@ pub struct TuiTheme {
 }
 
 impl Default for TuiTheme {
+    /// Tokyo Night Moon 调色板。
     fn default() -> Self {
         Self {
             bg: Color::Rgb(34, 36, 54),           // #222436
@@ -85,3 +102,123 @@ impl Default for TuiTheme {
         }
     }
 }
+
+/// 为 `TuiTheme` 生成按名称访问颜色的方法，名称与字段名相同。
+macro_rules! slots {
+    ($($slot:ident),* $(,)?) => {
+        impl TuiTheme {
+            /// 所有颜色的名称，按字段顺序排列。
+            pub const SLOTS: &'static [&'static str] = &[$(stringify!($slot)),*];
+
+            /// 返回指定名称的颜色。
+            pub fn slot(&self, name: &str) -> Option<Color> {
+                match name {
+                    $(stringify!($slot) => Some(self.$slot),)*
+                    _ => None,
+                }
+            }
+
+            /// 返回指定名称的颜色的可变引用。
+            pub fn slot_mut(&mut self, name: &str) -> Option<&mut Color> {
+                match name {
+                    $(stringify!($slot) => Some(&mut self.$slot),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+slots!(
+    bg,
+    bg_dark,
+    bg_dark1,
+    bg_highlight,
+    fg,
+    fg_dark,
+    fg_gutter,
+    comment,
+    blue,
+    blue0,
+    blue1,
+    blue2,
+    blue5,
+    blue6,
+    blue7,
+    cyan,
+    green,
+    green1,
+    green2,
+    magenta,
+    magenta2,
+    orange,
+    purple,
+    red,
+    red1,
+    teal,
+    yellow,
+    dark3,
+    dark5,
+    terminal_black,
+);
+
+impl TuiTheme {
+    /// 返回当前主题。
+    pub fn current() -> Self {
+        CURRENT
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .unwrap_or_default()
+    }
+
+    /// 将所有颜色映射为指定颜色深度下最接近的颜色。
+    pub fn adapt(mut self, depth: ColorDepth) -> Self {
+        for slot in Self::SLOTS {
+            if let Some(color) = self.slot_mut(slot) {
+                *color = depth.adapt(*color);
+            }
+        }
+        self
+    }
+
+    /// 设置当前主题，之后创建的组件使用新主题的颜色。
+    pub fn set_current(theme: Self) {
+        *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = Some(theme);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_slots() {
+        let mut theme = TuiTheme::default();
+        assert_eq!(TuiTheme::SLOTS.len(), 30);
+        assert_eq!(theme.slot("bg_highlight"), Some(Color::Rgb(47, 51, 77)));
+        assert_eq!(theme.slot("terminal_black"), Some(theme.terminal_black));
+        assert_eq!(theme.slot("nope"), None);
+
+        *theme.slot_mut("blue").unwrap() = Color::Blue;
+        assert_eq!(theme.blue, Color::Blue);
+        for slot in TuiTheme::SLOTS {
+            assert!(theme.slot(slot).is_some(), "{slot}");
+        }
+    }
+
+    #[test]
+    fn test_theme_adapt() {
+        let theme = TuiTheme::default();
+        assert_eq!(theme.adapt(ColorDepth::TrueColor), theme);
+
+        let adapted = theme.adapt(ColorDepth::Ansi256);
+        assert_eq!(adapted.blue, Color::Indexed(111));
+        for slot in TuiTheme::SLOTS {
+            assert!(
+                matches!(adapted.slot(slot), Some(Color::Indexed(_))),
+                "{slot}"
+            );
+        }
+        assert_eq!(theme.adapt(ColorDepth::Ansi16).blue, Color::LightBlue);
+    }
+}
@@ -0,0 +1,91 @@
+//! 曲目元数据模块。
+//!
+//! `Track` 由媒体库扫描标签得到，播放队列和各个页面都通过它显示曲目信息。
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// 一首曲目的元数据，缺失的标签为 `None`。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Track {
+    /// 文件路径
+    pub path: PathBuf,
+    /// 标题
+    pub title: Option<String>,
+    /// 艺术家
+    pub artist: Option<String>,
+    /// 专辑艺术家
+    pub album_artist: Option<String>,
+    /// 专辑
+    pub album: Option<String>,
+    /// 音轨号
+    pub track_number: Option<u32>,
+    /// 碟号
+    pub disc_number: Option<u32>,
+    /// 年份
+    pub year: Option<i32>,
+    /// 流派
+    pub genre: Option<String>,
+    /// 时长
+    pub duration: Option<Duration>,
+}
+
+impl Track {
+    /// 创建一个只有路径、没有任何标签的曲目。
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    /// 返回文件路径。
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 返回用于显示的标题，没有标题标签时使用文件名。
+    pub fn display_title(&self) -> String {
+        match &self.title {
+            Some(title) => title.clone(),
+            None => self
+                .path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// 返回专辑艺术家，没有时退回到艺术家。
+    pub fn album_artist_or_artist(&self) -> Option<&str> {
+        self.album_artist.as_deref().or(self.artist.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_display_title() {
+        let mut track = Track::new("/music/01 - Intro.flac");
+        assert_eq!(track.display_title(), "01 - Intro");
+
+        track.title = Some("Intro".to_string());
+        assert_eq!(track.display_title(), "Intro");
+    }
+
+    #[test]
+    fn test_track_album_artist_fallback() {
+        let mut track = Track::new("a.mp3");
+        assert_eq!(track.album_artist_or_artist(), None);
+
+        track.artist = Some("Artist".to_string());
+        assert_eq!(track.album_artist_or_artist(), Some("Artist"));
+
+        track.album_artist = Some("Various Artists".to_string());
+        assert_eq!(track.album_artist_or_artist(), Some("Various Artists"));
+    }
+}
@@ -4,6 +4,8 @@ use ratatui::{
     widgets::Borders,
 };
 
+use crate::theme::TuiTheme;
+
 /// 提供标题样式信息
 pub trait HasTitleStyle {
     /// 获取标题的整体样式（颜色、修饰符等）
@@ -53,6 +55,12 @@ pub trait HasTitleStyleSetter {
 
     /// 设置标题背景色
     fn set_title_bg(&mut self, bg: Color);
+
+    /// 用主题的颜色设置标题，与 `TitleStyle::default` 的取色一致
+    fn apply_title_theme(&mut self, theme: &TuiTheme) {
+        self.set_title_fg(theme.blue);
+        self.set_title_bg(theme.bg);
+    }
 }
 
 /// 修改边框样式
@@ -68,6 +76,12 @@ pub trait HasBorderStyleSetter {
 
     /// 切换边框
     fn toggle_border(&mut self);
+
+    /// 用主题的颜色设置边框，与 `BorderStyle::default` 的取色一致
+    fn apply_border_theme(&mut self, theme: &TuiTheme) {
+        self.set_border_fg(theme.blue);
+        self.set_border_bg(theme.bg);
+    }
 }
 
 /// 修改整体 TUI 样式
@@ -83,4 +97,10 @@ pub trait HasTuiStyleSetter {
 
     /// 设置修饰符（加粗、斜体等）
     fn set_tui_modifier(&mut self, modifier: Modifier);
+
+    /// 用主题的颜色设置组件，与 `TuiStyle::default` 的取色一致
+    fn apply_tui_theme(&mut self, theme: &TuiTheme) {
+        self.set_tui_fg(theme.blue);
+        self.set_tui_bg(theme.bg);
+    }
 }
@@ -0,0 +1,110 @@
+//! 该模块定义了在播放器引擎与 TUI 之间共享的核心数据类型。
+
+use std::{borrow::Cow, path::PathBuf};
+
+/// 播放状态枚举
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlaybackState {
+    Playing,
+    Paused,
+    #[default]
+    Stopped,
+}
+
+/// 定义了不同的播放模式，由播放队列在切换曲目时执行。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlaybackMode {
+    /// **列表循环**: 播放完列表最后一首后，从第一首开始继续播放。
+    #[default]
+    Repeat,
+    /// **随机播放**: 按打乱的顺序播放，一轮中每首曲目只播放一次，全部播放完后重新打乱。
+    Random,
+    /// **消费模式**: 播放过的曲目将从列表中移除。
+    Consume,
+    /// **单曲循环**: 单独重复播放当前曲目。
+    Single,
+    /// **顺序播放**: 按列表顺序播放，播放完最后一首后停止。
+    Sequential,
+}
+
+impl PlaybackMode {
+    /// 包含所有播放模式的常量数组，用于迭代。
+    pub const VARIANTS: &'static [PlaybackMode] = &[
+        PlaybackMode::Repeat,
+        PlaybackMode::Random,
+        PlaybackMode::Consume,
+        PlaybackMode::Single,
+        PlaybackMode::Sequential,
+    ];
+
+    /// 切换到下一个播放模式。
+    /// 这是一个循环切换，例如 `Repeat` -> `Random` -> `Consume` -> `Single` -> `Sequential` -> `Repeat`。
+    pub fn next(self) -> Self {
+        match self {
+            PlaybackMode::Repeat => PlaybackMode::Random,
+            PlaybackMode::Random => PlaybackMode::Consume,
+            PlaybackMode::Consume => PlaybackMode::Single,
+            PlaybackMode::Single => PlaybackMode::Sequential,
+            PlaybackMode::Sequential => PlaybackMode::Repeat,
+        }
+    }
+}
+
+/// 目录浏览中条目的类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    /// 子目录
+    Directory,
+    /// 可以播放的音频文件
+    Audio,
+    /// 不受支持的文件
+    Unsupported,
+}
+
+/// 目录浏览中的一个条目。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirEntry {
+    /// 条目路径
+    pub path: PathBuf,
+    /// 条目类型
+    pub kind: EntryKind,
+}
+
+impl DirEntry {
+    /// 创建一个目录条目。
+    pub fn new(path: impl Into<PathBuf>, kind: EntryKind) -> Self {
+        Self {
+            path: path.into(),
+            kind,
+        }
+    }
+
+    /// 返回条目的显示名称（文件名），没有文件名时（例如根目录）返回完整路径。
+    pub fn name(&self) -> Cow<'_, str> {
+        match self.path.file_name() {
+            Some(name) => name.to_string_lossy(),
+            None => self.path.to_string_lossy(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_playback_mode_next() {
+        assert_eq!(PlaybackMode::Repeat.next(), PlaybackMode::Random);
+        assert_eq!(PlaybackMode::Random.next(), PlaybackMode::Consume);
+        assert_eq!(PlaybackMode::Consume.next(), PlaybackMode::Single);
+        assert_eq!(PlaybackMode::Single.next(), PlaybackMode::Sequential);
+        assert_eq!(PlaybackMode::Sequential.next(), PlaybackMode::Repeat);
+    }
+
+    #[test]
+    fn test_dir_entry_name() {
+        let entry = DirEntry::new("/music/Album/01.flac", EntryKind::Audio);
+        assert_eq!(entry.name(), "01.flac");
+        assert_eq!(DirEntry::new("/", EntryKind::Directory).name(), "/");
+    }
+}
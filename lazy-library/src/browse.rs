@@ -0,0 +1,193 @@
+//! 目录浏览模块。
+//!
+//! 为目录页列出音乐目录中的子目录和文件，并按自然顺序排序（`2` 排在 `10` 之前），
+//! 同时提供递归收集目录下所有音频文件的函数，用于把整个目录加入播放队列。
+
+use std::{
+    cmp::Ordering,
+    fs,
+    iter::Peekable,
+    path::{Path, PathBuf},
+    str::Chars,
+};
+
+use lazy_core::types::{DirEntry, EntryKind};
+
+use crate::{error::LibraryError, tags::is_supported};
+
+/// 列出目录中的条目，隐藏文件（以 `.` 开头）会被忽略。
+///
+/// 子目录排在文件之前，同类条目按文件名的自然顺序排序。
+pub fn list_dir(path: &Path) -> Result<Vec<DirEntry>, LibraryError> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        // 跟随符号链接判断类型，指向目录的链接也可以展开
+        let kind = if path.is_dir() {
+            EntryKind::Directory
+        } else if is_supported(&path) {
+            EntryKind::Audio
+        } else {
+            EntryKind::Unsupported
+        };
+        entries.push(DirEntry::new(path, kind));
+    }
+    entries.sort_by(entry_order);
+    Ok(entries)
+}
+
+/// 按目录页的顺序递归收集路径下的所有音频文件。
+///
+/// `path` 是音频文件时只返回它本身；无法读取的子目录会被跳过。
+pub fn audio_files(path: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect_audio_files(path, &mut files);
+    files
+}
+
+/// 递归收集音频文件。
+fn collect_audio_files(path: &Path, files: &mut Vec<PathBuf>) {
+    if !path.is_dir() {
+        if is_supported(path) {
+            files.push(path.to_path_buf());
+        }
+        return;
+    }
+    for entry in list_dir(path).unwrap_or_default() {
+        match entry.kind {
+            EntryKind::Directory => collect_audio_files(&entry.path, files),
+            EntryKind::Audio => files.push(entry.path),
+            EntryKind::Unsupported => (),
+        }
+    }
+}
+
+/// 目录页中条目的顺序：子目录在前，其余按名称自然排序。
+fn entry_order(a: &DirEntry, b: &DirEntry) -> Ordering {
+    let is_file = |e: &DirEntry| e.kind != EntryKind::Directory;
+    is_file(a)
+        .cmp(&is_file(b))
+        .then_with(|| natural_cmp(&a.name(), &b.name()))
+}
+
+/// 自然顺序比较两个字符串：连续的数字按数值比较，其余字符忽略大小写比较。
+///
+/// 例如 `track 2` 排在 `track 10` 之前。两者自然顺序相同时（例如只有大小写不同），
+/// 再按原始字符串比较，保证结果稳定。
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a_chars, mut b_chars) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        let ordering = match (a_chars.peek().copied(), b_chars.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                compare_numbers(&take_digits(&mut a_chars), &take_digits(&mut b_chars))
+            }
+            (Some(x), Some(y)) => {
+                a_chars.next();
+                b_chars.next();
+                x.to_lowercase().cmp(y.to_lowercase())
+            }
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// 取出开头的一段连续数字。
+fn take_digits(chars: &mut Peekable<Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        digits.push(c);
+    }
+    digits
+}
+
+/// 按数值比较两段数字，不受长度限制（忽略前导零）。
+fn compare_numbers(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.trim_start_matches('0'), b.trim_start_matches('0'));
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_natural_cmp() {
+        let mut names = vec![
+            "Track 10.flac",
+            "track 2.flac",
+            "Track 1.flac",
+            "cover.jpg",
+            "Track 02b.flac",
+            "99999999999999999999999.mp3",
+            "100000000000000000000000.mp3",
+        ];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            names,
+            vec![
+                "99999999999999999999999.mp3",
+                "100000000000000000000000.mp3",
+                "cover.jpg",
+                "Track 1.flac",
+                "track 2.flac",
+                "Track 02b.flac",
+                "Track 10.flac",
+            ]
+        );
+        assert_eq!(natural_cmp("a", "A"), "a".cmp("A"));
+    }
+
+    #[test]
+    fn test_list_dir_and_audio_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for path in [
+            "Disc 10/01.flac",
+            "Disc 2/10.mp3",
+            "Disc 2/9.mp3",
+            "Disc 2/notes.txt",
+            "02.ogg",
+            "cover.jpg",
+            ".hidden.mp3",
+        ] {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"").unwrap();
+        }
+
+        let entries = list_dir(root).unwrap();
+        let listed: Vec<_> = entries.iter().map(|e| (e.name(), e.kind)).collect();
+        assert_eq!(
+            listed,
+            vec![
+                ("Disc 2".into(), EntryKind::Directory),
+                ("Disc 10".into(), EntryKind::Directory),
+                ("02.ogg".into(), EntryKind::Audio),
+                ("cover.jpg".into(), EntryKind::Unsupported),
+            ]
+        );
+
+        let files: Vec<_> = audio_files(root)
+            .into_iter()
+            .map(|p| p.strip_prefix(root).unwrap().to_path_buf())
+            .collect();
+        assert_eq!(
+            files,
+            ["Disc 2/9.mp3", "Disc 2/10.mp3", "Disc 10/01.flac", "02.ogg"]
+                .map(PathBuf::from)
+                .to_vec()
+        );
+        assert_eq!(audio_files(&root.join("02.ogg")), vec![root.join("02.ogg")]);
+        assert!(audio_files(&root.join("cover.jpg")).is_empty());
+        assert!(list_dir(&root.join("missing")).is_err());
+    }
+}
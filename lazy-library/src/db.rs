@@ -0,0 +1,521 @@
+//! 持久化的媒体库数据库模块。
+//!
+//! `LibraryDb` 将媒体库保存在 SQLite 数据库中，以文件路径为主键，并记录文件的修改时间和大小。
+//! 启动时直接从数据库加载索引；重新扫描时只读取新增或发生变化的文件，并删除已不存在的文件。
+
+use std::{
+    collections::{HashMap, HashSet},
+    env, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use lazy_core::track::Track;
+use rusqlite::{Connection, Row, Transaction, params};
+
+use crate::{
+    error::LibraryError,
+    index::{Library, Query, track_order},
+    scanner::{FileStamp, ScanError, Scanner, scan_file},
+};
+
+/// 数据库结构
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS tracks (
+    path         BLOB PRIMARY KEY NOT NULL,
+    mtime        INTEGER NOT NULL,
+    size         INTEGER NOT NULL,
+    title        TEXT,
+    artist       TEXT,
+    album_artist TEXT,
+    album        TEXT,
+    track_number INTEGER,
+    disc_number  INTEGER,
+    year         INTEGER,
+    genre        TEXT,
+    duration_ms  INTEGER
+);
+CREATE INDEX IF NOT EXISTS tracks_artist ON tracks (artist);
+CREATE INDEX IF NOT EXISTS tracks_album_artist ON tracks (COALESCE(album_artist, artist));
+CREATE INDEX IF NOT EXISTS tracks_album ON tracks (album);
+CREATE INDEX IF NOT EXISTS tracks_genre ON tracks (genre);
+CREATE INDEX IF NOT EXISTS tracks_year ON tracks (year);
+";
+
+/// 查询曲目时选择的列，顺序与 `track_from_row` 一致
+const TRACK_COLUMNS: &str = "path, title, artist, album_artist, album, \
+                             track_number, disc_number, year, genre, duration_ms";
+
+/// 返回默认的数据库路径：`$XDG_DATA_HOME/lazymusic/library.db`，
+/// 未设置时为 `~/.local/share/lazymusic/library.db`。
+pub fn default_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("library.db"))
+}
+
+/// 返回程序的数据目录：`$XDG_DATA_HOME/lazymusic`，未设置时为 `~/.local/share/lazymusic`。
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+    Some(data_home.join("lazymusic"))
+}
+
+/// 一次增量扫描的结果。
+#[derive(Debug, Default)]
+pub struct RescanReport {
+    /// 新增的曲目
+    pub added: Vec<Track>,
+    /// 文件发生变化、重新读取了标签的曲目
+    pub updated: Vec<Track>,
+    /// 已从磁盘删除（或变得无法读取）的曲目或目录
+    pub removed: Vec<PathBuf>,
+    /// 未发生变化而跳过的文件数
+    pub unchanged: usize,
+    /// 读取失败的文件或目录
+    pub errors: Vec<ScanError>,
+}
+
+impl RescanReport {
+    /// 将扫描结果合并到内存中的媒体库，返回扫描过程中的错误。
+    pub fn apply_to(self, library: &mut Library) -> Vec<ScanError> {
+        for path in &self.removed {
+            library.remove_under(path);
+        }
+        library.extend(self.added);
+        library.extend(self.updated);
+        self.errors
+    }
+}
+
+/// 媒体库数据库。
+pub struct LibraryDb {
+    /// SQLite 连接
+    conn: Connection,
+}
+
+impl LibraryDb {
+    /// 打开（必要时创建）指定路径的数据库。
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, LibraryError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)?;
+        // WAL 模式允许后台扫描写入的同时读取
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        Self::init(conn)
+    }
+
+    /// 打开一个内存数据库，主要用于测试。
+    pub fn open_in_memory() -> Result<Self, LibraryError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    /// 创建表结构。
+    fn init(conn: Connection) -> Result<Self, LibraryError> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// 返回数据库中的曲目数量。
+    pub fn len(&self) -> Result<usize, LibraryError> {
+        let count: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM tracks", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// 判断数据库是否为空。
+    pub fn is_empty(&self) -> Result<bool, LibraryError> {
+        Ok(self.len()? == 0)
+    }
+
+    /// 将整个数据库加载为内存中的媒体库。
+    pub fn load(&self) -> Result<Library, LibraryError> {
+        let sql = format!("SELECT {TRACK_COLUMNS} FROM tracks");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let tracks = stmt
+            .query_map([], track_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Library::from_tracks(tracks))
+    }
+
+    /// 按查询条件从数据库中查找曲目，结果排序与 `Library::query` 一致。
+    pub fn query(&self, query: &Query) -> Result<Vec<Track>, LibraryError> {
+        let (condition, value): (&str, rusqlite::types::Value) = match query {
+            Query::Artist(artist) => ("artist = ?1", artist.clone().into()),
+            Query::AlbumArtist(album_artist) => (
+                "COALESCE(album_artist, artist) = ?1",
+                album_artist.clone().into(),
+            ),
+            Query::Album(album) => ("album = ?1", album.clone().into()),
+            Query::Genre(genre) => ("genre = ?1", genre.clone().into()),
+            Query::Years(years) => {
+                let sql =
+                    format!("SELECT {TRACK_COLUMNS} FROM tracks WHERE year BETWEEN ?1 AND ?2");
+                let mut stmt = self.conn.prepare(&sql)?;
+                let tracks = stmt
+                    .query_map(params![years.start(), years.end()], track_from_row)?
+                    .collect::<Result<Vec<_>, _>>()?;
+                return Ok(sorted(tracks));
+            }
+            // 字节序与路径的组件顺序不完全一致，先按字节前缀筛选，再按路径组件精确过滤
+            Query::PathPrefix(prefix) => (
+                "substr(path, 1, length(?1)) = ?1",
+                path_to_bytes(prefix).into(),
+            ),
+        };
+
+        let sql = format!("SELECT {TRACK_COLUMNS} FROM tracks WHERE {condition}");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut tracks = stmt
+            .query_map([value], track_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Query::PathPrefix(prefix) = query {
+            tracks.retain(|track| track.path.starts_with(prefix));
+        }
+        Ok(sorted(tracks))
+    }
+
+    /// 增量扫描：只读取新增或修改时间、大小发生变化的文件，并删除已不存在的文件。
+    ///
+    /// 无法访问的目录下的曲目会被保留，避免音乐目录暂时未挂载时清空媒体库。
+    pub fn rescan(&mut self, scanner: &Scanner) -> Result<RescanReport, LibraryError> {
+        self.sync(scanner, &[])
+    }
+
+    /// 只刷新指定的文件或目录，用于响应文件系统事件。
+    ///
+    /// 仍然存在的路径按增量扫描处理；已不存在的路径连同其下的所有曲目一起删除。
+    pub fn refresh(&mut self, paths: &[PathBuf]) -> Result<RescanReport, LibraryError> {
+        let (existing, missing): (Vec<_>, Vec<_>) =
+            paths.iter().cloned().partition(|path| path.exists());
+        self.sync(&Scanner::new(existing), &missing)
+    }
+
+    /// 将扫描器看到的文件同步到数据库，并删除 `missing` 下的所有曲目。
+    fn sync(
+        &mut self,
+        scanner: &Scanner,
+        missing: &[PathBuf],
+    ) -> Result<RescanReport, LibraryError> {
+        let (files, errors) = scanner.files();
+        let stored = self.stamps()?;
+        let mut report = RescanReport {
+            errors,
+            ..Default::default()
+        };
+
+        let tx = self.conn.transaction()?;
+        let mut seen = HashSet::with_capacity(files.len());
+        for file in &files {
+            seen.insert(file.path.as_path());
+            let existing = stored.get(&file.path);
+            if existing == Some(&(file.mtime, file.size)) {
+                report.unchanged += 1;
+                continue;
+            }
+
+            match scan_file(&file.path) {
+                Ok(track) => {
+                    upsert(&tx, &track, file)?;
+                    match existing {
+                        Some(_) => report.updated.push(track),
+                        None => report.added.push(track),
+                    }
+                }
+                Err(error) => {
+                    // 修改后无法读取的文件从数据库中移除，下次扫描时重试
+                    if existing.is_some() {
+                        delete(&tx, &file.path)?;
+                        report.removed.push(file.path.clone());
+                    }
+                    report.errors.push(error);
+                }
+            }
+        }
+
+        let unreachable: Vec<_> = report.errors.iter().map(|e| e.path.clone()).collect();
+        for path in stored.keys() {
+            let in_roots = scanner.roots().iter().any(|root| path.starts_with(root));
+            let is_unreachable = unreachable.iter().any(|dir| path.starts_with(dir));
+            let is_missing = missing.iter().any(|prefix| path.starts_with(prefix));
+            if is_missing || (in_roots && !is_unreachable && !seen.contains(path.as_path())) {
+                delete(&tx, path)?;
+                report.removed.push(path.clone());
+            }
+        }
+        tx.commit()?;
+
+        Ok(report)
+    }
+
+    /// 读取所有曲目的修改时间和大小。
+    fn stamps(&self) -> Result<HashMap<PathBuf, (i64, u64)>, LibraryError> {
+        let mut stmt = self.conn.prepare("SELECT path, mtime, size FROM tracks")?;
+        let stamps = stmt
+            .query_map([], |row| {
+                let path: Vec<u8> = row.get(0)?;
+                let size: i64 = row.get(2)?;
+                Ok((path_from_bytes(path), (row.get(1)?, size as u64)))
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(stamps)
+    }
+}
+
+/// 插入或更新一首曲目。
+fn upsert(tx: &Transaction, track: &Track, stamp: &FileStamp) -> Result<(), LibraryError> {
+    tx.execute(
+        "INSERT OR REPLACE INTO tracks (path, mtime, size, title, artist, album_artist, album, \
+         track_number, disc_number, year, genre, duration_ms) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            path_to_bytes(&track.path),
+            stamp.mtime,
+            stamp.size as i64,
+            track.title,
+            track.artist,
+            track.album_artist,
+            track.album,
+            track.track_number,
+            track.disc_number,
+            track.year,
+            track.genre,
+            track.duration.map(|d| d.as_millis() as i64),
+        ],
+    )?;
+    Ok(())
+}
+
+/// 删除一首曲目。
+fn delete(tx: &Transaction, path: &Path) -> Result<(), LibraryError> {
+    tx.execute("DELETE FROM tracks WHERE path = ?1", [path_to_bytes(path)])?;
+    Ok(())
+}
+
+/// 从查询结果的一行构造曲目。
+fn track_from_row(row: &Row) -> rusqlite::Result<Track> {
+    let path: Vec<u8> = row.get(0)?;
+    let duration_ms: Option<i64> = row.get(9)?;
+    Ok(Track {
+        path: path_from_bytes(path),
+        title: row.get(1)?,
+        artist: row.get(2)?,
+        album_artist: row.get(3)?,
+        album: row.get(4)?,
+        track_number: row.get(5)?,
+        disc_number: row.get(6)?,
+        year: row.get(7)?,
+        genre: row.get(8)?,
+        duration: duration_ms.map(|ms| Duration::from_millis(ms as u64)),
+    })
+}
+
+/// 按默认顺序排序查询结果。
+fn sorted(mut tracks: Vec<Track>) -> Vec<Track> {
+    tracks.sort_by(track_order);
+    tracks
+}
+
+/// 将路径转换为字节，保证非 UTF-8 路径也能无损保存。
+#[cfg(unix)]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+/// 将字节转换回路径。
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+    PathBuf::from(std::ffi::OsString::from_vec(bytes))
+}
+
+/// 将路径转换为字节。
+#[cfg(not(unix))]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().into_owned().into_bytes()
+}
+
+/// 将字节转换回路径。
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::wav_bytes;
+
+    /// 写入一个带标题标签的 WAV 文件。
+    fn write_track(path: &Path, title: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, wav_bytes(8_000, 800, &[(b"INAM", title)])).unwrap();
+    }
+
+    #[test]
+    fn test_db_rescan_is_incremental() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("music");
+        write_track(&root.join("a/01.wav"), "One");
+        write_track(&root.join("a/02.wav"), "Two");
+        let scanner = Scanner::new([&root]);
+
+        let mut db = LibraryDb::open(dir.path().join("db/library.db")).unwrap();
+        let report = db.rescan(&scanner).unwrap();
+        assert_eq!(report.added.len(), 2);
+        assert_eq!(report.unchanged, 0);
+
+        // 没有变化时不重新读取任何文件
+        let report = db.rescan(&scanner).unwrap();
+        assert!(report.added.is_empty() && report.updated.is_empty());
+        assert_eq!(report.unchanged, 2);
+
+        // 修改、新增和删除文件
+        fs::write(
+            root.join("a/01.wav"),
+            wav_bytes(8_000, 1_600, &[(b"INAM", "One (Remix)")]),
+        )
+        .unwrap();
+        write_track(&root.join("b/01.wav"), "Three");
+        fs::remove_file(root.join("a/02.wav")).unwrap();
+
+        let report = db.rescan(&scanner).unwrap();
+        assert_eq!(report.added.len(), 1);
+        assert_eq!(report.updated.len(), 1);
+        assert_eq!(report.updated[0].title.as_deref(), Some("One (Remix)"));
+        assert_eq!(report.removed, vec![root.join("a/02.wav")]);
+        assert_eq!(report.unchanged, 0);
+        assert_eq!(db.len().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_db_load_persists_between_opens() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("music");
+        write_track(&root.join("01.wav"), "Persisted");
+        let db_path = dir.path().join("library.db");
+
+        let mut library = Library::new();
+        let report = LibraryDb::open(&db_path)
+            .unwrap()
+            .rescan(&Scanner::new([&root]))
+            .unwrap();
+        assert!(report.apply_to(&mut library).is_empty());
+
+        let loaded = LibraryDb::open(&db_path).unwrap().load().unwrap();
+        assert_eq!(loaded, library);
+        let track = loaded.get(&root.join("01.wav")).unwrap();
+        assert_eq!(track.title.as_deref(), Some("Persisted"));
+        assert_eq!(track.duration, Some(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn test_db_keeps_tracks_of_unreachable_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("music");
+        write_track(&root.join("01.wav"), "Kept");
+
+        let mut db = LibraryDb::open_in_memory().unwrap();
+        db.rescan(&Scanner::new([&root])).unwrap();
+
+        // 音乐目录暂时不可用（例如未挂载）时不删除已有记录
+        fs::rename(&root, dir.path().join("moved")).unwrap();
+        let report = db.rescan(&Scanner::new([&root])).unwrap();
+        assert!(report.removed.is_empty());
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(db.len().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_db_refresh_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("music");
+        write_track(&root.join("old/01.wav"), "One");
+        write_track(&root.join("old/02.wav"), "Two");
+        write_track(&root.join("other/01.wav"), "Other");
+
+        let mut db = LibraryDb::open_in_memory().unwrap();
+        db.rescan(&Scanner::new([&root])).unwrap();
+
+        // 重命名专辑目录：旧路径下的曲目被删除，新路径下的曲目被添加
+        fs::rename(root.join("old"), root.join("new")).unwrap();
+        let report = db.refresh(&[root.join("old"), root.join("new")]).unwrap();
+        assert_eq!(report.removed.len(), 2);
+        assert_eq!(report.added.len(), 2);
+
+        let library = db.load().unwrap();
+        assert!(library.under(&root.join("old")).is_empty());
+        assert_eq!(library.under(&root.join("new")).len(), 2);
+        assert_eq!(library.len(), 3);
+    }
+
+    #[test]
+    fn test_db_query() {
+        let mut db = LibraryDb::open_in_memory().unwrap();
+        let tracks = [
+            Track {
+                artist: Some("A".to_string()),
+                album: Some("X".to_string()),
+                track_number: Some(2),
+                year: Some(1995),
+                genre: Some("Rock".to_string()),
+                ..Track::new("/music/a/02.flac")
+            },
+            Track {
+                artist: Some("A".to_string()),
+                album: Some("X".to_string()),
+                track_number: Some(1),
+                year: Some(1995),
+                ..Track::new("/music/a/01.flac")
+            },
+            Track {
+                artist: Some("B".to_string()),
+                album_artist: Some("Various".to_string()),
+                album: Some("Y".to_string()),
+                year: Some(2001),
+                ..Track::new("/music/ab/01.flac")
+            },
+        ];
+        let tx = db.conn.transaction().unwrap();
+        for track in &tracks {
+            let stamp = FileStamp {
+                path: track.path.clone(),
+                mtime: 0,
+                size: 0,
+            };
+            upsert(&tx, track, &stamp).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let library = db.load().unwrap();
+        let queries = [
+            Query::Artist("A".to_string()),
+            Query::AlbumArtist("Various".to_string()),
+            Query::AlbumArtist("A".to_string()),
+            Query::Album("X".to_string()),
+            Query::Genre("Rock".to_string()),
+            Query::Years(1990..=1999),
+            Query::PathPrefix("/music/a".into()),
+        ];
+        for query in &queries {
+            let expected: Vec<_> = library.query(query).into_iter().cloned().collect();
+            assert_eq!(db.query(query).unwrap(), expected, "{query:?}");
+        }
+        assert_eq!(
+            db.query(&Query::PathPrefix("/music/a".into()))
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            db.query(&Query::AlbumArtist("A".to_string()))
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
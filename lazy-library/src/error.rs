@@ -0,0 +1,63 @@
+//! 媒体库的错误类型。
+
+use std::{error::Error, fmt, io};
+
+use symphonia::core::errors::Error as SymphoniaError;
+
+/// 扫描或读取标签时可能产生的错误。
+#[derive(Debug)]
+pub enum LibraryError {
+    /// 读取文件或目录时发生的 I/O 错误
+    Io(io::Error),
+    /// 文件格式或编码不受支持
+    Unsupported(String),
+    /// 标签或容器数据损坏，无法读取
+    Tag(String),
+    /// 媒体库数据库错误
+    Database(String),
+    /// 播放列表无法解析，或播放列表的名称无效
+    Playlist(String),
+}
+
+impl fmt::Display for LibraryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LibraryError::Io(e) => write!(f, "I/O error: {e}"),
+            LibraryError::Unsupported(msg) => write!(f, "unsupported format: {msg}"),
+            LibraryError::Tag(msg) => write!(f, "unreadable tags: {msg}"),
+            LibraryError::Database(msg) => write!(f, "library database error: {msg}"),
+            LibraryError::Playlist(msg) => write!(f, "playlist error: {msg}"),
+        }
+    }
+}
+
+impl Error for LibraryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LibraryError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LibraryError {
+    fn from(e: io::Error) -> Self {
+        LibraryError::Io(e)
+    }
+}
+
+impl From<SymphoniaError> for LibraryError {
+    fn from(e: SymphoniaError) -> Self {
+        match e {
+            SymphoniaError::IoError(e) => LibraryError::Io(e),
+            SymphoniaError::Unsupported(msg) => LibraryError::Unsupported(msg.to_string()),
+            e => LibraryError::Tag(e.to_string()),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for LibraryError {
+    fn from(e: rusqlite::Error) -> Self {
+        LibraryError::Database(e.to_string())
+    }
+}
@@ -0,0 +1,475 @@
+//! 内存中的媒体库索引模块。
+//!
+//! `Library` 以文件路径为键保存所有曲目，并提供按艺术家、专辑艺术家、专辑、
+//! 流派、年份和目录查询的接口。查询结果按专辑、碟号、音轨号排序。
+//! `summarize_albums` 将一组曲目汇总为专辑（年份、曲目数、总时长），供各个浏览页面使用。
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use lazy_core::track::Track;
+
+use crate::browse::natural_cmp;
+
+/// 媒体库查询条件。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Query {
+    /// 指定艺术家
+    Artist(String),
+    /// 指定专辑艺术家（没有专辑艺术家标签时匹配艺术家）
+    AlbumArtist(String),
+    /// 指定专辑
+    Album(String),
+    /// 指定流派
+    Genre(String),
+    /// 年份范围
+    Years(RangeInclusive<i32>),
+    /// 指定目录下（包括子目录）
+    PathPrefix(PathBuf),
+}
+
+/// 专辑的汇总信息。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Album {
+    /// 专辑名，没有专辑标签的曲目汇总到名称为 `None` 的专辑中
+    pub title: Option<String>,
+    /// 专辑艺术家（没有专辑艺术家标签时为艺术家）
+    pub artist: Option<String>,
+    /// 发行年份（专辑中最早的年份）
+    pub year: Option<i32>,
+    /// 专辑中曲目的路径，按碟号、音轨号排序
+    pub tracks: Vec<PathBuf>,
+    /// 专辑总时长，时长未知的曲目不计入
+    pub duration: Duration,
+}
+
+impl Album {
+    /// 返回专辑中的曲目数量。
+    pub fn track_count(&self) -> usize {
+        self.tracks.len()
+    }
+}
+
+/// 专辑列表的排序方式。
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AlbumSort {
+    /// 按专辑名的自然顺序
+    #[default]
+    Title,
+    /// 按年份，年份相同时按专辑名
+    Year,
+    /// 按曲目数量从多到少
+    TrackCount,
+    /// 按总时长从长到短
+    Duration,
+}
+
+impl AlbumSort {
+    /// 切换到下一种排序方式。
+    pub fn next(self) -> Self {
+        match self {
+            AlbumSort::Title => AlbumSort::Year,
+            AlbumSort::Year => AlbumSort::TrackCount,
+            AlbumSort::TrackCount => AlbumSort::Duration,
+            AlbumSort::Duration => AlbumSort::Title,
+        }
+    }
+
+    /// 按排序方式排序专辑列表。
+    pub fn sort(self, albums: &mut [Album]) {
+        let by_title = |a: &Album, b: &Album| {
+            natural_cmp(
+                a.title.as_deref().unwrap_or_default(),
+                b.title.as_deref().unwrap_or_default(),
+            )
+        };
+        albums.sort_by(|a, b| {
+            let ordering = match self {
+                AlbumSort::Title => Ordering::Equal,
+                AlbumSort::Year => a.year.cmp(&b.year),
+                AlbumSort::TrackCount => b.track_count().cmp(&a.track_count()),
+                AlbumSort::Duration => b.duration.cmp(&a.duration),
+            };
+            ordering.then_with(|| by_title(a, b))
+        });
+    }
+}
+
+/// 将曲目按专辑汇总，返回按专辑名排序的专辑列表。
+///
+/// 专辑由专辑名和专辑艺术家共同确定：不同艺术家的同名专辑会被分开，
+/// 而带有相同专辑艺术家标签的合辑即使曲目艺术家不同也会汇总为一张专辑。
+pub fn summarize_albums<'a>(tracks: impl IntoIterator<Item = &'a Track>) -> Vec<Album> {
+    let mut albums: BTreeMap<(Option<&str>, Option<&str>), Vec<&Track>> = BTreeMap::new();
+    for track in tracks {
+        let key = (track.album.as_deref(), track.album_artist_or_artist());
+        albums.entry(key).or_default().push(track);
+    }
+
+    let mut albums: Vec<_> = albums
+        .into_iter()
+        .map(|((title, artist), mut tracks)| {
+            tracks.sort_by(|a, b| track_order(a, b));
+            Album {
+                title: title.map(str::to_string),
+                artist: artist.map(str::to_string),
+                year: tracks.iter().filter_map(|t| t.year).min(),
+                duration: tracks.iter().filter_map(|t| t.duration).sum(),
+                tracks: tracks.into_iter().map(|t| t.path.clone()).collect(),
+            }
+        })
+        .collect();
+    AlbumSort::Title.sort(&mut albums);
+    albums
+}
+
+/// 媒体库索引。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Library {
+    /// 以路径为键的曲目表
+    tracks: BTreeMap<PathBuf, Track>,
+}
+
+impl Library {
+    /// 创建一个空的媒体库。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 用一组曲目创建媒体库。
+    pub fn from_tracks(tracks: impl IntoIterator<Item = Track>) -> Self {
+        let mut library = Self::new();
+        library.extend(tracks);
+        library
+    }
+
+    /// 添加或替换一首曲目，返回被替换的旧曲目。
+    pub fn insert(&mut self, track: Track) -> Option<Track> {
+        self.tracks.insert(track.path.clone(), track)
+    }
+
+    /// 添加或替换多首曲目。
+    pub fn extend(&mut self, tracks: impl IntoIterator<Item = Track>) {
+        for track in tracks {
+            self.insert(track);
+        }
+    }
+
+    /// 移除一首曲目。
+    pub fn remove(&mut self, path: &Path) -> Option<Track> {
+        self.tracks.remove(path)
+    }
+
+    /// 移除指定路径（文件或目录）下的所有曲目，返回移除的数量。
+    pub fn remove_under(&mut self, prefix: &Path) -> usize {
+        let paths: Vec<_> = self
+            .tracks
+            .range(prefix.to_path_buf()..)
+            .take_while(|(path, _)| path.starts_with(prefix))
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in &paths {
+            self.tracks.remove(path);
+        }
+        paths.len()
+    }
+
+    /// 按路径查找曲目。
+    pub fn get(&self, path: &Path) -> Option<&Track> {
+        self.tracks.get(path)
+    }
+
+    /// 返回曲目数量。
+    pub fn len(&self) -> usize {
+        self.tracks.len()
+    }
+
+    /// 判断媒体库是否为空。
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    /// 按路径顺序遍历所有曲目。
+    pub fn tracks(&self) -> impl Iterator<Item = &Track> {
+        self.tracks.values()
+    }
+
+    /// 返回所有艺术家（去重并排序）。
+    pub fn artists(&self) -> Vec<&str> {
+        self.distinct(|t| t.artist.as_deref())
+    }
+
+    /// 返回所有专辑艺术家（去重并排序），没有专辑艺术家标签时使用艺术家。
+    pub fn album_artists(&self) -> Vec<&str> {
+        self.distinct(Track::album_artist_or_artist)
+    }
+
+    /// 返回所有专辑（去重并排序）。
+    pub fn albums(&self) -> Vec<&str> {
+        self.distinct(|t| t.album.as_deref())
+    }
+
+    /// 返回所有流派（去重并排序）。
+    pub fn genres(&self) -> Vec<&str> {
+        self.distinct(|t| t.genre.as_deref())
+    }
+
+    /// 返回所有年份（去重并排序）。
+    pub fn years(&self) -> Vec<i32> {
+        let years: BTreeSet<_> = self.tracks().filter_map(|t| t.year).collect();
+        years.into_iter().collect()
+    }
+
+    /// 按查询条件查找曲目。
+    pub fn query(&self, query: &Query) -> Vec<&Track> {
+        match query {
+            Query::Artist(artist) => self.by_artist(artist),
+            Query::AlbumArtist(album_artist) => self.by_album_artist(album_artist),
+            Query::Album(album) => self.by_album(album),
+            Query::Genre(genre) => self.by_genre(genre),
+            Query::Years(years) => self.by_year(years.clone()),
+            Query::PathPrefix(prefix) => self.under(prefix),
+        }
+    }
+
+    /// 查询指定艺术家的曲目。
+    pub fn by_artist(&self, artist: &str) -> Vec<&Track> {
+        self.filter(|t| t.artist.as_deref() == Some(artist))
+    }
+
+    /// 查询指定专辑艺术家的曲目。
+    pub fn by_album_artist(&self, album_artist: &str) -> Vec<&Track> {
+        self.filter(|t| t.album_artist_or_artist() == Some(album_artist))
+    }
+
+    /// 查询指定专辑的曲目。
+    pub fn by_album(&self, album: &str) -> Vec<&Track> {
+        self.filter(|t| t.album.as_deref() == Some(album))
+    }
+
+    /// 查询指定流派的曲目。
+    pub fn by_genre(&self, genre: &str) -> Vec<&Track> {
+        self.filter(|t| t.genre.as_deref() == Some(genre))
+    }
+
+    /// 查询年份在指定范围内的曲目。
+    pub fn by_year(&self, years: RangeInclusive<i32>) -> Vec<&Track> {
+        self.filter(|t| t.year.is_some_and(|year| years.contains(&year)))
+    }
+
+    /// 查询指定目录下（包括子目录）的曲目。
+    pub fn under(&self, prefix: &Path) -> Vec<&Track> {
+        // 路径有序，目录下的曲目在表中是连续的一段
+        let mut tracks: Vec<_> = self
+            .tracks
+            .range(prefix.to_path_buf()..)
+            .take_while(|(path, _)| path.starts_with(prefix))
+            .map(|(_, track)| track)
+            .collect();
+        tracks.sort_by(|a, b| track_order(a, b));
+        tracks
+    }
+
+    /// 收集某个字段的所有取值。
+    fn distinct<'a>(&'a self, field: impl Fn(&'a Track) -> Option<&'a str>) -> Vec<&'a str> {
+        let values: BTreeSet<_> = self.tracks().filter_map(field).collect();
+        values.into_iter().collect()
+    }
+
+    /// 按条件筛选曲目并排序。
+    fn filter(&self, predicate: impl Fn(&Track) -> bool) -> Vec<&Track> {
+        let mut tracks: Vec<_> = self.tracks().filter(|t| predicate(t)).collect();
+        tracks.sort_by(|a, b| track_order(a, b));
+        tracks
+    }
+}
+
+/// 曲目的默认排序：专辑、碟号、音轨号，最后按路径。
+pub(crate) fn track_order(a: &Track, b: &Track) -> std::cmp::Ordering {
+    (&a.album, a.disc_number, a.track_number, &a.path).cmp(&(
+        &b.album,
+        b.disc_number,
+        b.track_number,
+        &b.path,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(path: &str, artist: &str, album: &str, number: u32, year: i32) -> Track {
+        Track {
+            artist: Some(artist.to_string()),
+            album: Some(album.to_string()),
+            track_number: Some(number),
+            year: Some(year),
+            ..Track::new(path)
+        }
+    }
+
+    fn library() -> Library {
+        let mut compilation = track("/music/va/01.flac", "Solo", "Hits", 1, 2005);
+        compilation.album_artist = Some("Various Artists".to_string());
+        compilation.genre = Some("Pop".to_string());
+
+        Library::from_tracks([
+            track("/music/a/x/02.flac", "A", "X", 2, 1991),
+            track("/music/a/x/01.flac", "A", "X", 1, 1991),
+            track("/music/a/y/01.flac", "A", "Y", 1, 1999),
+            track("/music/b/z/01.flac", "B", "Z", 1, 2003),
+            compilation,
+        ])
+    }
+
+    #[test]
+    fn test_library_distinct_values() {
+        let library = library();
+        assert_eq!(library.len(), 5);
+        assert_eq!(library.artists(), vec!["A", "B", "Solo"]);
+        assert_eq!(library.album_artists(), vec!["A", "B", "Various Artists"]);
+        assert_eq!(library.albums(), vec!["Hits", "X", "Y", "Z"]);
+        assert_eq!(library.genres(), vec!["Pop"]);
+        assert_eq!(library.years(), vec![1991, 1999, 2003, 2005]);
+    }
+
+    #[test]
+    fn test_library_queries() {
+        let library = library();
+        let paths = |tracks: Vec<&Track>| {
+            tracks
+                .into_iter()
+                .map(|t| t.path.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            paths(library.by_album("X")),
+            vec!["/music/a/x/01.flac", "/music/a/x/02.flac"]
+        );
+        assert_eq!(library.by_artist("A").len(), 3);
+        assert_eq!(
+            paths(library.by_album_artist("Various Artists")),
+            vec!["/music/va/01.flac"]
+        );
+        assert_eq!(library.by_genre("Pop").len(), 1);
+        assert_eq!(library.by_year(1990..=1999).len(), 3);
+        assert_eq!(
+            paths(library.under(Path::new("/music/a"))),
+            vec![
+                "/music/a/x/01.flac",
+                "/music/a/x/02.flac",
+                "/music/a/y/01.flac"
+            ]
+        );
+        assert!(library.under(Path::new("/music/c")).is_empty());
+        assert_eq!(
+            library.query(&Query::PathPrefix("/music/a".into())),
+            library.under(Path::new("/music/a"))
+        );
+        assert_eq!(
+            library.query(&Query::Years(2000..=2010)),
+            library.by_year(2000..=2010)
+        );
+    }
+
+    #[test]
+    fn test_library_insert_and_remove() {
+        let mut library = library();
+        let path = Path::new("/music/b/z/01.flac");
+
+        let mut retagged = library.get(path).unwrap().clone();
+        retagged.title = Some("New".to_string());
+        assert!(library.insert(retagged).is_some());
+        assert_eq!(library.get(path).unwrap().title.as_deref(), Some("New"));
+
+        assert!(library.remove(path).is_some());
+        assert!(library.get(path).is_none());
+        assert_eq!(library.len(), 4);
+
+        assert_eq!(library.remove_under(Path::new("/music/a")), 3);
+        assert_eq!(library.remove_under(Path::new("/music/a")), 0);
+        assert_eq!(library.len(), 1);
+    }
+
+    #[test]
+    fn test_summarize_albums() {
+        let mut library = library();
+        let mut other = track("/music/c/x/01.flac", "C", "X", 1, 2010);
+        other.duration = Some(Duration::from_secs(100));
+        library.insert(other);
+        let mut second = track("/music/va/02.flac", "Other", "Hits", 2, 2004);
+        second.album_artist = Some("Various Artists".to_string());
+        second.duration = Some(Duration::from_secs(30));
+        library.insert(second);
+
+        let albums = summarize_albums(library.tracks());
+        let summary: Vec<_> = albums
+            .iter()
+            .map(|a| {
+                (
+                    a.title.as_deref().unwrap(),
+                    a.artist.as_deref().unwrap(),
+                    a.year,
+                    a.track_count(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Hits", "Various Artists", Some(2004), 2),
+                ("X", "A", Some(1991), 2),
+                ("X", "C", Some(2010), 1),
+                ("Y", "A", Some(1999), 1),
+                ("Z", "B", Some(2003), 1),
+            ],
+            "不同艺术家的同名专辑分开汇总，合辑按专辑艺术家汇总"
+        );
+        assert_eq!(albums[0].duration, Duration::from_secs(30));
+        assert_eq!(
+            albums[1].tracks,
+            vec![
+                PathBuf::from("/music/a/x/01.flac"),
+                PathBuf::from("/music/a/x/02.flac")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_album_sort() {
+        let album = |title: &str, year: i32, count: usize, secs: u64| Album {
+            title: Some(title.to_string()),
+            artist: None,
+            year: Some(year),
+            tracks: vec![PathBuf::new(); count],
+            duration: Duration::from_secs(secs),
+        };
+        let mut albums = vec![
+            album("Album 10", 2001, 3, 100),
+            album("Album 2", 1999, 5, 50),
+            album("Album 1", 2001, 1, 300),
+        ];
+        let titles = |albums: &[Album]| {
+            albums
+                .iter()
+                .map(|a| a.title.clone().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        AlbumSort::Title.sort(&mut albums);
+        assert_eq!(titles(&albums), ["Album 1", "Album 2", "Album 10"]);
+        AlbumSort::Year.sort(&mut albums);
+        assert_eq!(titles(&albums), ["Album 2", "Album 1", "Album 10"]);
+        AlbumSort::TrackCount.sort(&mut albums);
+        assert_eq!(titles(&albums), ["Album 2", "Album 10", "Album 1"]);
+        AlbumSort::Duration.sort(&mut albums);
+        assert_eq!(titles(&albums), ["Album 1", "Album 10", "Album 2"]);
+        assert_eq!(AlbumSort::Duration.next(), AlbumSort::Title);
+    }
+}
@@ -0,0 +1,26 @@
+//! `lazy-library` crate 提供了 `lazymusic` 的媒体库。
+//!
+//! 主要包含以下模块：
+//! - `tags`: 读取音频文件的标签（ID3v2、Vorbis comments、RIFF INFO、MP4）和时长。
+//! - `mp4`: MP4/M4A 容器 `ilst` 标签的最小解析器。
+//! - `scanner`: 递归遍历音乐目录并逐个读取标签，单个文件的错误不会中断扫描。
+//! - `index`: 内存中的媒体库索引，供各个页面按艺术家、专辑等查询。
+//! - `db`: 基于 SQLite 的持久化媒体库，支持按修改时间和大小增量扫描。
+//! - `watcher`: 监视音乐目录的变化（去抖、重命名、批量移动），实时更新媒体库。
+//! - `browse`: 为目录页按自然顺序列出目录内容，并递归收集目录下的音频文件。
+//! - `playlist`: 读写 M3U/M3U8/PLS/XSPF 播放列表，并管理播放列表目录中的文件。
+//! - `search`: 解析搜索文本，在媒体库中模糊匹配并按相关度排序，支持按字段筛选。
+
+pub mod browse;
+pub mod db;
+pub mod error;
+pub mod index;
+pub mod mp4;
+pub mod playlist;
+pub mod scanner;
+pub mod search;
+pub mod tags;
+pub mod watcher;
+
+#[cfg(test)]
+mod test_utils;
@@ -0,0 +1,353 @@
+//! MP4/M4A 标签解析模块。
+//!
+//! 只解析读取标签所需的最少部分：`moov/mvhd` 中的时长、`moov/trak` 中的音轨类型和编码，
+//! 以及 `moov/udta/meta/ilst` 中的 iTunes 风格标签。音频数据（`mdat`）会被直接跳过。
+
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::Path,
+    time::Duration,
+};
+
+use lazy_core::track::Track;
+
+use crate::{
+    error::LibraryError,
+    tags::{parse_number, parse_year},
+};
+
+/// `moov` 原子大小上限，超出时视为文件损坏
+const MAX_MOOV_SIZE: u64 = 64 * 1024 * 1024;
+
+/// 播放器能解码的音频编码（`stsd` 中采样描述的类型）：AAC 和 ALAC
+const DECODABLE_CODECS: &[[u8; 4]] = &[*b"mp4a", *b"alac"];
+
+/// 读取一个 MP4/M4A 文件的标签和时长。
+pub fn read_track(path: impl AsRef<Path>) -> Result<Track, LibraryError> {
+    let path = path.as_ref();
+    let mut reader = BufReader::new(File::open(path)?);
+    let moov = read_moov(&mut reader)?;
+
+    let mut track = Track::new(path);
+    let mut codecs = Vec::new();
+    for (kind, body) in children(&moov)? {
+        match &kind {
+            b"mvhd" => track.duration = parse_mvhd(body),
+            b"trak" => codecs.extend(audio_codec(body)?),
+            b"udta" => {
+                if let Some((_, meta)) = children(body)?.into_iter().find(|(k, _)| k == b"meta") {
+                    parse_meta(meta, &mut track)?;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 至少有一条音频轨能被播放器解码
+    match codecs.first() {
+        None => Err(LibraryError::Unsupported("no audio track".to_string())),
+        Some(_) if codecs.iter().any(|codec| DECODABLE_CODECS.contains(codec)) => Ok(track),
+        Some(codec) => Err(LibraryError::Unsupported(format!(
+            "no decoder for the audio codec `{}`",
+            String::from_utf8_lossy(codec)
+        ))),
+    }
+}
+
+/// 顺序扫描顶层原子，读取 `moov` 原子的内容。
+fn read_moov<R: Read + Seek>(reader: &mut R) -> Result<Vec<u8>, LibraryError> {
+    let mut first = true;
+    loop {
+        let mut header = [0; 8];
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                return Err(LibraryError::Tag("missing moov atom".to_string()));
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        let kind = [header[4], header[5], header[6], header[7]];
+        if first && &kind != b"ftyp" {
+            return Err(LibraryError::Unsupported("not an MP4 file".to_string()));
+        }
+        first = false;
+
+        let size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let body_size = match size {
+            // 原子延伸到文件末尾
+            0 => None,
+            // 64 位扩展大小
+            1 => {
+                let mut large = [0; 8];
+                reader.read_exact(&mut large)?;
+                Some(
+                    u64::from_be_bytes(large)
+                        .checked_sub(16)
+                        .ok_or_else(|| LibraryError::Tag("invalid atom size".to_string()))?,
+                )
+            }
+            size => Some(
+                size.checked_sub(8)
+                    .ok_or_else(|| LibraryError::Tag("invalid atom size".to_string()))?,
+            ),
+        };
+
+        if &kind == b"moov" {
+            let mut body = Vec::new();
+            match body_size {
+                Some(size) if size > MAX_MOOV_SIZE => {
+                    return Err(LibraryError::Tag("moov atom too large".to_string()));
+                }
+                Some(size) => {
+                    body.resize(size as usize, 0);
+                    reader.read_exact(&mut body)?;
+                }
+                None => {
+                    reader.take(MAX_MOOV_SIZE).read_to_end(&mut body)?;
+                }
+            }
+            return Ok(body);
+        }
+
+        match body_size {
+            Some(size) => {
+                reader.seek(SeekFrom::Current(size as i64))?;
+            }
+            None => return Err(LibraryError::Tag("missing moov atom".to_string())),
+        }
+    }
+}
+
+/// 原子类型和内容
+type Atom<'a> = ([u8; 4], &'a [u8]);
+
+/// 将一段原子内容拆分为子原子列表。
+fn children(data: &[u8]) -> Result<Vec<Atom<'_>>, LibraryError> {
+    let mut atoms = Vec::new();
+    let mut rest = data;
+    while rest.len() >= 8 {
+        let size = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let kind = [rest[4], rest[5], rest[6], rest[7]];
+        let size = if size == 0 { rest.len() } else { size };
+        if size < 8 || size > rest.len() {
+            return Err(LibraryError::Tag("invalid atom size".to_string()));
+        }
+        atoms.push((kind, &rest[8..size]));
+        rest = &rest[size..];
+    }
+    Ok(atoms)
+}
+
+/// 从 `mvhd` 原子中读取时长。
+fn parse_mvhd(data: &[u8]) -> Option<Duration> {
+    let read_u32 = |at: usize| Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?));
+    let read_u64 = |at: usize| Some(u64::from_be_bytes(data.get(at..at + 8)?.try_into().ok()?));
+
+    let (timescale, duration) = match data.first()? {
+        1 => (read_u32(20)?, read_u64(24)?),
+        _ => (read_u32(12)?, read_u32(16)? as u64),
+    };
+    if timescale == 0 {
+        return None;
+    }
+    Some(Duration::from_secs_f64(duration as f64 / timescale as f64))
+}
+
+/// 返回音频轨（`mdia/hdlr` 的处理类型为 `soun`）的编码，不是音频轨时返回 `None`。
+///
+/// 编码是 `mdia/minf/stbl/stsd` 中第一个采样描述的类型。
+fn audio_codec(data: &[u8]) -> Result<Option<[u8; 4]>, LibraryError> {
+    let Some(mdia) = child(data, b"mdia")? else {
+        return Ok(None);
+    };
+    // 版本/标志 4 字节 + pre_defined 4 字节之后是处理类型
+    if child(mdia, b"hdlr")?.and_then(|hdlr| hdlr.get(8..12)) != Some(b"soun") {
+        return Ok(None);
+    }
+    let mut stsd = Some(mdia);
+    for kind in [b"minf", b"stbl", b"stsd"] {
+        stsd = match stsd {
+            Some(data) => child(data, kind)?,
+            None => None,
+        };
+    }
+    let Some(stsd) = stsd else {
+        return Err(LibraryError::Tag("missing stsd atom".to_string()));
+    };
+    // 版本/标志 4 字节 + 条目数 4 字节之后是采样描述
+    match children(stsd.get(8..).unwrap_or_default())?.first() {
+        Some((codec, _)) => Ok(Some(*codec)),
+        None => Err(LibraryError::Tag("empty stsd atom".to_string())),
+    }
+}
+
+/// 返回第一个指定类型的子原子的内容。
+fn child<'a>(data: &'a [u8], kind: &[u8; 4]) -> Result<Option<&'a [u8]>, LibraryError> {
+    Ok(children(data)?
+        .into_iter()
+        .find(|(k, _)| k == kind)
+        .map(|(_, body)| body))
+}
+
+/// 解析 `meta` 原子中的 `ilst` 标签列表。
+fn parse_meta(data: &[u8], track: &mut Track) -> Result<(), LibraryError> {
+    // iTunes 的 `meta` 是带版本/标志的完整原子，QuickTime 的则没有
+    let data = if data.get(4..8) == Some(b"hdlr") {
+        data
+    } else {
+        data.get(4..).unwrap_or_default()
+    };
+
+    let Some((_, ilst)) = children(data)?.into_iter().find(|(k, _)| k == b"ilst") else {
+        return Ok(());
+    };
+
+    for (kind, item) in children(ilst)? {
+        let Some((_, value)) = children(item)?.into_iter().find(|(k, _)| k == b"data") else {
+            continue;
+        };
+        // 类型 4 字节 + 区域 4 字节之后是数据
+        let Some(value) = value.get(8..) else {
+            continue;
+        };
+
+        match &kind {
+            b"trkn" => track.track_number = parse_index(value),
+            b"disk" => track.disc_number = parse_index(value),
+            _ => {
+                let text = String::from_utf8_lossy(value).trim().to_string();
+                if text.is_empty() {
+                    continue;
+                }
+                match &kind {
+                    b"\xa9nam" => track.title = Some(text),
+                    b"\xa9ART" => track.artist = Some(text),
+                    b"aART" => track.album_artist = Some(text),
+                    b"\xa9alb" => track.album = Some(text),
+                    b"\xa9gen" => track.genre = Some(text),
+                    b"\xa9day" => track.year = parse_year(&text),
+                    _ => {}
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 解析 `trkn`/`disk` 中的编号（2 字节填充 + 2 字节编号 + 2 字节总数）。
+///
+/// 少数编码器会把编号写成文本，这里一并兼容。
+fn parse_index(value: &[u8]) -> Option<u32> {
+    match value.get(2..4) {
+        Some(&[hi, lo]) if value.len() >= 6 => {
+            Some(u16::from_be_bytes([hi, lo]) as u32).filter(|n| *n > 0)
+        }
+        _ => parse_number(&String::from_utf8_lossy(value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 生成一个原子。
+    fn atom(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let mut out = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend(kind);
+        out.extend(body);
+        out
+    }
+
+    /// 生成一个 `ilst` 条目。
+    fn item(kind: &[u8; 4], value: &[u8]) -> Vec<u8> {
+        let mut data = vec![0, 0, 0, 1, 0, 0, 0, 0];
+        data.extend(value);
+        atom(kind, &atom(b"data", &data))
+    }
+
+    /// 生成一个带 `codec` 编码的音频轨和标签的最小 M4A 文件。
+    fn m4a_bytes(codec: &[u8; 4], tags: &[u8]) -> Vec<u8> {
+        let mut mvhd = vec![0; 4]; // 版本/标志
+        mvhd.extend([0; 8]); // 创建/修改时间
+        mvhd.extend(1_000u32.to_be_bytes());
+        mvhd.extend(2_500u32.to_be_bytes());
+
+        let mut hdlr = vec![0; 8];
+        hdlr.extend(b"soun");
+        hdlr.extend([0; 12]);
+        let mut stsd = vec![0, 0, 0, 0, 0, 0, 0, 1]; // 版本/标志 + 条目数
+        stsd.extend(atom(codec, &[0; 28]));
+        let stbl = atom(b"stbl", &atom(b"stsd", &stsd));
+        let mut mdia = atom(b"hdlr", &hdlr);
+        mdia.extend(atom(b"minf", &stbl));
+        let trak = atom(b"trak", &atom(b"mdia", &mdia));
+
+        let mut meta = vec![0; 4];
+        meta.extend(atom(b"ilst", tags));
+        let udta = atom(b"udta", &atom(b"meta", &meta));
+
+        let mut moov = atom(b"mvhd", &mvhd);
+        moov.extend(trak);
+        moov.extend(udta);
+
+        let mut out = atom(b"ftyp", b"M4A \0\0\0\0");
+        out.extend(atom(b"mdat", &[0; 64]));
+        out.extend(atom(b"moov", &moov));
+        out
+    }
+
+    #[test]
+    fn test_read_m4a_tags() {
+        let mut tags = item(b"\xa9nam", b"Title");
+        tags.extend(item(b"\xa9ART", b"Artist"));
+        tags.extend(item(b"aART", b"Album Artist"));
+        tags.extend(item(b"\xa9alb", b"Album"));
+        tags.extend(item(b"\xa9day", b"2010-01-01"));
+        tags.extend(item(b"\xa9gen", b"Pop"));
+        tags.extend(item(b"trkn", &[0, 0, 0, 5, 0, 12, 0, 0]));
+        tags.extend(item(b"disk", &[0, 0, 0, 2, 0, 2]));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("song.m4a");
+        std::fs::write(&path, m4a_bytes(b"mp4a", &tags)).unwrap();
+
+        let track = read_track(&path).unwrap();
+        assert_eq!(track.title.as_deref(), Some("Title"));
+        assert_eq!(track.artist.as_deref(), Some("Artist"));
+        assert_eq!(track.album_artist.as_deref(), Some("Album Artist"));
+        assert_eq!(track.album.as_deref(), Some("Album"));
+        assert_eq!(track.year, Some(2010));
+        assert_eq!(track.genre.as_deref(), Some("Pop"));
+        assert_eq!(track.track_number, Some(5));
+        assert_eq!(track.disc_number, Some(2));
+        assert_eq!(track.duration, Some(Duration::from_millis(2_500)));
+    }
+
+    #[test]
+    fn test_read_mp4_errors() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("fake.m4a");
+        std::fs::write(&path, b"definitely not mp4").unwrap();
+        assert!(matches!(
+            read_track(&path),
+            Err(LibraryError::Unsupported(_))
+        ));
+
+        let path = dir.path().join("truncated.m4a");
+        let mut bytes = m4a_bytes(b"mp4a", &[]);
+        bytes.truncate(bytes.len() - 10);
+        std::fs::write(&path, bytes).unwrap();
+        assert!(read_track(&path).is_err());
+
+        // 播放器不能解码的编码不会被当作可播放的曲目
+        let path = dir.path().join("surround.m4a");
+        std::fs::write(&path, m4a_bytes(b"ac-3", &[])).unwrap();
+        assert!(matches!(
+            read_track(&path),
+            Err(LibraryError::Unsupported(msg)) if msg.contains("ac-3")
+        ));
+    }
+}
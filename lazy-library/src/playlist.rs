@@ -0,0 +1,754 @@
+//! 播放列表模块。
+//!
+//! 读写 `.m3u`、`.m3u8`（扩展信息）、`.pls` 和 `.xspf` 播放列表。读取时相对路径以播放列表文件所在的目录为基准，
+//! 保存时位于该目录下的曲目写为相对路径，其余写为绝对路径，使播放列表可以随音乐目录一起移动。
+//! 播放列表保存在播放列表目录中，本模块同时提供列出、创建、重命名和删除播放列表文件的函数。
+
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt::Write,
+    fs,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use lazy_core::track::Track;
+use percent_encoding::{AsciiSet, CONTROLS, percent_decode_str, utf8_percent_encode};
+use quick_xml::{Reader, escape::escape, events::Event};
+
+use crate::{browse::natural_cmp, db::data_dir, error::LibraryError};
+
+/// XSPF 的 `location` 中需要转义的字符（非 ASCII 字符总是会被转义）
+const URI_ESCAPE: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b']')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// 返回默认的播放列表目录：`$XDG_DATA_HOME/lazymusic/playlists`，
+/// 未设置时为 `~/.local/share/lazymusic/playlists`。
+pub fn default_dir() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("playlists"))
+}
+
+/// 播放列表的文件格式。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaylistFormat {
+    /// 本地编码的 M3U，读取时无法按 UTF-8 解码的文件按 Latin-1 解码
+    M3u,
+    /// UTF-8 编码的扩展 M3U，保存时写入 `#EXTINF` 信息
+    M3u8,
+    /// PLS（INI 格式）
+    Pls,
+    /// XSPF（XML 格式）
+    Xspf,
+}
+
+impl PlaylistFormat {
+    /// 根据扩展名判断播放列表的格式（不区分大小写）。
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "m3u" => Some(PlaylistFormat::M3u),
+            "m3u8" => Some(PlaylistFormat::M3u8),
+            "pls" => Some(PlaylistFormat::Pls),
+            "xspf" => Some(PlaylistFormat::Xspf),
+            _ => None,
+        }
+    }
+
+    /// 返回格式对应的扩展名。
+    pub fn extension(self) -> &'static str {
+        match self {
+            PlaylistFormat::M3u => "m3u",
+            PlaylistFormat::M3u8 => "m3u8",
+            PlaylistFormat::Pls => "pls",
+            PlaylistFormat::Xspf => "xspf",
+        }
+    }
+}
+
+/// 播放列表中的一项。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlaylistEntry {
+    /// 曲目的绝对路径（相对路径已按播放列表所在目录解析）
+    pub path: PathBuf,
+    /// 播放列表中记录的标题
+    pub title: Option<String>,
+    /// 播放列表中记录的时长
+    pub duration: Option<Duration>,
+}
+
+impl PlaylistEntry {
+    /// 创建一个只有路径的条目。
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            title: None,
+            duration: None,
+        }
+    }
+
+    /// 根据曲目创建条目，标题为 `艺术家 - 标题`。
+    pub fn from_track(track: &Track) -> Self {
+        let title = match &track.artist {
+            Some(artist) => format!("{artist} - {}", track.display_title()),
+            None => track.display_title(),
+        };
+        Self {
+            path: track.path.clone(),
+            title: Some(title),
+            duration: track.duration,
+        }
+    }
+}
+
+/// 播放列表。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Playlist {
+    /// 播放列表文件的路径，扩展名决定保存的格式
+    pub path: PathBuf,
+    /// 播放列表中的条目
+    pub entries: Vec<PlaylistEntry>,
+}
+
+impl Playlist {
+    /// 创建一个空的播放列表。
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// 用曲目创建播放列表，例如把播放队列保存为播放列表。
+    pub fn from_tracks<'a>(
+        path: impl Into<PathBuf>,
+        tracks: impl IntoIterator<Item = &'a Track>,
+    ) -> Self {
+        Self {
+            path: path.into(),
+            entries: tracks.into_iter().map(PlaylistEntry::from_track).collect(),
+        }
+    }
+
+    /// 读取播放列表文件。
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, LibraryError> {
+        let path = path.into();
+        let format = format_of(&path)?;
+        let bytes = fs::read(&path)?;
+        let content = decode(&bytes, format);
+        let base = path.parent().unwrap_or(Path::new(""));
+        let entries = parse(&content, format, base)?;
+        Ok(Self { path, entries })
+    }
+
+    /// 按扩展名对应的格式保存播放列表，必要时创建所在的目录。
+    pub fn save(&self) -> Result<(), LibraryError> {
+        let format = format_of(&self.path)?;
+        let base = self.path.parent().unwrap_or(Path::new(""));
+        if !base.as_os_str().is_empty() {
+            fs::create_dir_all(base)?;
+        }
+        fs::write(&self.path, serialize(&self.entries, format, base))?;
+        Ok(())
+    }
+
+    /// 返回播放列表的名称（不含扩展名的文件名）。
+    pub fn name(&self) -> Cow<'_, str> {
+        playlist_name(&self.path)
+    }
+
+    /// 返回文件不存在的条目的索引。
+    pub fn missing(&self) -> Vec<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| !entry.path.exists())
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// 返回播放列表中的所有路径。
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.entries
+            .iter()
+            .map(|entry| entry.path.clone())
+            .collect()
+    }
+}
+
+/// 返回播放列表文件的名称（不含扩展名的文件名）。
+pub fn playlist_name(path: &Path) -> Cow<'_, str> {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default()
+}
+
+/// 列出目录中的播放列表文件，按名称的自然顺序排序；目录不存在时返回空列表。
+pub fn list_playlists(dir: &Path) -> Result<Vec<PathBuf>, LibraryError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut playlists = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && PlaylistFormat::from_path(&path).is_some() {
+            playlists.push(path);
+        }
+    }
+    playlists.sort_by(|a, b| natural_cmp(&playlist_name(a), &playlist_name(b)));
+    Ok(playlists)
+}
+
+/// 返回目录中指定名称的播放列表的路径。
+///
+/// 名称带有支持的扩展名（例如 `mix.pls`）时使用该格式，否则保存为 `.m3u8`。
+/// 名称为空、以 `.` 开头或包含路径分隔符时返回错误。
+pub fn playlist_path(dir: &Path, name: &str) -> Result<PathBuf, LibraryError> {
+    let name = name.trim();
+    let mut components = Path::new(name).components();
+    let valid = matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    ) && !name.starts_with('.');
+    if !valid {
+        return Err(LibraryError::Playlist(format!(
+            "invalid playlist name: {name:?}"
+        )));
+    }
+    let path = dir.join(name);
+    if PlaylistFormat::from_path(&path).is_some() {
+        Ok(path)
+    } else {
+        Ok(dir.join(format!("{name}.{}", PlaylistFormat::M3u8.extension())))
+    }
+}
+
+/// 在目录中创建一个空的播放列表，已存在同名的播放列表时返回错误。
+pub fn create_playlist(dir: &Path, name: &str) -> Result<Playlist, LibraryError> {
+    let path = new_playlist_path(dir, name)?;
+    let playlist = Playlist::new(path);
+    playlist.save()?;
+    Ok(playlist)
+}
+
+/// 重命名播放列表，返回新的路径。
+///
+/// 新名称没有指定扩展名时保留原来的格式；改变扩展名时按新格式重新保存。
+pub fn rename_playlist(path: &Path, name: &str) -> Result<PathBuf, LibraryError> {
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let name = match PlaylistFormat::from_path(Path::new(name.trim())) {
+        Some(_) => name.to_string(),
+        None => format!("{}.{}", name.trim(), format_of(path)?.extension()),
+    };
+    let target = new_playlist_path(dir, &name)?;
+    if PlaylistFormat::from_path(&target) == PlaylistFormat::from_path(path) {
+        fs::rename(path, &target)?;
+    } else {
+        let mut playlist = Playlist::load(path)?;
+        playlist.path = target.clone();
+        playlist.save()?;
+        fs::remove_file(path)?;
+    }
+    Ok(target)
+}
+
+/// 删除播放列表文件。
+pub fn delete_playlist(path: &Path) -> Result<(), LibraryError> {
+    format_of(path)?;
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+/// 返回一个新播放列表的路径，已存在同名的播放列表时返回错误。
+fn new_playlist_path(dir: &Path, name: &str) -> Result<PathBuf, LibraryError> {
+    let path = playlist_path(dir, name)?;
+    if path.exists() {
+        return Err(LibraryError::Playlist(format!(
+            "playlist already exists: {}",
+            path.display()
+        )));
+    }
+    Ok(path)
+}
+
+/// 根据扩展名获取播放列表的格式，不支持时返回错误。
+fn format_of(path: &Path) -> Result<PlaylistFormat, LibraryError> {
+    PlaylistFormat::from_path(path)
+        .ok_or_else(|| LibraryError::Unsupported(format!("not a playlist: {}", path.display())))
+}
+
+/// 将文件内容解码为字符串，并去掉开头的 BOM。
+///
+/// `.m3u` 没有规定编码，无法按 UTF-8 解码时按 Latin-1 解码；其他格式按 UTF-8 解码，无效字符会被替换。
+fn decode(bytes: &[u8], format: PlaylistFormat) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    match (String::from_utf8(bytes.to_vec()), format) {
+        (Ok(content), _) => content,
+        (Err(_), PlaylistFormat::M3u) => bytes.iter().map(|&b| b as char).collect(),
+        (Err(e), _) => String::from_utf8_lossy(e.as_bytes()).into_owned(),
+    }
+}
+
+/// 解析播放列表的内容，相对路径以 `base` 为基准。
+pub fn parse(
+    content: &str,
+    format: PlaylistFormat,
+    base: &Path,
+) -> Result<Vec<PlaylistEntry>, LibraryError> {
+    match format {
+        PlaylistFormat::M3u | PlaylistFormat::M3u8 => Ok(parse_m3u(content, base)),
+        PlaylistFormat::Pls => Ok(parse_pls(content, base)),
+        PlaylistFormat::Xspf => parse_xspf(content, base),
+    }
+}
+
+/// 将条目序列化为播放列表的内容，位于 `base` 下的路径写为相对路径。
+pub fn serialize(entries: &[PlaylistEntry], format: PlaylistFormat, base: &Path) -> String {
+    match format {
+        PlaylistFormat::M3u => serialize_m3u(entries, base, false),
+        PlaylistFormat::M3u8 => serialize_m3u(entries, base, true),
+        PlaylistFormat::Pls => serialize_pls(entries, base),
+        PlaylistFormat::Xspf => serialize_xspf(entries, base),
+    }
+}
+
+/// 解析 M3U，`#EXTINF:时长,标题` 作用于下一个条目。
+fn parse_m3u(content: &str, base: &Path) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut info = None;
+    for line in content.lines().map(str::trim) {
+        if let Some(extinf) = line.strip_prefix("#EXTINF:") {
+            let (seconds, title) = extinf.split_once(',').unwrap_or((extinf, ""));
+            info = Some((
+                parse_seconds(seconds),
+                Some(title.trim()).filter(|t| !t.is_empty()),
+            ));
+        } else if !line.is_empty() && !line.starts_with('#') {
+            let (duration, title) = info.take().unwrap_or_default();
+            entries.push(PlaylistEntry {
+                path: resolve(line, base),
+                title: title.map(str::to_string),
+                duration,
+            });
+        }
+    }
+    entries
+}
+
+/// 序列化为 M3U，`extended` 为真时写入 `#EXTM3U` 头和 `#EXTINF` 信息。
+fn serialize_m3u(entries: &[PlaylistEntry], base: &Path, extended: bool) -> String {
+    let mut content = String::new();
+    if extended {
+        content.push_str("#EXTM3U\n");
+    }
+    for entry in entries {
+        if extended {
+            let seconds = entry.duration.map_or(-1, |d| d.as_secs() as i64);
+            let title = entry.title.as_deref().unwrap_or_default();
+            let _ = writeln!(content, "#EXTINF:{seconds},{title}");
+        }
+        let _ = writeln!(content, "{}", relative(&entry.path, base).display());
+    }
+    content
+}
+
+/// 解析 PLS，条目按 `FileN` 中的序号排序，键不区分大小写。
+fn parse_pls(content: &str, base: &Path) -> Vec<PlaylistEntry> {
+    let mut entries: BTreeMap<u32, PlaylistEntry> = BTreeMap::new();
+    let mut infos: BTreeMap<u32, (Option<String>, Option<Duration>)> = BTreeMap::new();
+    for line in content.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let split = key.find(|c: char| c.is_ascii_digit()).unwrap_or(key.len());
+        let (name, number) = key.split_at(split);
+        let Ok(number) = number.parse::<u32>() else {
+            continue;
+        };
+        let value = value.trim();
+        match name {
+            "file" => {
+                entries.insert(number, PlaylistEntry::new(resolve(value, base)));
+            }
+            "title" => infos.entry(number).or_default().0 = Some(value.to_string()),
+            "length" => infos.entry(number).or_default().1 = parse_seconds(value),
+            _ => (),
+        }
+    }
+    entries
+        .into_iter()
+        .map(|(number, mut entry)| {
+            if let Some((title, duration)) = infos.remove(&number) {
+                entry.title = title;
+                entry.duration = duration;
+            }
+            entry
+        })
+        .collect()
+}
+
+/// 序列化为 PLS（版本 2）。
+fn serialize_pls(entries: &[PlaylistEntry], base: &Path) -> String {
+    let mut content = String::from("[playlist]\n");
+    for (number, entry) in (1..).zip(entries) {
+        let _ = writeln!(
+            content,
+            "File{number}={}",
+            relative(&entry.path, base).display()
+        );
+        if let Some(title) = &entry.title {
+            let _ = writeln!(content, "Title{number}={title}");
+        }
+        let seconds = entry.duration.map_or(-1, |d| d.as_secs() as i64);
+        let _ = writeln!(content, "Length{number}={seconds}");
+    }
+    let _ = writeln!(content, "NumberOfEntries={}", entries.len());
+    content.push_str("Version=2\n");
+    content
+}
+
+/// XSPF 中 `track` 元素里需要读取的子元素。
+#[derive(Clone, Copy)]
+enum XspfField {
+    Location,
+    Title,
+    Duration,
+}
+
+/// 解析 XSPF，`location` 是 URI，`duration` 以毫秒为单位。
+fn parse_xspf(content: &str, base: &Path) -> Result<Vec<PlaylistEntry>, LibraryError> {
+    let xml_error = |e: quick_xml::Error| LibraryError::Playlist(format!("invalid XSPF: {e}"));
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().trim_text(true);
+
+    let mut entries = Vec::new();
+    // 当前 track 的内容：(location, title, duration)
+    let mut track: Option<(Option<PathBuf>, Option<String>, Option<Duration>)> = None;
+    let mut field = None;
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"track" => track = Some(Default::default()),
+                b"location" => field = Some(XspfField::Location),
+                b"title" => field = Some(XspfField::Title),
+                b"duration" => field = Some(XspfField::Duration),
+                _ => field = None,
+            },
+            Event::Text(text) => {
+                let (Some(track), Some(field)) = (track.as_mut(), field) else {
+                    continue;
+                };
+                let text = text.unescape().map_err(xml_error)?;
+                match field {
+                    // 一个 track 可以有多个 location，只使用第一个
+                    XspfField::Location if track.0.is_none() => {
+                        track.0 = Some(resolve_uri(&text, base));
+                    }
+                    XspfField::Location => (),
+                    XspfField::Title => track.1 = Some(text.into_owned()),
+                    XspfField::Duration => {
+                        track.2 = text.trim().parse().ok().map(Duration::from_millis);
+                    }
+                }
+            }
+            Event::End(e) => {
+                field = None;
+                if e.local_name().as_ref() == b"track"
+                    && let Some((Some(path), title, duration)) = track.take()
+                {
+                    entries.push(PlaylistEntry {
+                        path,
+                        title,
+                        duration,
+                    });
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+    Ok(entries)
+}
+
+/// 序列化为 XSPF（版本 1）。
+fn serialize_xspf(entries: &[PlaylistEntry], base: &Path) -> String {
+    let mut content = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n  <trackList>\n",
+    );
+    for entry in entries {
+        content.push_str("    <track>\n");
+        let _ = writeln!(
+            content,
+            "      <location>{}</location>",
+            escape(to_uri(&entry.path, base))
+        );
+        if let Some(title) = &entry.title {
+            let _ = writeln!(content, "      <title>{}</title>", escape(title.as_str()));
+        }
+        if let Some(duration) = entry.duration {
+            let _ = writeln!(
+                content,
+                "      <duration>{}</duration>",
+                duration.as_millis()
+            );
+        }
+        content.push_str("    </track>\n");
+    }
+    content.push_str("  </trackList>\n</playlist>\n");
+    content
+}
+
+/// 解析秒数，负数（未知时长）和无效值返回 `None`。
+fn parse_seconds(value: &str) -> Option<Duration> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+/// 将 M3U 或 PLS 中的位置解析为路径：`file://` URI 会被解码，相对路径以 `base` 为基准。
+fn resolve(location: &str, base: &Path) -> PathBuf {
+    match location.strip_prefix("file://") {
+        Some(uri) => resolve_uri(uri, base),
+        None => base.join(location),
+    }
+}
+
+/// 将 XSPF 的 `location` 解析为路径，`location` 总是 URI，相对 URI 以 `base` 为基准。
+fn resolve_uri(location: &str, base: &Path) -> PathBuf {
+    // file://localhost/path 和 file:///path 都表示本机的绝对路径
+    let uri = match location.strip_prefix("file://") {
+        Some(uri) => uri.strip_prefix("localhost").unwrap_or(uri),
+        None if location.contains("://") => return PathBuf::from(location),
+        None => location,
+    };
+    base.join(percent_decode_str(uri).decode_utf8_lossy().as_ref())
+}
+
+/// 位于 `base` 下的路径返回相对路径，其余返回原路径。
+fn relative<'a>(path: &'a Path, base: &Path) -> &'a Path {
+    match path.strip_prefix(base) {
+        Ok(relative) if !base.as_os_str().is_empty() => relative,
+        _ => path,
+    }
+}
+
+/// 将路径转换为 XSPF 的 `location`：相对路径写为相对 URI，绝对路径写为 `file://` URI。
+fn to_uri(path: &Path, base: &Path) -> String {
+    let relative = relative(path, base);
+    let encoded = utf8_percent_encode(&relative.to_string_lossy(), URI_ESCAPE).to_string();
+    if relative.is_absolute() {
+        format!("file://{encoded}")
+    } else {
+        encoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, title: &str, seconds: u64) -> PlaylistEntry {
+        PlaylistEntry {
+            path: PathBuf::from(path),
+            title: Some(title.to_string()),
+            duration: Some(Duration::from_secs(seconds)),
+        }
+    }
+
+    #[test]
+    fn test_parse_m3u() {
+        let content = "\u{feff}#EXTM3U\n\
+                       #EXTINF:123,Artist - Song\n\
+                       a/01 Song.flac\n\
+                       \n\
+                       # comment\n\
+                       /abs/50% 02.mp3\n\
+                       #EXTINF:-1,Stream\n\
+                       file:///music/My%20Song.ogg\n";
+        let entries = parse(
+            &decode(content.as_bytes(), PlaylistFormat::M3u8),
+            PlaylistFormat::M3u8,
+            Path::new("/lists"),
+        )
+        .unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                entry("/lists/a/01 Song.flac", "Artist - Song", 123),
+                PlaylistEntry::new("/abs/50% 02.mp3"),
+                PlaylistEntry {
+                    title: Some("Stream".to_string()),
+                    ..PlaylistEntry::new("/music/My Song.ogg")
+                },
+            ]
+        );
+
+        // .m3u 不是 UTF-8 时按 Latin-1 解码
+        let latin1 = decode(b"caf\xe9.mp3\n", PlaylistFormat::M3u);
+        assert_eq!(
+            parse(&latin1, PlaylistFormat::M3u, Path::new("/m")).unwrap(),
+            vec![PlaylistEntry::new("/m/café.mp3")]
+        );
+    }
+
+    #[test]
+    fn test_parse_pls() {
+        let content = "[playlist]\n\
+                       File2=/abs/b.mp3\n\
+                       Title2=B\n\
+                       file1=a.flac\n\
+                       Length1=61\n\
+                       NumberOfEntries=2\n\
+                       Version=2\n";
+        let entries = parse(content, PlaylistFormat::Pls, Path::new("/lists")).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                PlaylistEntry {
+                    duration: Some(Duration::from_secs(61)),
+                    ..PlaylistEntry::new("/lists/a.flac")
+                },
+                PlaylistEntry {
+                    title: Some("B".to_string()),
+                    ..PlaylistEntry::new("/abs/b.mp3")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_xspf() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <playlist version="1" xmlns="http://xspf.org/ns/0/">
+              <title>Mix</title>
+              <trackList>
+                <track>
+                  <location>file:///music/Rock%20%26%20Roll.flac</location>
+                  <title>Rock &amp; Roll</title>
+                  <duration>185000</duration>
+                </track>
+                <track><location>sub/a%20b.mp3</location></track>
+                <track><title>No location</title></track>
+              </trackList>
+            </playlist>"#;
+        let entries = parse(content, PlaylistFormat::Xspf, Path::new("/lists")).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                entry("/music/Rock & Roll.flac", "Rock & Roll", 185),
+                PlaylistEntry::new("/lists/sub/a b.mp3"),
+            ]
+        );
+        assert!(
+            parse(
+                "<playlist><trackList></playlist>",
+                PlaylistFormat::Xspf,
+                Path::new("/")
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_round_trip_all_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let inside = dir.path().join("music/01 A & B.flac");
+        let entries = vec![
+            PlaylistEntry {
+                path: inside.clone(),
+                title: Some("A & B <live>".to_string()),
+                duration: Some(Duration::from_secs(200)),
+            },
+            PlaylistEntry::new("/elsewhere/ü 02.mp3"),
+        ];
+        for format in [
+            PlaylistFormat::M3u,
+            PlaylistFormat::M3u8,
+            PlaylistFormat::Pls,
+            PlaylistFormat::Xspf,
+        ] {
+            let path = dir.path().join(format!("list.{}", format.extension()));
+            let playlist = Playlist {
+                path: path.clone(),
+                entries: entries.clone(),
+            };
+            playlist.save().unwrap();
+
+            let content = fs::read_to_string(&path).unwrap();
+            assert!(
+                !content.contains(&*dir.path().to_string_lossy()),
+                "{format:?} 使用相对路径"
+            );
+
+            let loaded = Playlist::load(&path).unwrap();
+            let expected = if format == PlaylistFormat::M3u {
+                // 普通 M3U 不保存扩展信息
+                entries
+                    .iter()
+                    .map(|e| PlaylistEntry::new(&e.path))
+                    .collect()
+            } else {
+                entries.clone()
+            };
+            assert_eq!(loaded.entries, expected, "{format:?}");
+            assert_eq!(loaded.missing(), vec![0, 1]);
+        }
+    }
+
+    #[test]
+    fn test_manage_playlists() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path().join("playlists");
+        assert!(list_playlists(&dir).unwrap().is_empty());
+
+        let mut playlist = create_playlist(&dir, "Mix 10").unwrap();
+        assert_eq!(playlist.path, dir.join("Mix 10.m3u8"));
+        assert_eq!(playlist.name(), "Mix 10");
+        playlist.entries.push(entry("/music/a.flac", "A", 1));
+        playlist.save().unwrap();
+        create_playlist(&dir, "Mix 2.pls").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+        assert!(
+            create_playlist(&dir, "Mix 2.pls").is_err(),
+            "同名播放列表已存在"
+        );
+        for name in ["", "  ", "../up", "a/b", ".hidden"] {
+            assert!(playlist_path(&dir, name).is_err(), "{name:?}");
+        }
+        assert_eq!(
+            list_playlists(&dir).unwrap(),
+            vec![dir.join("Mix 2.pls"), dir.join("Mix 10.m3u8")]
+        );
+
+        // 不带扩展名时保留格式，改变扩展名时转换格式
+        let renamed = rename_playlist(&dir.join("Mix 10.m3u8"), "Best").unwrap();
+        assert_eq!(renamed, dir.join("Best.m3u8"));
+        let converted = rename_playlist(&renamed, "Best.xspf").unwrap();
+        assert!(!renamed.exists());
+        assert_eq!(
+            Playlist::load(&converted).unwrap().entries,
+            playlist.entries
+        );
+        assert!(rename_playlist(&converted, "Mix 2.pls").is_err());
+
+        delete_playlist(&converted).unwrap();
+        assert!(delete_playlist(&dir.join("notes.txt")).is_err());
+        assert_eq!(list_playlists(&dir).unwrap(), vec![dir.join("Mix 2.pls")]);
+    }
+}
@@ -0,0 +1,226 @@
+//! 媒体库扫描模块。
+//!
+//! `Scanner` 递归遍历音乐根目录，对每个识别的音频文件读取标签。
+//! 单个文件或目录的错误会被记录在 `ScanReport` 中，而不会中断整个扫描。
+
+use std::{
+    fs::Metadata,
+    io,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use lazy_core::track::Track;
+use walkdir::WalkDir;
+
+use crate::{
+    error::LibraryError,
+    tags::{is_supported, read_track},
+};
+
+/// 扫描过程中单个文件或目录的错误。
+#[derive(Debug)]
+pub struct ScanError {
+    /// 出错的路径
+    pub path: PathBuf,
+    /// 错误原因
+    pub error: LibraryError,
+}
+
+/// 磁盘上一个音频文件的路径、修改时间和大小，用于判断文件是否发生变化。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileStamp {
+    /// 文件路径
+    pub path: PathBuf,
+    /// 修改时间（自 UNIX 纪元起的纳秒数）
+    pub mtime: i64,
+    /// 文件大小（字节）
+    pub size: u64,
+}
+
+impl FileStamp {
+    /// 从文件元数据创建。
+    pub fn new(path: PathBuf, metadata: &Metadata) -> Self {
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos() as i64)
+            .unwrap_or_default();
+        Self {
+            path,
+            mtime,
+            size: metadata.len(),
+        }
+    }
+}
+
+/// 一次扫描的结果。
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    /// 成功读取的曲目
+    pub tracks: Vec<Track>,
+    /// 读取失败的文件或目录
+    pub errors: Vec<ScanError>,
+}
+
+/// 音乐目录扫描器。
+#[derive(Clone, Debug, Default)]
+pub struct Scanner {
+    /// 音乐根目录
+    roots: Vec<PathBuf>,
+    /// 是否跟随符号链接
+    follow_links: bool,
+}
+
+impl Scanner {
+    /// 创建一个扫描指定根目录的扫描器。
+    pub fn new<I, P>(roots: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        Self {
+            roots: roots.into_iter().map(Into::into).collect(),
+            follow_links: false,
+        }
+    }
+
+    /// 设置是否跟随符号链接（默认不跟随）。
+    pub fn follow_links(mut self, follow_links: bool) -> Self {
+        self.follow_links = follow_links;
+        self
+    }
+
+    /// 返回音乐根目录。
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// 列出所有根目录下识别的音频文件，按路径排序。
+    pub fn files(&self) -> (Vec<FileStamp>, Vec<ScanError>) {
+        let mut files = Vec::new();
+        let mut errors = Vec::new();
+
+        for root in &self.roots {
+            let walker = WalkDir::new(root)
+                .follow_links(self.follow_links)
+                .sort_by_file_name();
+            for entry in walker {
+                match entry {
+                    Ok(entry) if entry.file_type().is_file() && is_supported(entry.path()) => {
+                        match entry.metadata() {
+                            Ok(metadata) => {
+                                files.push(FileStamp::new(entry.into_path(), &metadata))
+                            }
+                            Err(e) => errors.push(ScanError {
+                                path: entry.into_path(),
+                                error: LibraryError::Io(io::Error::from(e)),
+                            }),
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        let path = e.path().unwrap_or(root).to_path_buf();
+                        errors.push(ScanError {
+                            path,
+                            error: LibraryError::Io(io::Error::from(e)),
+                        });
+                    }
+                }
+            }
+        }
+
+        (files, errors)
+    }
+
+    /// 扫描所有根目录并读取标签。
+    pub fn scan(&self) -> ScanReport {
+        let (files, errors) = self.files();
+        let mut report = ScanReport {
+            tracks: Vec::with_capacity(files.len()),
+            errors,
+        };
+        for file in files {
+            report.push(scan_file(&file.path));
+        }
+        report
+    }
+}
+
+impl ScanReport {
+    /// 记录单个文件的扫描结果。
+    pub fn push(&mut self, result: Result<Track, ScanError>) {
+        match result {
+            Ok(track) => self.tracks.push(track),
+            Err(error) => self.errors.push(error),
+        }
+    }
+}
+
+/// 读取单个文件的标签，错误中附带文件路径。
+pub fn scan_file(path: &Path) -> Result<Track, ScanError> {
+    read_track(path).map_err(|error| ScanError {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{opus_bytes, wav_bytes};
+
+    #[test]
+    fn test_scanner_collects_tracks_and_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("Artist/Album")).unwrap();
+        std::fs::write(root.join("Artist/Album/01.wav"), wav_bytes(8_000, 800, &[])).unwrap();
+        std::fs::write(root.join("Artist/Album/02.wav"), wav_bytes(8_000, 800, &[])).unwrap();
+        std::fs::write(root.join("Artist/Album/cover.jpg"), b"jpeg").unwrap();
+        std::fs::write(root.join("broken.flac"), b"not flac").unwrap();
+
+        let report = Scanner::new([root]).scan();
+        let paths: Vec<_> = report.tracks.iter().map(|t| t.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                root.join("Artist/Album/01.wav"),
+                root.join("Artist/Album/02.wav")
+            ]
+        );
+        assert_eq!(report.errors.len(), 1, "损坏的文件应单独报告");
+        assert_eq!(report.errors[0].path, root.join("broken.flac"));
+    }
+
+    #[test]
+    fn test_scanner_reports_unsupported_codecs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("01.opus"), opus_bytes(2, 50, &[])).unwrap();
+        std::fs::write(root.join("02.opus"), opus_bytes(6, 50, &[])).unwrap();
+        std::fs::write(root.join("03.wma"), b"windows media").unwrap();
+
+        let report = Scanner::new([root]).scan();
+        assert_eq!(report.tracks.len(), 1);
+        assert_eq!(report.tracks[0].path, root.join("01.opus"));
+        // 无法播放的文件既不会被忽略，也不会加入媒体库
+        let errors: Vec<_> = report.errors.iter().map(|e| e.path.clone()).collect();
+        assert_eq!(errors, vec![root.join("02.opus"), root.join("03.wma")]);
+        assert!(
+            report
+                .errors
+                .iter()
+                .all(|e| matches!(e.error, LibraryError::Unsupported(_)))
+        );
+    }
+
+    #[test]
+    fn test_scanner_missing_root() {
+        let report = Scanner::new(["/nonexistent/music/root"]).scan();
+        assert!(report.tracks.is_empty());
+        assert_eq!(report.errors.len(), 1);
+        assert!(matches!(report.errors[0].error, LibraryError::Io(_)));
+    }
+}
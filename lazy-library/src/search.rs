@@ -0,0 +1,521 @@
+//! 搜索模块。
+//!
+//! `SearchQuery` 解析搜索框中的文本：普通词在标题、艺术家、专辑和路径中模糊匹配，
+//! `artist:`、`album:`、`genre:` 和 `year:` 前缀只筛选对应字段。`SearchIndex` 预先把各字段转换为小写，
+//! 搜索时按匹配得分排序结果；搜索可以被取消，方便在后台线程中只保留最新的一次搜索。
+
+use std::{cmp::Reverse, error::Error, fmt, ops::RangeInclusive, path::PathBuf, sync::Arc};
+
+use lazy_core::track::Track;
+
+use crate::index::Library;
+
+/// 每检查多少首曲目检查一次搜索是否已被取消
+const CANCEL_CHECK_INTERVAL: usize = 1024;
+
+/// 子串匹配的基础得分，总是高于模糊匹配
+const SUBSTRING_SCORE: u32 = 1000;
+
+/// 模糊匹配的最高得分
+const MAX_FUZZY_SCORE: u32 = 900;
+
+/// 搜索文本无法解析。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryError(String);
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for QueryError {}
+
+/// 解析后的搜索条件，所有文本都已转换为小写。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// 模糊匹配的词，每个词都必须匹配
+    pub terms: Vec<String>,
+    /// 艺术家或专辑艺术家包含的文本
+    pub artist: Option<String>,
+    /// 专辑包含的文本
+    pub album: Option<String>,
+    /// 流派包含的文本
+    pub genre: Option<String>,
+    /// 年份范围
+    pub years: Option<RangeInclusive<i32>>,
+}
+
+impl SearchQuery {
+    /// 解析搜索文本。
+    ///
+    /// 文本按空白分词，双引号中的空白不分词，例如 `artist:"pink floyd" wall`。
+    /// 年份支持 `year:1995`、`year:1990..1999`、`year:1990..` 和 `year:..1999`。
+    /// 未知的前缀（例如 `foo:bar`）作为普通的词匹配。
+    pub fn parse(text: &str) -> Result<Self, QueryError> {
+        let mut query = SearchQuery::default();
+        for token in tokenize(text)? {
+            let token = token.to_lowercase();
+            let (field, value) = token.split_once(':').unwrap_or(("", &token));
+            let slot = match field {
+                "artist" => &mut query.artist,
+                "album" => &mut query.album,
+                "genre" => &mut query.genre,
+                "year" => {
+                    query.years = Some(parse_years(value)?);
+                    continue;
+                }
+                _ => {
+                    query.terms.push(token.clone());
+                    continue;
+                }
+            };
+            if value.is_empty() {
+                return Err(QueryError(format!("missing value for {field}:")));
+            }
+            *slot = Some(value.to_string());
+        }
+        Ok(query)
+    }
+
+    /// 判断搜索条件是否为空。
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+            && self.artist.is_none()
+            && self.album.is_none()
+            && self.genre.is_none()
+            && self.years.is_none()
+    }
+}
+
+/// 按空白分词，双引号中的内容作为一个整体（引号本身被去掉）。
+fn tokenize(text: &str) -> Result<Vec<String>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            c => token.push(c),
+        }
+    }
+    if quoted {
+        return Err(QueryError("unclosed quote".to_string()));
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// 解析年份范围。
+fn parse_years(value: &str) -> Result<RangeInclusive<i32>, QueryError> {
+    let year = |text: &str, default: i32| -> Result<i32, QueryError> {
+        if text.is_empty() {
+            return Ok(default);
+        }
+        text.parse()
+            .map_err(|_| QueryError(format!("invalid year: {text:?}")))
+    };
+    let (start, end) = match value.split_once("..") {
+        Some((start, end)) if !(start.is_empty() && end.is_empty()) => {
+            (year(start, i32::MIN)?, year(end, i32::MAX)?)
+        }
+        Some(_) => return Err(QueryError("empty year range".to_string())),
+        None if value.is_empty() => return Err(QueryError("missing value for year:".to_string())),
+        None => {
+            let year = year(value, 0)?;
+            (year, year)
+        }
+    };
+    if start > end {
+        return Err(QueryError(format!("empty year range: {value}")));
+    }
+    Ok(start..=end)
+}
+
+/// 搜索结果。
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchResults {
+    /// 搜索的原始文本
+    pub query: String,
+    /// 得分最高的曲目，按得分从高到低排序
+    pub tracks: Vec<Track>,
+    /// 匹配的曲目总数（可能多于 `tracks` 的数量）
+    pub total: usize,
+}
+
+/// 索引中的一首曲目，各字段已转换为小写。
+#[derive(Debug)]
+struct Entry {
+    path: PathBuf,
+    title: String,
+    artist: String, // 艺术家和专辑艺术家，以换行分隔
+    album: String,
+    genre: String,
+    location: String, // 文件路径
+    year: Option<i32>,
+}
+
+/// 可搜索的媒体库索引。
+///
+/// 构建索引需要遍历整个媒体库，应在媒体库发生变化后在后台重新构建。
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    library: Arc<Library>,
+    entries: Vec<Entry>, // 按艺术家、专辑、碟号、音轨号排序，得分相同的结果按此顺序排列
+}
+
+impl SearchIndex {
+    /// 为媒体库构建索引。
+    pub fn new(library: Arc<Library>) -> Self {
+        let mut tracks: Vec<&Track> = library.tracks().collect();
+        tracks.sort_by(|a, b| {
+            a.album_artist_or_artist()
+                .cmp(&b.album_artist_or_artist())
+                .then_with(|| crate::index::track_order(a, b))
+        });
+        let lower = |text: Option<&str>| text.unwrap_or_default().to_lowercase();
+        let entries = tracks
+            .into_iter()
+            .map(|track| Entry {
+                path: track.path.clone(),
+                title: track.display_title().to_lowercase(),
+                artist: format!(
+                    "{}\n{}",
+                    lower(track.artist.as_deref()),
+                    lower(track.album_artist.as_deref())
+                ),
+                album: lower(track.album.as_deref()),
+                genre: lower(track.genre.as_deref()),
+                location: track.path.to_string_lossy().to_lowercase(),
+                year: track.year,
+            })
+            .collect();
+        Self { library, entries }
+    }
+
+    /// 返回索引对应的媒体库。
+    pub fn library(&self) -> &Arc<Library> {
+        &self.library
+    }
+
+    /// 搜索媒体库，最多返回 `limit` 首曲目。搜索条件为空时没有结果。
+    pub fn search(&self, text: &str, query: &SearchQuery, limit: usize) -> SearchResults {
+        self.search_with_cancel(text, query, limit, || false)
+            .unwrap_or_default()
+    }
+
+    /// 搜索媒体库，`cancelled` 返回 `true` 时停止搜索并返回 `None`。
+    pub fn search_with_cancel(
+        &self,
+        text: &str,
+        query: &SearchQuery,
+        limit: usize,
+        cancelled: impl Fn() -> bool,
+    ) -> Option<SearchResults> {
+        let mut hits = Vec::new();
+        if !query.is_empty() {
+            for (index, entry) in self.entries.iter().enumerate() {
+                if index % CANCEL_CHECK_INTERVAL == 0 && cancelled() {
+                    return None;
+                }
+                if let Some(score) = score(entry, query) {
+                    hits.push((Reverse(score), index));
+                }
+            }
+        }
+        let total = hits.len();
+        // 只对前 limit 个结果完整排序
+        if hits.len() > limit && limit > 0 {
+            hits.select_nth_unstable(limit - 1);
+        }
+        hits.truncate(limit);
+        hits.sort_unstable();
+        let tracks = hits
+            .into_iter()
+            .filter_map(|(_, index)| self.library.get(&self.entries[index].path).cloned())
+            .collect();
+        Some(SearchResults {
+            query: text.to_string(),
+            tracks,
+            total,
+        })
+    }
+}
+
+/// 计算曲目的得分，不满足搜索条件时返回 `None`。
+fn score(entry: &Entry, query: &SearchQuery) -> Option<u32> {
+    let contains = |field: &str, filter: &Option<String>| {
+        filter
+            .as_deref()
+            .is_none_or(|filter| field.contains(filter))
+    };
+    if !(contains(&entry.artist, &query.artist)
+        && contains(&entry.album, &query.album)
+        && contains(&entry.genre, &query.genre))
+    {
+        return None;
+    }
+    if let Some(years) = &query.years
+        && !entry.year.is_some_and(|year| years.contains(&year))
+    {
+        return None;
+    }
+
+    // 每个词取得分最高的字段，标题优先于艺术家、专辑和路径
+    let mut total = 0;
+    for term in &query.terms {
+        total += [
+            (&entry.title, 30),
+            (&entry.artist, 20),
+            (&entry.album, 10),
+            (&entry.location, 0),
+        ]
+        .into_iter()
+        .filter_map(|(field, bonus)| fuzzy_score(term, field).map(|score| score + bonus))
+        .max()?;
+    }
+    Some(total)
+}
+
+/// 计算词在文本中的匹配得分，两者都应为小写。
+///
+/// 子串匹配的得分总是高于模糊匹配，出现在单词开头或文本开头时得分更高；
+/// 模糊匹配要求词中的字符按顺序出现，连续匹配和单词开头的匹配得分更高，间隔越多得分越低。
+pub fn fuzzy_score(term: &str, text: &str) -> Option<u32> {
+    if term.is_empty() {
+        return Some(0);
+    }
+    if let Some(position) = text.find(term) {
+        let word_start = text[..position]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !c.is_alphanumeric());
+        let mut score = SUBSTRING_SCORE;
+        if word_start {
+            score += 100;
+        }
+        if position == 0 {
+            score += 50;
+        }
+        if text.len() == term.len() {
+            score += 50;
+        }
+        return Some(score);
+    }
+
+    let mut wanted = term.chars().peekable();
+    let (mut score, mut gaps) = (0u32, 0u32);
+    let (mut started, mut previous_matched) = (false, false);
+    let mut previous: Option<char> = None;
+    for c in text.chars() {
+        let Some(&next) = wanted.peek() else {
+            break;
+        };
+        if c == next {
+            wanted.next();
+            score += 10;
+            if previous_matched {
+                score += 15;
+            }
+            if previous.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 20;
+            }
+            started = true;
+            previous_matched = true;
+        } else {
+            if started {
+                gaps += 1;
+            }
+            previous_matched = false;
+        }
+        previous = Some(c);
+    }
+    if wanted.peek().is_some() {
+        return None;
+    }
+    Some(score.saturating_sub(gaps.min(50)).clamp(1, MAX_FUZZY_SCORE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(path: &str, title: &str, artist: &str, album: &str, year: i32) -> Track {
+        Track {
+            title: Some(title.to_string()),
+            artist: Some(artist.to_string()),
+            album: Some(album.to_string()),
+            year: Some(year),
+            genre: Some("Rock".to_string()),
+            ..Track::new(path)
+        }
+    }
+
+    fn index() -> SearchIndex {
+        SearchIndex::new(Arc::new(Library::from_tracks([
+            track(
+                "/m/pf/wall/01.flac",
+                "In the Flesh?",
+                "Pink Floyd",
+                "The Wall",
+                1979,
+            ),
+            track(
+                "/m/pf/wall/02.flac",
+                "Another Brick in the Wall",
+                "Pink Floyd",
+                "The Wall",
+                1979,
+            ),
+            track(
+                "/m/pf/moon/01.flac",
+                "Money",
+                "Pink Floyd",
+                "The Dark Side of the Moon",
+                1973,
+            ),
+            track("/m/other/01.flac", "Wallflower", "Someone", "Bricks", 1995),
+            track("/m/other/02.flac", "Feel", "Pinkish", "Flowers", 1999),
+        ])))
+    }
+
+    fn titles(results: &SearchResults) -> Vec<&str> {
+        results
+            .tracks
+            .iter()
+            .map(|t| t.title.as_deref().unwrap())
+            .collect()
+    }
+
+    fn search(index: &SearchIndex, text: &str) -> SearchResults {
+        index.search(text, &SearchQuery::parse(text).unwrap(), 10)
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query =
+            SearchQuery::parse(r#"Wall artist:"Pink Floyd" year:1970..1979 genre:rock foo:bar"#)
+                .unwrap();
+        assert_eq!(
+            query,
+            SearchQuery {
+                terms: vec!["wall".to_string(), "foo:bar".to_string()],
+                artist: Some("pink floyd".to_string()),
+                album: None,
+                genre: Some("rock".to_string()),
+                years: Some(1970..=1979),
+            }
+        );
+        assert_eq!(
+            SearchQuery::parse("year:1995").unwrap().years,
+            Some(1995..=1995)
+        );
+        assert_eq!(
+            SearchQuery::parse("year:..1990").unwrap().years,
+            Some(i32::MIN..=1990)
+        );
+        assert_eq!(
+            SearchQuery::parse("year:2000..").unwrap().years,
+            Some(2000..=i32::MAX)
+        );
+        assert!(SearchQuery::parse("   ").unwrap().is_empty());
+        for invalid in [
+            "year:abc",
+            "year:..",
+            "year:1999..1990",
+            "year:",
+            "artist:",
+            "\"open",
+        ] {
+            assert!(SearchQuery::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(
+            fuzzy_score("wall", "the wall") > fuzzy_score("wall", "wallflower").map(|s| s - 60)
+        );
+        assert!(fuzzy_score("wall", "wall") > fuzzy_score("wall", "the wall"));
+        assert!(fuzzy_score("wall", "the wall") > fuzzy_score("wall", "firewall"));
+        assert!(fuzzy_score("wall", "firewall") > fuzzy_score("wll", "wall"));
+        assert!(fuzzy_score("abw", "another brick in the wall") > fuzzy_score("abw", "xaxxbxxw"));
+        assert_eq!(fuzzy_score("xyz", "wall"), None);
+        assert_eq!(fuzzy_score("llaw", "wall"), None, "字符必须按顺序出现");
+    }
+
+    #[test]
+    fn test_search_ranks_results() {
+        let index = index();
+        let results = search(&index, "wall");
+        assert_eq!(results.total, 3);
+        assert_eq!(
+            titles(&results),
+            ["Wallflower", "Another Brick in the Wall", "In the Flesh?"],
+            "标题匹配优先于专辑匹配"
+        );
+
+        // 模糊匹配
+        assert_eq!(
+            titles(&search(&index, "abitw")),
+            ["Another Brick in the Wall"]
+        );
+        // 多个词都必须匹配
+        assert_eq!(titles(&search(&index, "pink money")), ["Money"]);
+        // 路径也参与匹配
+        assert_eq!(search(&index, "/m/other").total, 2);
+        assert!(search(&index, "").tracks.is_empty());
+    }
+
+    #[test]
+    fn test_search_field_filters() {
+        let index = index();
+        let results = search(&index, "artist:floyd");
+        assert_eq!(
+            titles(&results),
+            ["Money", "In the Flesh?", "Another Brick in the Wall"],
+            "只有筛选条件时按专辑和音轨排序"
+        );
+        assert_eq!(
+            titles(&search(&index, "year:1990..1999")),
+            ["Feel", "Wallflower"]
+        );
+        assert_eq!(
+            titles(&search(&index, "album:wall brick")),
+            ["Another Brick in the Wall"]
+        );
+        assert_eq!(search(&index, "genre:jazz").total, 0);
+    }
+
+    #[test]
+    fn test_search_limit_and_cancel() {
+        let tracks = (0..100_000).map(|i| {
+            track(
+                &format!("/m/{i}.flac"),
+                &format!("Song {i}"),
+                "Artist",
+                "Album",
+                2000,
+            )
+        });
+        let index = SearchIndex::new(Arc::new(Library::from_tracks(tracks)));
+        let query = SearchQuery::parse("song 4242").unwrap();
+        let results = index.search("song 4242", &query, 5);
+        assert!(results.total > 5);
+        assert_eq!(results.tracks.len(), 5);
+        assert_eq!(results.tracks[0].title.as_deref(), Some("Song 4242"));
+
+        assert!(
+            index
+                .search_with_cancel("song", &query, 5, || true)
+                .is_none()
+        );
+    }
+}
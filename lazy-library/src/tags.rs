@@ -0,0 +1,303 @@
+//! 读取音频文件标签的模块。
+//!
+//! MP3 的 ID3v2、FLAC/Ogg/Opus 的 Vorbis comments 和 WAV 的 RIFF INFO 由 `symphonia` 读取，
+//! MP4/M4A 由 `mp4` 模块读取。播放器无法解码的文件会返回错误，不会加入媒体库。
+
+use std::{fs::File, path::Path, time::Duration};
+
+use lazy_core::track::Track;
+use symphonia::core::{
+    codecs::{CODEC_TYPE_NULL, CODEC_TYPE_OPUS, CodecParameters},
+    formats::FormatOptions,
+    io::MediaSourceStream,
+    meta::{MetadataOptions, MetadataRevision, StandardTagKey, Value},
+    probe::Hint,
+};
+
+use crate::{error::LibraryError, mp4};
+
+/// 扫描器识别的音频文件扩展名（小写）。
+///
+/// 包括播放器无法解码的常见格式，扫描时这些文件会被逐个报告为错误，而不是被悄悄忽略。
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "mp3", "flac", "ogg", "oga", "opus", "wav", "m4a", "mp4", "aac", "aif", "aiff", "ape", "wv",
+    "wma", "mka", "dsf", "mpc",
+];
+
+/// 判断文件是否是扫描器识别的音频文件。
+pub fn is_supported(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SUPPORTED_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// 读取一个音频文件的标签和时长。
+///
+/// 没有标签的文件也会返回一个 `Track`，只是对应字段为 `None`；
+/// 文件无法打开、格式或编码不受支持时返回错误。
+pub fn read_track(path: impl AsRef<Path>) -> Result<Track, LibraryError> {
+    let path = path.as_ref();
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+
+    match ext.as_deref() {
+        Some("m4a" | "mp4") => mp4::read_track(path),
+        _ => read_symphonia_track(path),
+    }
+}
+
+/// 通过 `symphonia` 探测文件并读取标签。
+fn read_symphonia_track(path: &Path) -> Result<Track, LibraryError> {
+    let file = File::open(path)?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let mut probed = symphonia::default::get_probe().format(
+        &hint,
+        mss,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+
+    let audio = probed
+        .format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| LibraryError::Unsupported("no audio track".to_string()))?;
+
+    // 确认编码可以被解码，否则这首曲目无法播放
+    let params = &audio.codec_params;
+    if !is_decodable(params) {
+        return Err(LibraryError::Unsupported(
+            "no decoder for the audio codec".to_string(),
+        ));
+    }
+
+    let mut track = Track::new(path);
+    track.duration = params.n_frames.and_then(|n| match params.time_base {
+        Some(tb) => Some(tb.calc_time(n).into()),
+        None => params
+            .sample_rate
+            .map(|rate| Duration::from_secs_f64(n as f64 / rate as f64)),
+    });
+
+    // 先应用容器外的标签（如 ID3v2），再用容器内的标签覆盖
+    if let Some(revision) = probed
+        .metadata
+        .get()
+        .as_mut()
+        .and_then(|m| m.skip_to_latest())
+    {
+        apply_revision(&mut track, revision);
+    }
+    if let Some(revision) = probed.format.metadata().skip_to_latest() {
+        apply_revision(&mut track, revision);
+    }
+
+    Ok(track)
+}
+
+/// 判断播放器能否解码这条音轨。
+///
+/// 播放器除了 `symphonia` 的解码器外，还通过 libopus 解码单声道和立体声的 Opus。
+fn is_decodable(params: &CodecParameters) -> bool {
+    if params.codec == CODEC_TYPE_OPUS {
+        return params.channels.is_none_or(|channels| channels.count() <= 2);
+    }
+    symphonia::default::get_codecs()
+        .get_codec(params.codec)
+        .is_some()
+}
+
+/// 将一组标签写入曲目。
+fn apply_revision(track: &mut Track, revision: &MetadataRevision) {
+    for tag in revision.tags() {
+        let Some(key) = tag.std_key else { continue };
+        let text = match &tag.value {
+            // RIFF INFO 等格式的字符串可能带有结尾的 NUL
+            Value::String(s) => s
+                .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+                .to_string(),
+            Value::UnsignedInt(n) => n.to_string(),
+            Value::SignedInt(n) => n.to_string(),
+            _ => continue,
+        };
+        if text.is_empty() {
+            continue;
+        }
+
+        match key {
+            StandardTagKey::TrackTitle => track.title = Some(text),
+            StandardTagKey::Artist => track.artist = Some(text),
+            StandardTagKey::AlbumArtist => track.album_artist = Some(text),
+            StandardTagKey::Album => track.album = Some(text),
+            StandardTagKey::Genre => track.genre = Some(text),
+            StandardTagKey::TrackNumber => track.track_number = parse_number(&text),
+            StandardTagKey::DiscNumber => track.disc_number = parse_number(&text),
+            StandardTagKey::Date => track.year = parse_year(&text).or(track.year),
+            _ => {}
+        }
+    }
+}
+
+/// 解析 `3` 或 `3/12` 形式的编号。
+pub(crate) fn parse_number(text: &str) -> Option<u32> {
+    text.split('/').next()?.trim().parse().ok()
+}
+
+/// 从 `1999`、`1999-03-01` 等日期中解析年份。
+pub(crate) fn parse_year(text: &str) -> Option<i32> {
+    let digits: String = text
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    if digits.len() == 4 {
+        digits.parse().ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{id3v2_tag, opus_bytes, wav_bytes};
+
+    #[test]
+    fn test_parse_number_and_year() {
+        assert_eq!(parse_number("3"), Some(3));
+        assert_eq!(parse_number(" 7/12"), Some(7));
+        assert_eq!(parse_number("A1"), None);
+
+        assert_eq!(parse_year("1999"), Some(1999));
+        assert_eq!(parse_year("2004-05-17T00:00:00Z"), Some(2004));
+        assert_eq!(parse_year("unknown"), None);
+    }
+
+    #[test]
+    fn test_is_supported() {
+        assert!(is_supported(Path::new("/a/b.FLAC")));
+        assert!(is_supported(Path::new("b.m4a")));
+        assert!(is_supported(Path::new("c.opus")));
+        assert!(is_supported(Path::new("d.wma")));
+        assert!(!is_supported(Path::new("cover.jpg")));
+        assert!(!is_supported(Path::new("README")));
+    }
+
+    #[test]
+    fn test_read_track_riff_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("info.wav");
+        let info: &[(&[u8; 4], &str)] = &[
+            (b"INAM", "Song"),
+            (b"IART", "Artist"),
+            (b"IPRD", "Album"),
+            (b"ICRD", "1999"),
+            (b"IGNR", "Rock"),
+            (b"IPRT", "4"),
+        ];
+        std::fs::write(&path, wav_bytes(8_000, 16_000, info)).unwrap();
+
+        let track = read_track(&path).unwrap();
+        assert_eq!(track.path, path);
+        assert_eq!(track.title.as_deref(), Some("Song"));
+        assert_eq!(track.artist.as_deref(), Some("Artist"));
+        assert_eq!(track.album.as_deref(), Some("Album"));
+        assert_eq!(track.year, Some(1999));
+        assert_eq!(track.genre.as_deref(), Some("Rock"));
+        assert_eq!(track.track_number, Some(4));
+        assert_eq!(track.duration, Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_read_track_id3v2() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("id3.wav");
+        let mut bytes = id3v2_tag(&[
+            (b"TIT2", "Title"),
+            (b"TPE1", "Artist"),
+            (b"TPE2", "Album Artist"),
+            (b"TALB", "Album"),
+            (b"TRCK", "2/10"),
+            (b"TPOS", "1/2"),
+            (b"TYER", "2001"),
+            (b"TCON", "Jazz"),
+        ]);
+        bytes.extend(wav_bytes(8_000, 8_000, &[]));
+        std::fs::write(&path, bytes).unwrap();
+
+        let track = read_track(&path).unwrap();
+        assert_eq!(track.title.as_deref(), Some("Title"));
+        assert_eq!(track.artist.as_deref(), Some("Artist"));
+        assert_eq!(track.album_artist.as_deref(), Some("Album Artist"));
+        assert_eq!(track.album.as_deref(), Some("Album"));
+        assert_eq!(track.track_number, Some(2));
+        assert_eq!(track.disc_number, Some(1));
+        assert_eq!(track.year, Some(2001));
+        assert_eq!(track.genre.as_deref(), Some("Jazz"));
+        assert_eq!(track.duration, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_read_track_without_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plain.wav");
+        std::fs::write(&path, wav_bytes(8_000, 4_000, &[])).unwrap();
+
+        let track = read_track(&path).unwrap();
+        assert_eq!(track.title, None);
+        assert_eq!(track.display_title(), "plain");
+    }
+
+    #[test]
+    fn test_read_track_opus() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("song.opus");
+        let comments = [
+            ("TITLE", "Title"),
+            ("ARTIST", "Artist"),
+            ("TRACKNUMBER", "3"),
+        ];
+        std::fs::write(&path, opus_bytes(2, 100, &comments)).unwrap();
+
+        let track = read_track(&path).unwrap();
+        assert_eq!(track.title.as_deref(), Some("Title"));
+        assert_eq!(track.artist.as_deref(), Some("Artist"));
+        assert_eq!(track.track_number, Some(3));
+        assert_eq!(track.duration, Some(Duration::from_secs(2)));
+
+        // 播放器只能解码单声道和立体声的 Opus
+        std::fs::write(&path, opus_bytes(6, 1, &comments)).unwrap();
+        assert!(matches!(
+            read_track(&path),
+            Err(LibraryError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_track_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.flac");
+        std::fs::write(&path, b"not a flac file").unwrap();
+
+        assert!(read_track(&path).is_err());
+        let path = dir.path().join("song.wma");
+        std::fs::write(&path, b"0&\xb2u\x8ef\xcf\x11 asf header").unwrap();
+        assert!(matches!(
+            read_track(&path),
+            Err(LibraryError::Unsupported(_))
+        ));
+        assert!(matches!(
+            read_track(dir.path().join("missing.mp3")),
+            Err(LibraryError::Io(_))
+        ));
+    }
+}
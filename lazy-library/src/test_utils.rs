@@ -0,0 +1,113 @@
+//! 测试辅助函数。
+
+/// 生成一个静音的 16 位单声道 WAV 文件内容，`info` 中的标签写入 `LIST/INFO` 块。
+pub(crate) fn wav_bytes(sample_rate: u32, frames: u32, info: &[(&[u8; 4], &str)]) -> Vec<u8> {
+    let mut fmt = Vec::new();
+    fmt.extend(1u16.to_le_bytes()); // PCM
+    fmt.extend(1u16.to_le_bytes()); // 单声道
+    fmt.extend(sample_rate.to_le_bytes());
+    fmt.extend((sample_rate * 2).to_le_bytes());
+    fmt.extend(2u16.to_le_bytes());
+    fmt.extend(16u16.to_le_bytes());
+
+    let mut list = b"INFO".to_vec();
+    for (id, text) in info {
+        let mut value = text.as_bytes().to_vec();
+        value.push(0);
+        list.extend(chunk(id, &value));
+    }
+
+    let mut body = b"WAVE".to_vec();
+    body.extend(chunk(b"fmt ", &fmt));
+    if !info.is_empty() {
+        body.extend(chunk(b"LIST", &list));
+    }
+    body.extend(chunk(b"data", &vec![0; frames as usize * 2]));
+
+    chunk(b"RIFF", &body)
+}
+
+/// 生成一个 ID3v2.3 标签，只包含 ISO-8859-1 编码的文本帧。
+pub(crate) fn id3v2_tag(frames: &[(&[u8; 4], &str)]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (id, text) in frames {
+        body.extend(*id);
+        body.extend((text.len() as u32 + 1).to_be_bytes());
+        body.extend([0, 0, 0]); // 标志位 + 编码
+        body.extend(text.as_bytes());
+    }
+
+    let size = body.len() as u32;
+    let mut tag = b"ID3".to_vec();
+    tag.extend([3, 0, 0]);
+    // 同步安全整数：每个字节只使用低 7 位
+    tag.extend([
+        (size >> 21) as u8 & 0x7f,
+        (size >> 14) as u8 & 0x7f,
+        (size >> 7) as u8 & 0x7f,
+        size as u8 & 0x7f,
+    ]);
+    tag.extend(body);
+    tag
+}
+
+/// 生成一个 Ogg Opus 文件内容，`comments` 写入 `OpusTags` 头。
+///
+/// 每个数据包只有一个表示 20 毫秒的 TOC 字节，不能解码，只用于读取标签和时长。
+pub(crate) fn opus_bytes(channels: u8, packets: u64, comments: &[(&str, &str)]) -> Vec<u8> {
+    use ogg::{PacketWriteEndInfo, PacketWriter};
+
+    const PRE_SKIP: u64 = 312;
+    let mut head = b"OpusHead".to_vec();
+    head.extend([1, channels]);
+    head.extend((PRE_SKIP as u16).to_le_bytes());
+    head.extend(48_000u32.to_le_bytes());
+    head.extend([0, 0]); // 输出增益
+    if channels <= 2 {
+        head.push(0);
+    } else {
+        // 多声道使用 Vorbis 声道映射：每个声道一个独立的流
+        head.extend([1, channels, 0]);
+        head.extend(0..channels);
+    }
+
+    let mut tags = b"OpusTags".to_vec();
+    tags.extend(0u32.to_le_bytes()); // 空的 vendor 字符串
+    tags.extend((comments.len() as u32).to_le_bytes());
+    for (key, value) in comments {
+        let comment = format!("{key}={value}");
+        tags.extend((comment.len() as u32).to_le_bytes());
+        tags.extend(comment.as_bytes());
+    }
+
+    let mut writer = PacketWriter::new(Vec::new());
+    for header in [head, tags] {
+        writer
+            .write_packet(header.into_boxed_slice(), 1, PacketWriteEndInfo::EndPage, 0)
+            .unwrap();
+    }
+    for i in 1..=packets {
+        let end = if i == packets {
+            PacketWriteEndInfo::EndStream
+        } else {
+            PacketWriteEndInfo::EndPage
+        };
+        // 颗粒位置包括预跳过的帧
+        let granule = i * 960 + PRE_SKIP;
+        writer
+            .write_packet(Box::new([0xfc]), 1, end, granule)
+            .unwrap();
+    }
+    writer.into_inner()
+}
+
+/// 生成一个 RIFF 块，奇数长度时补齐一个字节。
+fn chunk(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut out = id.to_vec();
+    out.extend((data.len() as u32).to_le_bytes());
+    out.extend(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+    out
+}
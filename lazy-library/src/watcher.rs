@@ -0,0 +1,264 @@
+//! 文件系统监视模块。
+//!
+//! `LibraryWatcher` 通过 inotify（其他平台上为对应的原生接口）监视音乐根目录，
+//! 将一段时间内的事件合并（去抖）后只刷新受影响的文件或目录，并把结果作为 `LibraryEvent` 发送给应用程序。
+//! 重命名和批量移动会被拆分为“删除旧路径”和“添加新路径”。
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use lazy_core::publisher::Publisher;
+use notify_debouncer_full::{
+    DebounceEventResult, Debouncer, RecommendedCache, new_debouncer,
+    notify::{
+        self, EventKind, RecommendedWatcher, RecursiveMode,
+        event::{ModifyKind, RenameMode},
+    },
+};
+
+use crate::{
+    db::{LibraryDb, RescanReport},
+    error::LibraryError,
+    scanner::Scanner,
+    tags::is_supported,
+};
+
+/// 默认的去抖时间，复制整张专辑时的大量事件会被合并为一次刷新
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// 媒体库在后台发生的事件。
+#[derive(Debug)]
+pub enum LibraryEvent {
+    /// 扫描或文件系统变化导致媒体库内容发生变化
+    Scanned(RescanReport),
+    /// 达到系统的文件监视数量上限，部分目录的变化将无法被监视到
+    WatchLimitReached,
+    /// 监视过程中发生的其他错误
+    Error(String),
+}
+
+/// 音乐目录监视器，被丢弃时停止监视。
+pub struct LibraryWatcher {
+    /// 去抖后的文件系统监视器
+    _debouncer: Debouncer<RecommendedWatcher, RecommendedCache>,
+}
+
+impl LibraryWatcher {
+    /// 开始监视音乐根目录。
+    ///
+    /// 设置了 `db_path` 时变化会同步到数据库，否则直接重新读取变化的文件。
+    /// 单个根目录无法监视（例如达到监视数量上限）时会发送对应的事件并继续监视其余目录；
+    /// 只有无法创建监视器本身时才返回错误。
+    pub fn watch(
+        roots: &[PathBuf],
+        db_path: Option<PathBuf>,
+        debounce: Duration,
+        events: impl Into<Publisher<LibraryEvent>>,
+    ) -> Result<Self, LibraryError> {
+        let events = events.into();
+        let handler_events = events.clone();
+        let mut db = None;
+        let mut debouncer = new_debouncer(debounce, None, move |result: DebounceEventResult| {
+            let event = match result {
+                Ok(debounced) => {
+                    let paths = changed_paths(debounced.iter().map(|e| &e.event));
+                    if paths.is_empty() {
+                        return;
+                    }
+                    match refresh(&mut db, db_path.as_deref(), &paths) {
+                        Ok(report) => LibraryEvent::Scanned(report),
+                        Err(e) => LibraryEvent::Error(e.to_string()),
+                    }
+                }
+                Err(errors) => match errors.into_iter().next() {
+                    Some(error) => watch_error(error),
+                    None => return,
+                },
+            };
+            handler_events.publish(event);
+        })
+        .map_err(|e| LibraryError::Io(std::io::Error::other(e)))?;
+
+        for root in roots {
+            if let Err(error) = debouncer.watch(root, RecursiveMode::Recursive) {
+                events.publish(watch_error(error));
+            }
+        }
+
+        Ok(Self {
+            _debouncer: debouncer,
+        })
+    }
+}
+
+/// 将监视错误转换为事件。
+fn watch_error(error: notify::Error) -> LibraryEvent {
+    match error.kind {
+        notify::ErrorKind::MaxFilesWatch => LibraryEvent::WatchLimitReached,
+        _ => LibraryEvent::Error(error.to_string()),
+    }
+}
+
+/// 从一批文件系统事件中收集需要刷新的路径。
+fn changed_paths<'a>(events: impl Iterator<Item = &'a notify::Event>) -> Vec<PathBuf> {
+    let mut paths = BTreeSet::new();
+    for event in events {
+        let relevant = match event.kind {
+            EventKind::Create(_) | EventKind::Remove(_) => true,
+            // 重命名（包括 `Both` 形式的旧路径/新路径对）和内容修改
+            EventKind::Modify(ModifyKind::Name(
+                RenameMode::From | RenameMode::To | RenameMode::Both | RenameMode::Any,
+            )) => true,
+            EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Any) => true,
+            _ => false,
+        };
+        if relevant {
+            paths.extend(event.paths.iter().filter(|p| is_candidate(p)).cloned());
+        }
+    }
+
+    // 父目录已经在列表中时，子路径不需要单独刷新
+    let mut result: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        if !result.last().is_some_and(|parent| path.starts_with(parent)) {
+            result.push(path);
+        }
+    }
+    result
+}
+
+/// 判断路径是否可能影响媒体库：音频文件、目录，或已被删除的路径。
+fn is_candidate(path: &Path) -> bool {
+    is_supported(path) || path.is_dir() || !path.exists()
+}
+
+/// 刷新变化的路径。
+fn refresh(
+    db: &mut Option<LibraryDb>,
+    db_path: Option<&Path>,
+    paths: &[PathBuf],
+) -> Result<RescanReport, LibraryError> {
+    let Some(db_path) = db_path else {
+        return Ok(refresh_without_db(paths));
+    };
+    if db.is_none() {
+        *db = Some(LibraryDb::open(db_path)?);
+    }
+    db.as_mut().expect("数据库已打开").refresh(paths)
+}
+
+/// 没有数据库时直接重新读取仍然存在的路径，已不存在的路径作为删除项。
+fn refresh_without_db(paths: &[PathBuf]) -> RescanReport {
+    let (existing, removed): (Vec<_>, Vec<_>) =
+        paths.iter().cloned().partition(|path| path.exists());
+    let report = Scanner::new(existing).scan();
+    RescanReport {
+        added: report.tracks,
+        removed,
+        errors: report.errors,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{index::Library, test_utils::wav_bytes};
+    use notify::event::{AccessKind, CreateKind, RemoveKind};
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn event(kind: EventKind, paths: &[&Path]) -> notify::Event {
+        notify::Event {
+            kind,
+            paths: paths.iter().map(|p| p.to_path_buf()).collect(),
+            attrs: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_changed_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let album = dir.path().join("album");
+        std::fs::create_dir(&album).unwrap();
+        std::fs::write(album.join("01.flac"), b"").unwrap();
+        std::fs::write(dir.path().join("cover.jpg"), b"").unwrap();
+
+        let events = [
+            event(EventKind::Create(CreateKind::Folder), &[&album]),
+            event(
+                EventKind::Create(CreateKind::File),
+                &[&album.join("01.flac")],
+            ),
+            event(
+                EventKind::Create(CreateKind::File),
+                &[&dir.path().join("cover.jpg")],
+            ),
+            event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                &[&dir.path().join("gone"), &dir.path().join("album")],
+            ),
+            event(
+                EventKind::Remove(RemoveKind::File),
+                &[&dir.path().join("deleted.mp3")],
+            ),
+            event(EventKind::Access(AccessKind::Any), &[dir.path()]),
+        ];
+
+        assert_eq!(
+            changed_paths(events.iter()),
+            vec![
+                album.clone(),
+                dir.path().join("deleted.mp3"),
+                dir.path().join("gone"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_refresh_without_db() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("new.wav");
+        std::fs::write(&path, wav_bytes(8_000, 800, &[])).unwrap();
+
+        let mut library =
+            Library::from_tracks([lazy_core::track::Track::new(dir.path().join("old/01.wav"))]);
+        let report = refresh_without_db(&[path.clone(), dir.path().join("old")]);
+        assert!(report.apply_to(&mut library).is_empty());
+        assert_eq!(library.len(), 1);
+        assert!(library.get(&path).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_watcher_reports_new_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let (tx, mut rx) = unbounded_channel();
+        let _watcher = LibraryWatcher::watch(
+            std::slice::from_ref(&root),
+            None,
+            Duration::from_millis(100),
+            tx,
+        )
+        .unwrap();
+
+        // 模拟复制进一整张专辑
+        let album = root.join("album");
+        std::fs::create_dir(&album).unwrap();
+        std::fs::write(album.join("01.wav"), wav_bytes(8_000, 800, &[])).unwrap();
+
+        let mut library = Library::new();
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        while library.get(&album.join("01.wav")).is_none() {
+            let event = tokio::time::timeout_at(deadline, rx.recv())
+                .await
+                .expect("等待文件系统事件超时")
+                .unwrap();
+            if let LibraryEvent::Scanned(report) = event {
+                report.apply_to(&mut library);
+            }
+        }
+    }
+}
@@ -6,6 +6,10 @@
 //! 宏能够生成一个 `match` 语句，将不同的 `TuiEnent` 变体分派给相应的子组件
 //! (widget) 的方法进行处理。
 //!
+//! 最后一个方法的返回值通过 `EventResult::from` 转换为处理结果：返回 `()` 的方法表示
+//! 处理了事件，返回 `bool` 或 `EventResult` 的方法可以决定事件是否继续传递。
+//! 没有映射的事件交给结构体的 `fallback_handle` 方法。
+//!
 //! # 使用方法
 //!
 //! 在一个 `impl TuiEventHandle for YourStruct {}` 块上添加 `#[auto_delegate_events(...)]` 属性。
@@ -28,7 +32,7 @@
 //!     TuiEnent::Playback => (PlaybackTui, toggle_state()),
 //!     TuiEnent::Volume(delta) => (VolumeTui, adjust_volume(delta)),
 //!     TuiEnent::Track(track) => (TrackTui, set_track(track)),
-//!     TuiEnent::PlaybackProgress(duration, progress) => (PlaybackProgressTui, set_progress(progress); set_duration(duration))
+//!     TuiEnent::PlaybackProgress(progress, duration) => (PlaybackProgressTui, set_progress(progress); set_duration(duration))
 //! )]
 //! impl TuiEventHandle for PlayerTui {}
 //! ```
@@ -36,20 +40,18 @@
 //! 上述代码会为 `PlayerTui` 生成如下 `event_handle` 方法：
 //!
 //! ```rust,ignore
-//! fn event_handle(&mut self, event: TuiEnent) {
+//! fn event_handle(&mut self, event: TuiEnent) -> EventResult {
 //!     match event {
-//!         TuiEnent::Playback => {
-//!             if let Some(w) = self.get_widget_mut::<PlaybackTui>() {
-//!                 w.toggle_state();
-//!             }
+//!         TuiEnent::Playback => match self.get_widget_mut::<PlaybackTui>() {
+//!             Some(w) => EventResult::from(w.toggle_state()),
+//!             None => EventResult::Ignored,
 //!         },
-//!         TuiEnent::Volume(delta) => {
-//!             if let Some(w) = self.get_widget_mut::<VolumeTui>() {
-//!                 w.adjust_volume(delta);
-//!             }
+//!         TuiEnent::Volume(delta) => match self.get_widget_mut::<VolumeTui>() {
+//!             Some(w) => EventResult::from(w.adjust_volume(delta)),
+//!             None => EventResult::Ignored,
 //!         },
 //!         // ... 其他事件臂
-//!         _ => (),
+//!         event => self.fallback_handle(event),
 //!     }
 //! }
 //! ```
@@ -136,15 +138,19 @@ pub fn expand_event_mappings(
         .map(|f| {
             let event = &f.event;
             let ty = &f.ty;
-            let methods = &f.methods;
+            // 解析时保证了至少有一个方法
+            let (last, methods) = f.methods.split_last().unwrap();
             quote! {
-                // 生成 `TuiEnent::Variant => { ... }`
-                #event => {
-                    // `self.get_widget_mut` 是 `HasWidgets` Trait 的方法
-                    if let Some(w) = self.get_widget_mut::<#ty>() {
-                        // 在获取到的 widget 上执行所有指定的方法
-                        #(w.#methods);*
+                // 生成 `TuiEnent::Variant => match ... { ... }`
+                // `self.get_widget_mut` 是 `HasWidgets` Trait 的方法
+                #event => match self.get_widget_mut::<#ty>() {
+                    Some(w) => {
+                        // 在获取到的 widget 上执行所有指定的方法，最后一个方法的返回值作为处理结果
+                        #(w.#methods;)*
+                        EventResult::from(w.#last)
                     }
+                    // 没有这个子组件时不处理事件
+                    None => EventResult::Ignored,
                 }
             }
         })
@@ -152,12 +158,12 @@ pub fn expand_event_mappings(
 
     // 4. 构建完整的 `event_handle` 方法
     let fn_event_handle = parse_quote! {
-        fn event_handle(&mut self, event: TuiEnent) {
+        fn event_handle(&mut self, event: TuiEnent) -> EventResult {
             match event {
                 // 插入所有生成的 match 臂
                 #(#mapping_tokens),*
-                // 默认臂，对于未处理的事件不执行任何操作
-                _ => (),
+                // 后备臂，没有映射的事件交给 `fallback_handle`
+                event => self.fallback_handle(event),
             }
         }
     };
@@ -13,7 +13,6 @@ use crate::{
     has_tui_style::expand_has_tui_style,
 };
 
-use proc_macro::token_stream;
 use syn::DeriveInput;
 
 /// 为结构体字段自动生成 getter 和/或 setter 方法。
@@ -65,7 +64,7 @@ pub fn derive_acessor(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 ///
 /// # 辅助属性
 ///
-/// - `skip`: 如果一个字段带有 `#[DeriveHasTuiStyle(skip)]`，宏将跳过该字段。
+/// - `Skip`: 如果一个字段带有 `#[DeriveHasTuiStyle(Skip)]`，宏将跳过该字段。
 ///
 /// # Example
 ///
@@ -82,8 +81,8 @@ pub fn derive_acessor(input: proc_macro::TokenStream) -> proc_macro::TokenStream
 ///     // 宏会自动为 MyWidget 实现 HasTitleStyle 和 HasTitleStyleSetter
 ///     title: TitleStyle,
 ///
-///     // 使用 `skip` 属性，这个字段将被宏忽略
-///     #[DeriveHasTuiStyle(skip)]
+///     // 使用 `Skip` 属性，这个字段将被宏忽略
+///     #[DeriveHasTuiStyle(Skip)]
 ///     other_style: TuiStyle,
 /// }
 ///
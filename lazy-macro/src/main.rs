@@ -1,8 +0,0 @@
-use lazy_macro::Accessor;
-
-#[derive(Accessor)]
-struct test {
-    name: String,
-    age: u8,
-}
-fn main() {}
@@ -0,0 +1,358 @@
+//! 基于 `symphonia` 的音频解码器模块。
+//!
+//! `Decoder` 打开本地音频文件，并以交错排列的 `f32` 采样块的形式逐块输出解码结果。
+//! 启用 `opus` 特性时，Ogg 容器中的 Opus 流由 `OpusDecoder` 解码。
+
+use std::{fs::File, path::Path, sync::LazyLock, time::Duration};
+
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{CODEC_TYPE_NULL, CodecRegistry, Decoder as CodecDecoder, DecoderOptions},
+    errors::Error as SymphoniaError,
+    formats::{FormatOptions, FormatReader, SeekMode, SeekTo},
+    io::MediaSourceStream,
+    meta::MetadataOptions,
+    probe::Hint,
+    units::{Time, TimeBase},
+};
+
+use crate::error::PlayerError;
+
+/// 音频流的格式信息（采样率和声道数）。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AudioSpec {
+    /// 采样率（Hz）
+    pub sample_rate: u32,
+    /// 声道数
+    pub channels: u16,
+}
+
+impl AudioSpec {
+    /// 将帧数换算为时长。
+    pub fn frames_to_duration(&self, frames: u64) -> Duration {
+        Duration::from_secs_f64(frames as f64 / self.sample_rate as f64)
+    }
+
+    /// 将时长换算为帧数。
+    pub fn duration_to_frames(&self, duration: Duration) -> u64 {
+        (duration.as_secs_f64() * self.sample_rate as f64).round() as u64
+    }
+}
+
+/// 返回可用的编解码器：`symphonia` 内置的编解码器，启用 `opus` 特性时还包括 Opus。
+fn codecs() -> &'static CodecRegistry {
+    static CODECS: LazyLock<CodecRegistry> = LazyLock::new(|| {
+        let mut registry = CodecRegistry::new();
+        symphonia::default::register_enabled_codecs(&mut registry);
+        #[cfg(feature = "opus")]
+        registry.register_all::<crate::opus::OpusDecoder>();
+        registry
+    });
+    &CODECS
+}
+
+/// 本地音频文件解码器。
+pub struct Decoder {
+    /// 容器格式读取器
+    format: Box<dyn FormatReader>,
+    /// 编码解码器
+    decoder: Box<dyn CodecDecoder>,
+    /// 正在解码的音轨 ID
+    track_id: u32,
+    /// 音轨时间基，用于将时间戳换算为时间
+    time_base: Option<TimeBase>,
+    /// 输出格式
+    spec: AudioSpec,
+    /// 音轨总时长（如果容器提供）
+    duration: Option<Duration>,
+    /// 复用的采样缓冲区
+    buffer: Option<SampleBuffer<f32>>,
+    /// 已输出的帧数，用于计算播放位置
+    frames: u64,
+    /// 跳转后需要丢弃的起始时间戳（精确跳转）
+    skip_until: Option<u64>,
+}
+
+impl Decoder {
+    /// 打开并探测一个音频文件。
+    ///
+    /// 根据文件扩展名提示格式探测，选择第一条可解码的音轨。
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PlayerError> {
+        let path = path.as_ref();
+        let file = File::open(path)?;
+        let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+        let mut hint = Hint::new();
+        if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+            hint.with_extension(ext);
+        }
+
+        let format_opts = FormatOptions {
+            enable_gapless: true,
+            ..Default::default()
+        };
+        let probed = symphonia::default::get_probe().format(
+            &hint,
+            mss,
+            &format_opts,
+            &MetadataOptions::default(),
+        )?;
+        let format = probed.format;
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or_else(|| PlayerError::Unsupported("no decodable audio track".to_string()))?;
+
+        let params = &track.codec_params;
+        let decoder = codecs().make(params, &DecoderOptions::default())?;
+
+        let sample_rate = params
+            .sample_rate
+            .ok_or_else(|| PlayerError::Unsupported("unknown sample rate".to_string()))?;
+        let channels = params.channels.map(|c| c.count() as u16).unwrap_or(2);
+        let spec = AudioSpec {
+            sample_rate,
+            channels,
+        };
+
+        // 优先使用时间基计算总时长，否则退回到采样率
+        let duration = params.n_frames.map(|n| match params.time_base {
+            Some(tb) => tb.calc_time(n).into(),
+            None => spec.frames_to_duration(n),
+        });
+
+        Ok(Self {
+            track_id: track.id,
+            time_base: params.time_base,
+            format,
+            decoder,
+            spec,
+            duration,
+            buffer: None,
+            frames: 0,
+            skip_until: None,
+        })
+    }
+
+    /// 返回输出格式。
+    pub fn spec(&self) -> AudioSpec {
+        self.spec
+    }
+
+    /// 返回音轨总时长（未知时为 `None`）。
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
+    /// 返回当前播放位置。
+    pub fn position(&self) -> Duration {
+        self.spec.frames_to_duration(self.frames)
+    }
+
+    /// 解码下一块音频数据。
+    ///
+    /// 返回交错排列的 `f32` 采样；到达文件末尾时返回 `Ok(None)`。
+    /// 单个损坏的数据包会被跳过，而不会中断播放。
+    pub fn next_chunk(&mut self) -> Result<Option<&[f32]>, PlayerError> {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(SymphoniaError::IoError(e))
+                    if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    return Ok(None);
+                }
+                Err(SymphoniaError::ResetRequired) => return Ok(None),
+                Err(e) => return Err(e.into()),
+            };
+
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            // 精确跳转：丢弃目标位置之前的整包数据
+            let mut skip_frames = 0;
+            if let Some(target) = self.skip_until {
+                if packet.ts() + packet.dur() <= target {
+                    continue;
+                }
+                skip_frames = self.ts_to_frames(target.saturating_sub(packet.ts()));
+                self.skip_until = None;
+            }
+
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // 可恢复的解码错误，跳过该数据包
+                Err(SymphoniaError::DecodeError(_)) => continue,
+                Err(e) => return Err(e.into()),
+            };
+
+            let signal_spec = *decoded.spec();
+            self.spec = AudioSpec {
+                sample_rate: signal_spec.rate,
+                channels: signal_spec.channels.count() as u16,
+            };
+
+            // 缓冲区容量以采样数计，需要容纳 帧数 × 声道数
+            let required = decoded.capacity() * signal_spec.channels.count();
+            let needs_new_buffer = self.buffer.as_ref().is_none_or(|b| b.capacity() < required);
+            if needs_new_buffer {
+                self.buffer = Some(SampleBuffer::new(decoded.capacity() as u64, signal_spec));
+            }
+
+            let buffer = self.buffer.as_mut().expect("缓冲区已初始化");
+            buffer.copy_interleaved_ref(decoded);
+
+            let channels = self.spec.channels as usize;
+            let total_frames = buffer.len() / channels;
+            let skip_frames = (skip_frames as usize).min(total_frames);
+            self.frames += (total_frames - skip_frames) as u64;
+
+            return Ok(Some(&buffer.samples()[skip_frames * channels..]));
+        }
+    }
+
+    /// 跳转到指定位置，返回实际的播放位置。
+    pub fn seek(&mut self, position: Duration) -> Result<Duration, PlayerError> {
+        let position = match self.duration {
+            Some(duration) => position.min(duration),
+            None => position,
+        };
+        let seeked = self.format.seek(
+            SeekMode::Accurate,
+            SeekTo::Time {
+                time: Time::from(position),
+                track_id: Some(self.track_id),
+            },
+        )?;
+        self.decoder.reset();
+        self.skip_until = Some(seeked.required_ts);
+        self.frames = self.ts_to_frames(seeked.required_ts);
+        Ok(self.position())
+    }
+
+    /// 将音轨时间戳换算为帧数。
+    fn ts_to_frames(&self, ts: u64) -> u64 {
+        match self.time_base {
+            Some(tb) => self.spec.duration_to_frames(tb.calc_time(ts).into()),
+            None => ts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::write_sine_wav;
+
+    #[test]
+    fn test_decoder_open_wav() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sine.wav");
+        write_sine_wav(&path, 44_100, 2, 44_100);
+
+        let decoder = Decoder::open(&path).unwrap();
+        assert_eq!(
+            decoder.spec(),
+            AudioSpec {
+                sample_rate: 44_100,
+                channels: 2
+            }
+        );
+        assert_eq!(decoder.duration(), Some(Duration::from_secs(1)));
+        assert_eq!(decoder.position(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_decoder_decodes_all_frames() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sine.wav");
+        let frames = write_sine_wav(&path, 8_000, 1, 12_345);
+
+        let mut decoder = Decoder::open(&path).unwrap();
+        let mut decoded = 0;
+        while let Some(chunk) = decoder.next_chunk().unwrap() {
+            decoded += chunk.len();
+        }
+        assert_eq!(decoded, frames as usize);
+        assert_eq!(
+            decoder.position(),
+            decoder.spec().frames_to_duration(frames as u64)
+        );
+    }
+
+    #[test]
+    fn test_decoder_seek() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sine.wav");
+        write_sine_wav(&path, 8_000, 1, 16_000);
+
+        let mut decoder = Decoder::open(&path).unwrap();
+        let position = decoder.seek(Duration::from_millis(500)).unwrap();
+        assert_eq!(position, Duration::from_millis(500));
+
+        let mut decoded = 0;
+        while let Some(chunk) = decoder.next_chunk().unwrap() {
+            decoded += chunk.len();
+        }
+        assert_eq!(decoded, 12_000, "跳转后应只解码剩余的帧");
+
+        // 超出总时长的跳转会被截断到末尾
+        let position = decoder.seek(Duration::from_secs(10)).unwrap();
+        assert_eq!(position, Duration::from_secs(2));
+    }
+
+    #[cfg(feature = "opus")]
+    #[test]
+    fn test_decoder_decodes_opus() {
+        use crate::test_utils::write_sine_opus;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sine.opus");
+        let frames = write_sine_opus(&path, 2, 12_345);
+
+        let mut decoder = Decoder::open(&path).unwrap();
+        let mut samples = Vec::new();
+        while let Some(chunk) = decoder.next_chunk().unwrap() {
+            samples.extend_from_slice(chunk);
+        }
+        assert_eq!(
+            decoder.spec(),
+            AudioSpec {
+                sample_rate: 48_000,
+                channels: 2
+            }
+        );
+        // 编码器延迟和末尾的填充被去掉，解码出的帧数与编码前相同
+        assert_eq!(samples.len(), frames as usize * 2);
+        let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+        assert!(rms > 0.2, "应解码出正弦波而不是静音，rms = {rms}");
+    }
+
+    #[test]
+    fn test_decoder_supports_mp4_codecs() {
+        use symphonia::core::codecs::{CODEC_TYPE_AAC, CODEC_TYPE_ALAC};
+
+        // 媒体库会把 M4A/MP4 中的 AAC 和 ALAC 音轨当作可播放的曲目
+        assert!(codecs().get_codec(CODEC_TYPE_AAC).is_some());
+        assert!(codecs().get_codec(CODEC_TYPE_ALAC).is_some());
+    }
+
+    #[test]
+    fn test_decoder_missing_file() {
+        assert!(matches!(
+            Decoder::open("/nonexistent/file.flac"),
+            Err(PlayerError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_decoder_unsupported_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("not_audio.opus");
+        std::fs::write(&path, b"definitely not audio data").unwrap();
+        assert!(Decoder::open(&path).is_err());
+    }
+}
@@ -0,0 +1,707 @@
+//! 播放引擎核心模块。
+//!
+//! `Engine` 是同步的状态机：`handle` 处理控制命令，`step` 解码并输出一块音频。
+//! 状态变化以 `PlayerEvent` 的形式通过 `Publisher` 发布给应用程序。
+//!
+//! 应用程序通过 `PlayerCommand::Preload` 告诉引擎下一首曲目，引擎提前打开并解码它的第一块音频。
+//! 当前曲目播放完毕时，下一首的采样紧接着写入输出端，中间不会插入静音；
+//! 格式与输出端不同时重采样到输出端的格式。
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use lazy_core::{publisher::Publisher, types::PlaybackState};
+use log::warn;
+
+use crate::{
+    decoder::{AudioSpec, Decoder},
+    error::PlayerError,
+    resample::Resampler,
+    sink::AudioSink,
+};
+
+/// 发送给播放引擎的控制命令。
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlayerCommand {
+    /// 从头播放指定文件
+    Play(PathBuf),
+    /// 暂停
+    Pause,
+    /// 恢复播放
+    Resume,
+    /// 播放/暂停切换；停止状态下重新播放当前曲目
+    TogglePause,
+    /// 停止播放
+    Stop,
+    /// 跳转到指定位置
+    Seek(Duration),
+    /// 设置音量，范围 0..=100
+    SetVolume(u8),
+    /// 设置当前曲目播放完毕后无缝播放的曲目，`None` 表示播放完毕后停止
+    Preload(Option<PathBuf>),
+}
+
+/// 播放引擎发出的事件。
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlayerEvent {
+    /// 播放状态改变
+    State(PlaybackState),
+    /// 播放进度更新
+    ///
+    /// 第一个 `Duration` 是当前播放时间，第二个是总时长。
+    Progress(Duration, Duration),
+    /// 开始播放新的曲目
+    TrackStarted(PathBuf),
+    /// 曲目播放完毕
+    TrackFinished(PathBuf),
+    /// 当前曲目播放完毕，已无缝切换到预加载的曲目
+    ///
+    /// 随后会发送这首曲目的 `TrackStarted`，不会发送 `TrackFinished`。
+    TrackAdvanced(PathBuf),
+    /// 发生错误
+    Error(String),
+}
+
+/// 已经打开并解码了第一块音频的曲目。
+struct Preloaded {
+    /// 曲目路径
+    path: PathBuf,
+    /// 曲目的解码器
+    decoder: Decoder,
+    /// 预先解码的第一块音频
+    pending: Vec<f32>,
+}
+
+impl Preloaded {
+    /// 打开曲目并解码第一块音频，解码器的格式随之确定。
+    fn open(path: PathBuf) -> Result<Self, PlayerError> {
+        let mut decoder = Decoder::open(&path)?;
+        let pending = decoder.next_chunk()?.map(<[f32]>::to_vec);
+        Ok(Self {
+            path,
+            decoder,
+            pending: pending.unwrap_or_default(),
+        })
+    }
+}
+
+/// 同步播放引擎。
+pub struct Engine {
+    /// 音频输出端
+    sink: Box<dyn AudioSink>,
+    /// 输出端当前的格式
+    output: Option<AudioSpec>,
+    /// 当前曲目的格式与输出端不同时使用的重采样器
+    resampler: Option<Resampler>,
+    /// 当前曲目的解码器
+    decoder: Option<Decoder>,
+    /// 已解码但尚未输出的采样
+    pending: Vec<f32>,
+    /// 当前曲目播放完毕后无缝播放的曲目
+    next: Option<Preloaded>,
+    /// 当前曲目路径
+    current: Option<PathBuf>,
+    /// 当前播放状态
+    state: PlaybackState,
+    /// 音量增益，范围 0.0..=1.0
+    volume: f32,
+    /// 应用音量后的采样缓冲区
+    scratch: Vec<f32>,
+    /// 上一次发送进度事件时的位置
+    last_progress: Duration,
+    /// 事件发送端
+    events: Publisher<PlayerEvent>,
+}
+
+impl Engine {
+    /// 两次进度事件之间的最小播放时长
+    const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+    /// 创建一个新的播放引擎。
+    pub fn new(sink: Box<dyn AudioSink>, events: impl Into<Publisher<PlayerEvent>>) -> Self {
+        Self {
+            sink,
+            output: None,
+            resampler: None,
+            decoder: None,
+            pending: Vec::new(),
+            next: None,
+            current: None,
+            state: PlaybackState::Stopped,
+            volume: 0.5,
+            scratch: Vec::new(),
+            last_progress: Duration::ZERO,
+            events: events.into(),
+        }
+    }
+
+    /// 返回当前播放状态。
+    pub fn state(&self) -> PlaybackState {
+        self.state
+    }
+
+    /// 返回当前曲目路径。
+    pub fn current(&self) -> Option<&Path> {
+        self.current.as_deref()
+    }
+
+    /// 是否正在播放（需要继续调用 `step`）。
+    pub fn is_playing(&self) -> bool {
+        self.state == PlaybackState::Playing
+    }
+
+    /// 处理一条控制命令。
+    pub fn handle(&mut self, command: PlayerCommand) {
+        match command {
+            PlayerCommand::Play(path) => self.play(path),
+            PlayerCommand::Pause => self.pause(),
+            PlayerCommand::Resume => self.resume(),
+            PlayerCommand::TogglePause => match self.state {
+                PlaybackState::Playing => self.pause(),
+                PlaybackState::Paused => self.resume(),
+                PlaybackState::Stopped => {
+                    if let Some(path) = self.current.clone() {
+                        self.play(path);
+                    }
+                }
+            },
+            PlayerCommand::Stop => self.stop(),
+            PlayerCommand::Seek(position) => self.seek(position),
+            PlayerCommand::SetVolume(volume) => self.volume = volume.min(100) as f32 / 100.0,
+            PlayerCommand::Preload(path) => self.preload(path),
+        }
+    }
+
+    /// 解码并输出一块音频。
+    ///
+    /// 返回 `true` 表示仍在播放，需要继续调用。
+    pub fn step(&mut self) -> bool {
+        if !self.is_playing() {
+            return false;
+        }
+        let Some(decoder) = self.decoder.as_mut() else {
+            return false;
+        };
+
+        // 先输出打开曲目时预先解码的音频
+        let chunk = if self.pending.is_empty() {
+            decoder.next_chunk()
+        } else {
+            Ok(Some(self.pending.as_slice()))
+        };
+        match chunk {
+            Ok(Some(samples)) => {
+                self.scratch.clear();
+                match self.resampler.as_mut() {
+                    Some(resampler) => resampler.process(samples, &mut self.scratch),
+                    None => self.scratch.extend_from_slice(samples),
+                }
+                self.pending.clear();
+                self.write_scratch()
+            }
+            Ok(None) if self.resampler.is_some() => {
+                // 先输出重采样器保留的尾部
+                self.scratch.clear();
+                if let Some(resampler) = self.resampler.take() {
+                    resampler.finish(&mut self.scratch);
+                }
+                self.write_scratch()
+            }
+            Ok(None) => match self.next.take() {
+                Some(next) => {
+                    self.advance(next);
+                    true
+                }
+                None => {
+                    self.finish();
+                    false
+                }
+            },
+            Err(e) => {
+                self.fail(e.to_string());
+                false
+            }
+        }
+    }
+
+    /// 把 `scratch` 中的采样应用音量后写入输出端。
+    fn write_scratch(&mut self) -> bool {
+        self.scratch.iter_mut().for_each(|s| *s *= self.volume);
+        if let Err(e) = self.sink.write(&self.scratch) {
+            self.fail(e.to_string());
+            return false;
+        }
+        self.report_progress(false);
+        true
+    }
+
+    /// 打开并开始播放一个文件。
+    ///
+    /// 要播放的正是预加载的曲目时直接使用已经打开的解码器。
+    fn play(&mut self, path: PathBuf) {
+        self.sink.clear();
+        self.decoder = None;
+        self.pending.clear();
+        self.current = Some(path.clone());
+
+        let track = match self.next.take() {
+            Some(next) if next.path == path => Ok(next),
+            next => {
+                self.next = next;
+                Preloaded::open(path.clone())
+            }
+        };
+        let track = match track {
+            Ok(track) => track,
+            Err(e) => return self.fail(format!("{}: {e}", path.display())),
+        };
+        let spec = track.decoder.spec();
+        if let Err(e) = self.sink.open(spec) {
+            return self.fail(e.to_string());
+        }
+        self.output = Some(spec);
+        self.resampler = None;
+        self.sink.resume();
+
+        self.start(track);
+        self.set_state(PlaybackState::Playing);
+        self.report_progress(true);
+    }
+
+    /// 当前曲目播放完毕，无缝切换到预加载的曲目。
+    ///
+    /// 输出端不会被清空或重新打开，下一首的采样紧接着上一首写入；
+    /// 格式与输出端不同时重采样到输出端的格式。
+    fn advance(&mut self, next: Preloaded) {
+        self.reset_resampler(next.decoder.spec());
+        self.emit(PlayerEvent::TrackAdvanced(next.path.clone()));
+        self.start(next);
+        self.report_progress(true);
+    }
+
+    /// 按曲目的格式重新创建重采样器，丢弃上一段音频缓存的输入和滤波器状态；格式与输出端相同时不需要重采样。
+    fn reset_resampler(&mut self, spec: AudioSpec) {
+        self.resampler = self
+            .output
+            .filter(|&output| output != spec)
+            .map(|output| Resampler::new(spec, output));
+    }
+
+    /// 把已打开的曲目设为当前曲目。
+    fn start(&mut self, track: Preloaded) {
+        self.current = Some(track.path.clone());
+        self.decoder = Some(track.decoder);
+        self.pending = track.pending;
+        self.emit(PlayerEvent::TrackStarted(track.path));
+    }
+
+    /// 预加载当前曲目播放完毕后无缝播放的曲目，与已预加载的曲目相同时不重复打开。
+    ///
+    /// 打开失败时不预加载，播放到这首曲目时再报告错误。
+    fn preload(&mut self, path: Option<PathBuf>) {
+        if self.next.as_ref().map(|next| &next.path) == path.as_ref() {
+            return;
+        }
+        self.next = path.and_then(|path| match Preloaded::open(path.clone()) {
+            Ok(next) => Some(next),
+            Err(e) => {
+                warn!("Failed to preload {}: {e}", path.display());
+                None
+            }
+        });
+    }
+
+    /// 暂停播放。
+    fn pause(&mut self) {
+        if self.state == PlaybackState::Playing {
+            self.sink.pause();
+            self.set_state(PlaybackState::Paused);
+        }
+    }
+
+    /// 恢复播放。
+    fn resume(&mut self) {
+        if self.state == PlaybackState::Paused {
+            self.sink.resume();
+            self.set_state(PlaybackState::Playing);
+        }
+    }
+
+    /// 停止播放，保留当前曲目以便重新播放。
+    fn stop(&mut self) {
+        self.sink.clear();
+        self.decoder = None;
+        self.pending.clear();
+        self.set_state(PlaybackState::Stopped);
+        self.emit(PlayerEvent::Progress(Duration::ZERO, Duration::ZERO));
+    }
+
+    /// 跳转到指定位置。
+    fn seek(&mut self, position: Duration) {
+        let Some(decoder) = self.decoder.as_mut() else {
+            return;
+        };
+        match decoder.seek(position) {
+            Ok(_) => {
+                // 跳转前缓存的音频不能与跳转后的音频一起重采样
+                let spec = decoder.spec();
+                self.reset_resampler(spec);
+                self.pending.clear();
+                self.sink.clear();
+                self.report_progress(true);
+            }
+            Err(e) => self.emit(PlayerEvent::Error(e.to_string())),
+        }
+    }
+
+    /// 当前曲目正常播放完毕。
+    fn finish(&mut self) {
+        if let Err(e) = self.sink.flush() {
+            self.emit(PlayerEvent::Error(e.to_string()));
+        }
+        self.report_progress(true);
+        self.decoder = None;
+        if let Some(path) = self.current.clone() {
+            self.emit(PlayerEvent::TrackFinished(path));
+        }
+        self.set_state(PlaybackState::Stopped);
+    }
+
+    /// 发生不可恢复的错误，停止播放。
+    fn fail(&mut self, message: String) {
+        self.decoder = None;
+        self.pending.clear();
+        self.emit(PlayerEvent::Error(message));
+        self.set_state(PlaybackState::Stopped);
+    }
+
+    /// 更新播放状态，仅在状态改变时发送事件。
+    fn set_state(&mut self, state: PlaybackState) {
+        if self.state != state {
+            self.state = state;
+            self.emit(PlayerEvent::State(state));
+        }
+    }
+
+    /// 发送进度事件；非强制时按 `PROGRESS_INTERVAL` 节流。
+    fn report_progress(&mut self, force: bool) {
+        let Some(decoder) = self.decoder.as_ref() else {
+            return;
+        };
+        let position = decoder.position();
+        if force
+            || position < self.last_progress
+            || position - self.last_progress >= Self::PROGRESS_INTERVAL
+        {
+            self.last_progress = position;
+            let duration = decoder.duration().unwrap_or_default();
+            self.emit(PlayerEvent::Progress(position, duration));
+        }
+    }
+
+    /// 发送事件；接收端已关闭时忽略。
+    fn emit(&self, event: PlayerEvent) {
+        self.events.publish(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sink::NullSink, sink::WavSink, test_utils::write_sine_wav};
+    use std::sync::atomic::Ordering;
+    use tokio::sync::mpsc::{UnboundedReceiver, unbounded_channel};
+
+    fn drain(rx: &mut UnboundedReceiver<PlayerEvent>) -> Vec<PlayerEvent> {
+        std::iter::from_fn(|| rx.try_recv().ok()).collect()
+    }
+
+    fn run_to_end(engine: &mut Engine) {
+        while engine.step() {}
+    }
+
+    #[test]
+    fn test_engine_plays_to_end() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.wav");
+        let frames = write_sine_wav(&path, 8_000, 2, 8_000);
+
+        let sink = NullSink::new();
+        let counter = sink.frame_counter();
+        let (tx, mut rx) = unbounded_channel();
+        let mut engine = Engine::new(Box::new(sink), tx);
+
+        engine.handle(PlayerCommand::Play(path.clone()));
+        assert_eq!(engine.state(), PlaybackState::Playing);
+        run_to_end(&mut engine);
+
+        assert_eq!(engine.state(), PlaybackState::Stopped);
+        assert_eq!(counter.load(Ordering::Relaxed), frames as u64);
+
+        let events = drain(&mut rx);
+        assert_eq!(events[0], PlayerEvent::TrackStarted(path.clone()));
+        assert_eq!(events[1], PlayerEvent::State(PlaybackState::Playing));
+        assert!(events.contains(&PlayerEvent::Progress(
+            Duration::from_secs(1),
+            Duration::from_secs(1)
+        )));
+        assert!(events.contains(&PlayerEvent::TrackFinished(path)));
+        assert_eq!(
+            events.last(),
+            Some(&PlayerEvent::State(PlaybackState::Stopped))
+        );
+    }
+
+    #[test]
+    fn test_engine_pause_resume_stop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.wav");
+        write_sine_wav(&path, 8_000, 1, 8_000);
+
+        let (tx, _rx) = unbounded_channel();
+        let mut engine = Engine::new(Box::new(NullSink::new()), tx);
+
+        // 没有曲目时切换不做任何事
+        engine.handle(PlayerCommand::TogglePause);
+        assert_eq!(engine.state(), PlaybackState::Stopped);
+
+        engine.handle(PlayerCommand::Play(path.clone()));
+        engine.handle(PlayerCommand::TogglePause);
+        assert_eq!(engine.state(), PlaybackState::Paused);
+        assert!(!engine.step(), "暂停时不应输出数据");
+
+        engine.handle(PlayerCommand::TogglePause);
+        assert_eq!(engine.state(), PlaybackState::Playing);
+
+        engine.handle(PlayerCommand::Stop);
+        assert_eq!(engine.state(), PlaybackState::Stopped);
+        assert_eq!(engine.current(), Some(path.as_path()));
+
+        // 停止后切换会重新播放当前曲目
+        engine.handle(PlayerCommand::TogglePause);
+        assert_eq!(engine.state(), PlaybackState::Playing);
+    }
+
+    #[test]
+    fn test_engine_seek_reports_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.wav");
+        write_sine_wav(&path, 8_000, 1, 16_000);
+
+        let sink = NullSink::new();
+        let counter = sink.frame_counter();
+        let (tx, mut rx) = unbounded_channel();
+        let mut engine = Engine::new(Box::new(sink), tx);
+
+        engine.handle(PlayerCommand::Play(path));
+        drain(&mut rx);
+        engine.handle(PlayerCommand::Seek(Duration::from_secs(1)));
+        assert_eq!(
+            drain(&mut rx),
+            vec![PlayerEvent::Progress(
+                Duration::from_secs(1),
+                Duration::from_secs(2)
+            )]
+        );
+
+        run_to_end(&mut engine);
+        assert_eq!(counter.load(Ordering::Relaxed), 8_000);
+    }
+
+    #[test]
+    fn test_engine_reports_open_errors() {
+        let (tx, mut rx) = unbounded_channel();
+        let mut engine = Engine::new(Box::new(NullSink::new()), tx);
+
+        engine.handle(PlayerCommand::Play(PathBuf::from("/nonexistent.flac")));
+        assert_eq!(engine.state(), PlaybackState::Stopped);
+        assert!(matches!(drain(&mut rx).as_slice(), [PlayerEvent::Error(_)]));
+    }
+
+    #[test]
+    fn test_engine_applies_volume() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("in.wav");
+        let output = dir.path().join("out.wav");
+        write_sine_wav(&input, 8_000, 1, 800);
+
+        let (tx, _rx) = unbounded_channel();
+        let mut engine = Engine::new(Box::new(WavSink::new(&output)), tx);
+        engine.handle(PlayerCommand::SetVolume(0));
+        engine.handle(PlayerCommand::Play(input));
+        run_to_end(&mut engine);
+        drop(engine);
+
+        let mut reader = hound::WavReader::open(&output).unwrap();
+        assert!(reader.samples::<f32>().all(|s| s.unwrap() == 0.0));
+    }
+
+    /// 依次解码文件中的所有采样。
+    fn decode_all(paths: &[&Path]) -> Vec<f32> {
+        let mut samples = Vec::new();
+        for path in paths {
+            let mut decoder = Decoder::open(path).unwrap();
+            while let Some(chunk) = decoder.next_chunk().unwrap() {
+                samples.extend_from_slice(chunk);
+            }
+        }
+        samples
+    }
+
+    #[test]
+    fn test_engine_gapless_same_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("a.wav");
+        let second = dir.path().join("b.wav");
+        let output = dir.path().join("out.wav");
+        write_sine_wav(&first, 8_000, 2, 3_001);
+        write_sine_wav(&second, 8_000, 2, 5_000);
+
+        let (tx, mut rx) = unbounded_channel();
+        let mut engine = Engine::new(Box::new(WavSink::new(&output)), tx);
+        engine.handle(PlayerCommand::SetVolume(100));
+        engine.handle(PlayerCommand::Play(first.clone()));
+        engine.handle(PlayerCommand::Preload(Some(second.clone())));
+        run_to_end(&mut engine);
+        drop(engine);
+
+        // 输出正好是两首曲目的采样首尾相接，中间没有插入任何采样
+        let mut reader = hound::WavReader::open(&output).unwrap();
+        let samples: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+        assert_eq!(samples, decode_all(&[&first, &second]));
+
+        // 切换时不停止播放，只有第二首发送播放完毕的事件
+        let events = drain(&mut rx);
+        let position = |event: &PlayerEvent| events.iter().position(|e| e == event).unwrap();
+        assert!(
+            position(&PlayerEvent::TrackAdvanced(second.clone()))
+                < position(&PlayerEvent::TrackStarted(second.clone()))
+        );
+        assert!(!events.contains(&PlayerEvent::TrackFinished(first)));
+        assert!(events.contains(&PlayerEvent::TrackFinished(second)));
+        let states = events
+            .iter()
+            .filter(|e| matches!(e, PlayerEvent::State(_)))
+            .count();
+        assert_eq!(states, 2);
+    }
+
+    #[test]
+    fn test_engine_gapless_resamples_other_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("a.wav");
+        let second = dir.path().join("b.wav");
+        let output = dir.path().join("out.wav");
+        write_sine_wav(&first, 8_000, 1, 4_000);
+        write_sine_wav(&second, 16_000, 2, 8_000);
+
+        let (tx, _rx) = unbounded_channel();
+        let mut engine = Engine::new(Box::new(WavSink::new(&output)), tx);
+        engine.handle(PlayerCommand::SetVolume(100));
+        engine.handle(PlayerCommand::Preload(Some(second.clone())));
+        engine.handle(PlayerCommand::Play(first.clone()));
+        run_to_end(&mut engine);
+        assert_eq!(engine.current(), Some(second.as_path()));
+        drop(engine);
+
+        // 第二首被转换为输出端的格式，时长不变，切换处没有静音
+        let mut reader = hound::WavReader::open(&output).unwrap();
+        assert_eq!(reader.spec().sample_rate, 8_000);
+        assert_eq!(reader.spec().channels, 1);
+        let samples: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+        assert_eq!(samples.len(), 8_000);
+        assert_eq!(samples[..4_000], decode_all(&[&first]));
+        assert!(!samples.windows(2).any(|w| w == [0.0, 0.0]));
+    }
+
+    #[test]
+    fn test_engine_seek_resets_resampler() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("a.wav");
+        let second = dir.path().join("b.wav");
+        let output = dir.path().join("out.wav");
+        write_sine_wav(&first, 12_000, 1, 1_200);
+        write_sine_wav(&second, 8_000, 1, 8_000);
+
+        let (tx, _rx) = unbounded_channel();
+        let mut engine = Engine::new(Box::new(WavSink::new(&output)), tx);
+        engine.handle(PlayerCommand::SetVolume(100));
+        engine.handle(PlayerCommand::Play(first.clone()));
+        engine.handle(PlayerCommand::Preload(Some(second.clone())));
+        while engine.current() != Some(second.as_path()) {
+            assert!(engine.step());
+        }
+        // 输出第二首的第一块后跳回开头
+        assert!(engine.step());
+        engine.handle(PlayerCommand::Seek(Duration::ZERO));
+        run_to_end(&mut engine);
+        drop(engine);
+
+        // 跳转后的输出与从头重采样整首曲目相同，不包含跳转前的采样
+        let resample = |samples: &[f32], finish: bool| {
+            let mut output = Vec::new();
+            let from = AudioSpec {
+                sample_rate: 8_000,
+                channels: 1,
+            };
+            let to = AudioSpec {
+                sample_rate: 12_000,
+                channels: 1,
+            };
+            let mut resampler = Resampler::new(from, to);
+            resampler.process(samples, &mut output);
+            if finish {
+                resampler.finish(&mut output);
+            }
+            output
+        };
+        let mut decoder = Decoder::open(&second).unwrap();
+        let mut expected = decode_all(&[&first]);
+        expected.extend(resample(decoder.next_chunk().unwrap().unwrap(), false));
+        expected.extend(resample(&decode_all(&[&second]), true));
+
+        let mut reader = hound::WavReader::open(&output).unwrap();
+        let samples: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+        assert_eq!(samples.len(), expected.len());
+        // 分块重采样与一次重采样的结果可能有舍入误差
+        assert!(
+            samples
+                .iter()
+                .zip(&expected)
+                .all(|(a, b)| (a - b).abs() < 1e-5)
+        );
+    }
+
+    #[test]
+    fn test_engine_preload_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("a.wav");
+        let second = dir.path().join("b.wav");
+        write_sine_wav(&first, 8_000, 1, 1_000);
+        write_sine_wav(&second, 8_000, 1, 2_000);
+
+        let sink = NullSink::new();
+        let counter = sink.frame_counter();
+        let (tx, mut rx) = unbounded_channel();
+        let mut engine = Engine::new(Box::new(sink), tx);
+
+        // 无法打开的曲目不预加载，取消预加载后播放完当前曲目就停止
+        engine.handle(PlayerCommand::Preload(Some(PathBuf::from(
+            "/nonexistent.flac",
+        ))));
+        engine.handle(PlayerCommand::Play(first.clone()));
+        engine.handle(PlayerCommand::Preload(Some(second.clone())));
+        engine.handle(PlayerCommand::Preload(None));
+        run_to_end(&mut engine);
+        assert_eq!(counter.load(Ordering::Relaxed), 1_000);
+        assert!(drain(&mut rx).contains(&PlayerEvent::TrackFinished(first)));
+
+        // 直接播放预加载的曲目时使用已经解码的音频
+        engine.handle(PlayerCommand::Preload(Some(second.clone())));
+        engine.handle(PlayerCommand::Play(second));
+        run_to_end(&mut engine);
+        assert_eq!(counter.load(Ordering::Relaxed), 3_000);
+    }
+}
@@ -0,0 +1,54 @@
+//! 播放引擎的错误类型。
+
+use std::{error::Error, fmt, io};
+
+use symphonia::core::errors::Error as SymphoniaError;
+
+/// 播放引擎在打开、解码或输出音频时可能产生的错误。
+#[derive(Debug)]
+pub enum PlayerError {
+    /// 读取文件时发生的 I/O 错误
+    Io(io::Error),
+    /// 文件格式或编码不受支持（例如 Opus）
+    Unsupported(String),
+    /// 解码过程中发生的错误
+    Decode(String),
+    /// 音频输出端发生的错误
+    Sink(String),
+}
+
+impl fmt::Display for PlayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayerError::Io(e) => write!(f, "I/O error: {e}"),
+            PlayerError::Unsupported(msg) => write!(f, "unsupported format: {msg}"),
+            PlayerError::Decode(msg) => write!(f, "decode error: {msg}"),
+            PlayerError::Sink(msg) => write!(f, "audio sink error: {msg}"),
+        }
+    }
+}
+
+impl Error for PlayerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PlayerError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PlayerError {
+    fn from(e: io::Error) -> Self {
+        PlayerError::Io(e)
+    }
+}
+
+impl From<SymphoniaError> for PlayerError {
+    fn from(e: SymphoniaError) -> Self {
+        match e {
+            SymphoniaError::IoError(e) => PlayerError::Io(e),
+            SymphoniaError::Unsupported(msg) => PlayerError::Unsupported(msg.to_string()),
+            e => PlayerError::Decode(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! `lazy-player` crate 提供了 `lazymusic` 的音频播放引擎。
+//!
+//! 主要包含以下模块：
+//! - `decoder`: 基于 `symphonia` 的本地音频文件解码器（FLAC、MP3、Ogg Vorbis、Opus、WAV，以及 M4A/MP4 中的 AAC 和 ALAC）。
+//! - `opus`: 基于 libopus 的 Opus 解码器，需要启用默认开启的 `opus` 特性。
+//! - `sink`: 可插拔的音频输出端，包括空输出、WAV 文件输出以及可选的 `cpal` 声卡输出。
+//! - `resample`: 无缝播放时把下一首曲目转换为输出端格式的带限重采样器。
+//! - `engine`: 同步的播放引擎核心，负责解码、音量、无缝播放和播放状态管理。
+//! - `player`: 在后台线程中运行引擎，并通过通道与应用程序交互。
+
+pub mod decoder;
+pub mod engine;
+pub mod error;
+#[cfg(feature = "opus")]
+pub mod opus;
+pub mod player;
+pub mod resample;
+pub mod sink;
+
+#[cfg(test)]
+mod test_utils;
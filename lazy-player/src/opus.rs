@@ -0,0 +1,135 @@
+//! 基于 libopus 的 Opus 解码器模块。
+//!
+//! `symphonia` 0.5 能读取 Ogg 容器中的 Opus 流，但没有 Opus 解码器。
+//! `OpusDecoder` 通过 `audiopus` 调用 libopus，实现 `symphonia` 的解码器 trait，
+//! 注册到 `Decoder` 使用的编解码器注册表中。
+
+use std::sync::Mutex;
+
+use audiopus::{Channels as OpusChannels, SampleRate, coder::Decoder as LibOpusDecoder};
+use symphonia::core::{
+    audio::{AsAudioBufferRef, AudioBuffer, AudioBufferRef, Channels, Signal, SignalSpec},
+    codecs::{
+        CODEC_TYPE_OPUS, CodecDescriptor, CodecParameters, Decoder, DecoderOptions, FinalizeResult,
+    },
+    errors::{Result, decode_error, unsupported_error},
+    formats::Packet,
+    support_codec,
+};
+
+/// Opus 总是以 48 kHz 解码
+const SAMPLE_RATE: u32 = 48_000;
+
+/// 单个数据包最多包含 120 毫秒的音频
+const MAX_FRAMES: usize = 5_760;
+
+/// 单声道或立体声的 Opus 解码器。
+pub struct OpusDecoder {
+    /// 编解码参数
+    params: CodecParameters,
+    /// libopus 解码器，`symphonia` 要求解码器实现 `Sync`
+    decoder: Mutex<LibOpusDecoder>,
+    /// 声道数
+    channels: usize,
+    /// 流开头还需要丢弃的帧数（标识头中的预跳过）
+    skip: usize,
+    /// libopus 输出的交错采样
+    pcm: Vec<f32>,
+    /// 解码结果
+    buf: AudioBuffer<f32>,
+}
+
+impl OpusDecoder {
+    /// 创建一个 libopus 解码器。
+    fn open(channels: usize) -> Result<LibOpusDecoder> {
+        let channels = match channels {
+            1 => OpusChannels::Mono,
+            2 => OpusChannels::Stereo,
+            _ => return unsupported_error("opus: multichannel streams are not supported"),
+        };
+        LibOpusDecoder::new(SampleRate::Hz48000, channels)
+            .or_else(|_| decode_error("opus: failed to create decoder"))
+    }
+}
+
+impl Decoder for OpusDecoder {
+    fn try_new(params: &CodecParameters, _options: &DecoderOptions) -> Result<Self> {
+        let layout = params.channels.unwrap_or(Channels::FRONT_LEFT);
+        let channels = layout.count();
+        Ok(Self {
+            params: params.clone(),
+            decoder: Mutex::new(Self::open(channels)?),
+            channels,
+            skip: pre_skip(params),
+            pcm: vec![0.0; MAX_FRAMES * channels],
+            buf: AudioBuffer::new(MAX_FRAMES as u64, SignalSpec::new(SAMPLE_RATE, layout)),
+        })
+    }
+
+    fn supported_codecs() -> &'static [CodecDescriptor] {
+        &[support_codec!(CODEC_TYPE_OPUS, "opus", "Opus (libopus)")]
+    }
+
+    fn reset(&mut self) {
+        // 跳转后丢弃之前的解码状态，时间戳已经包含预跳过，不再丢弃开头的帧
+        if let Ok(decoder) = Self::open(self.channels) {
+            self.decoder = Mutex::new(decoder);
+        }
+        self.skip = 0;
+    }
+
+    fn codec_params(&self) -> &CodecParameters {
+        &self.params
+    }
+
+    fn decode(&mut self, packet: &Packet) -> Result<AudioBufferRef<'_>> {
+        self.buf.clear();
+        let Ok(input) = packet.buf().try_into() else {
+            return decode_error("opus: empty packet");
+        };
+        let Ok(output) = self.pcm.as_mut_slice().try_into() else {
+            return decode_error("opus: invalid output buffer");
+        };
+        let decoder = self.decoder.get_mut().unwrap_or_else(|e| e.into_inner());
+        let Ok(frames) = decoder.decode_float(Some(input), output, false) else {
+            return decode_error("opus: invalid packet");
+        };
+
+        // 交错采样转换为按声道存放
+        self.buf.render_reserved(Some(frames));
+        for channel in 0..self.channels {
+            let samples = self.pcm.iter().skip(channel).step_by(self.channels);
+            self.buf
+                .chan_mut(channel)
+                .iter_mut()
+                .zip(samples)
+                .for_each(|(dst, src)| *dst = *src);
+        }
+        // 去掉编码器延迟和末尾的填充，实现无缝播放
+        let skip = self.skip.min(frames);
+        self.skip -= skip;
+        self.buf.trim(
+            packet.trim_start() as usize + skip,
+            packet.trim_end() as usize,
+        );
+        Ok(self.buf.as_audio_buffer_ref())
+    }
+
+    fn finalize(&mut self) -> FinalizeResult {
+        FinalizeResult::default()
+    }
+
+    fn last_decoded(&self) -> AudioBufferRef<'_> {
+        self.buf.as_audio_buffer_ref()
+    }
+}
+
+/// 从 `OpusHead` 标识头中读取预跳过的帧数。
+///
+/// Ogg 解复用器不会按预跳过裁剪数据包，由解码器在流的开头丢弃这些帧。
+fn pre_skip(params: &CodecParameters) -> usize {
+    match params.extra_data.as_deref() {
+        Some(head) if head.len() >= 12 => u16::from_le_bytes([head[10], head[11]]) as usize,
+        _ => 0,
+    }
+}
@@ -0,0 +1,141 @@
+//! 在后台线程中运行播放引擎。
+//!
+//! `Player` 是应用程序持有的句柄：命令通过标准库通道发送到引擎线程，
+//! 事件通过启动时传入的 `Publisher` 发布，由应用程序决定发送到哪个通道。
+
+use std::{
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    thread,
+    time::Duration,
+};
+
+use lazy_core::publisher::Publisher;
+
+use crate::{
+    engine::{Engine, PlayerCommand, PlayerEvent},
+    sink::AudioSink,
+};
+
+/// 播放器句柄。
+///
+/// 句柄被丢弃时引擎线程随之退出。
+pub struct Player {
+    /// 命令发送端
+    commands: Sender<PlayerCommand>,
+}
+
+impl Player {
+    /// 启动引擎线程。
+    ///
+    /// 输出端在引擎线程中通过 `make_sink` 创建，因此输出端本身不需要实现 `Send`。
+    /// 引擎产生的事件通过 `events` 发布。
+    pub fn spawn<F>(make_sink: F, events: impl Into<Publisher<PlayerEvent>>) -> Self
+    where
+        F: FnOnce() -> Box<dyn AudioSink> + Send + 'static,
+    {
+        let (command_tx, command_rx) = mpsc::channel();
+        let events = events.into();
+
+        thread::Builder::new()
+            .name("lazy-player".to_string())
+            .spawn(move || Self::run(Engine::new(make_sink(), events), command_rx))
+            .expect("无法创建播放引擎线程");
+
+        Self {
+            commands: command_tx,
+        }
+    }
+
+    /// 引擎线程主循环。
+    fn run(mut engine: Engine, commands: Receiver<PlayerCommand>) {
+        loop {
+            // 播放中不阻塞地检查命令，否则阻塞等待下一条命令
+            let command = if engine.is_playing() {
+                match commands.try_recv() {
+                    Ok(command) => Some(command),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Disconnected) => break,
+                }
+            } else {
+                match commands.recv() {
+                    Ok(command) => Some(command),
+                    Err(_) => break,
+                }
+            };
+
+            match command {
+                // 先处理完所有待处理的命令，再继续解码
+                Some(command) => engine.handle(command),
+                None => {
+                    engine.step();
+                }
+            }
+        }
+    }
+
+    /// 发送一条命令；引擎线程已退出时忽略。
+    pub fn send(&self, command: PlayerCommand) {
+        let _ = self.commands.send(command);
+    }
+
+    /// 从头播放指定文件。
+    pub fn play(&self, path: impl Into<PathBuf>) {
+        self.send(PlayerCommand::Play(path.into()));
+    }
+
+    /// 播放/暂停切换。
+    pub fn toggle_pause(&self) {
+        self.send(PlayerCommand::TogglePause);
+    }
+
+    /// 停止播放。
+    pub fn stop(&self) {
+        self.send(PlayerCommand::Stop);
+    }
+
+    /// 跳转到指定位置。
+    pub fn seek(&self, position: Duration) {
+        self.send(PlayerCommand::Seek(position));
+    }
+
+    /// 设置音量，范围 0..=100。
+    pub fn set_volume(&self, volume: u8) {
+        self.send(PlayerCommand::SetVolume(volume));
+    }
+
+    /// 设置当前曲目播放完毕后无缝播放的曲目，`None` 表示播放完毕后停止。
+    pub fn preload(&self, path: Option<PathBuf>) {
+        self.send(PlayerCommand::Preload(path));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sink::NullSink, test_utils::write_sine_wav};
+    use lazy_core::types::PlaybackState;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    #[tokio::test]
+    async fn test_player_plays_in_background() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.wav");
+        write_sine_wav(&path, 8_000, 1, 4_000);
+
+        let (tx, mut rx) = unbounded_channel();
+        let player = Player::spawn(|| Box::new(NullSink::new()), tx);
+        player.play(&path);
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            let finished = event == PlayerEvent::State(PlaybackState::Stopped);
+            events.push(event);
+            if finished {
+                break;
+            }
+        }
+        assert_eq!(events[0], PlayerEvent::TrackStarted(path.clone()));
+        assert!(events.contains(&PlayerEvent::TrackFinished(path)));
+    }
+}
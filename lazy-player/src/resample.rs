@@ -0,0 +1,249 @@
+//! 重采样模块。
+//!
+//! 无缝播放时输出端保持打开，下一首曲目的格式与输出端不同时，
+//! `Resampler` 把它的采样转换为输出端的采样率和声道数。
+//!
+//! 采样率转换使用 `rubato` 的 FFT 重采样器，频谱乘以抗混叠的低通滤波器后再变换回时域：
+//! 降低采样率时高于新奈奎斯特频率的成分被滤除，而不是混叠到可听频段。
+//! 重采样器按固定长度的块处理音频并带有固定的延迟，
+//! `Resampler` 在内部缓存不足一块的输入、丢弃开头的延迟，曲目结束时用 [`Resampler::finish`] 输出剩余的采样。
+
+use rubato::{FftFixedInOut, Resampler as _};
+
+use crate::decoder::AudioSpec;
+
+/// 逐块转换交错排列的 `f32` 采样的重采样器。
+///
+/// 声道数不同时先混合或复制声道，采样率不同时再经过带限的 FFT 重采样。
+/// 未处理的输入和滤波器的状态在块之间保留，因此分块转换的结果与一次转换整段音频相同，
+/// 块与块之间不会产生间隙或跳变。
+pub struct Resampler {
+    /// 输入格式
+    from: AudioSpec,
+    /// 输出格式
+    to: AudioSpec,
+    /// 采样率不同时使用的 FFT 重采样器
+    fft: Option<FftFixedInOut<f32>>,
+    /// 按声道分开、尚未凑满一块的输入帧
+    input: Vec<Vec<f32>>,
+    /// 按声道分开的一块输出帧
+    output: Vec<Vec<f32>>,
+    /// 开头还要丢弃的输出帧数，即重采样器的延迟
+    delay: usize,
+    /// 已输入的帧数
+    frames_in: u64,
+    /// 已输出的帧数
+    frames_out: u64,
+}
+
+impl Resampler {
+    /// 重采样器每块输入的帧数，实际的块长会按两个采样率的比例向上取整
+    const CHUNK_FRAMES: usize = 1024;
+
+    /// 创建把 `from` 格式转换为 `to` 格式的重采样器。
+    pub fn new(from: AudioSpec, to: AudioSpec) -> Self {
+        let channels = to.channels.max(1) as usize;
+        let fft = (from.sample_rate != to.sample_rate)
+            .then(|| {
+                FftFixedInOut::new(
+                    from.sample_rate as usize,
+                    to.sample_rate as usize,
+                    Self::CHUNK_FRAMES,
+                    channels,
+                )
+                .ok()
+            })
+            .flatten();
+        let (output, delay) = match &fft {
+            Some(fft) => (fft.output_buffer_allocate(true), fft.output_delay()),
+            None => (Vec::new(), 0),
+        };
+        Self {
+            from,
+            to,
+            fft,
+            input: vec![Vec::new(); channels],
+            output,
+            delay,
+            frames_in: 0,
+            frames_out: 0,
+        }
+    }
+
+    /// 转换一块采样，把结果追加到 `output` 中。
+    ///
+    /// 不足重采样器一块的输入会留到下一次调用，曲目结束时需要调用 [`Resampler::finish`]。
+    pub fn process(&mut self, samples: &[f32], output: &mut Vec<f32>) {
+        let channels = self.to.channels.max(1) as usize;
+        let frames = samples.chunks_exact(self.from.channels.max(1) as usize);
+        if self.fft.is_none() {
+            // 采样率相同时只转换声道
+            for frame in frames {
+                output.extend((0..channels).map(|i| map_channel(frame, i, channels)));
+            }
+            return;
+        }
+
+        for frame in frames {
+            for (i, input) in self.input.iter_mut().enumerate() {
+                input.push(map_channel(frame, i, channels));
+            }
+            self.frames_in += 1;
+        }
+        while self.input[0].len() >= self.chunk_frames() {
+            self.resample_chunk(u64::MAX, output);
+        }
+    }
+
+    /// 输出缓存的输入和重采样器延迟的尾部，使输出的总时长与输入相同。
+    pub fn finish(mut self, output: &mut Vec<f32>) {
+        if self.fft.is_none() {
+            return;
+        }
+        let total =
+            (self.frames_in * self.to.sample_rate as u64).div_ceil(self.from.sample_rate as u64);
+        while self.frames_out < total {
+            // 用静音补齐最后一块
+            let chunk = self.chunk_frames();
+            self.input
+                .iter_mut()
+                .for_each(|input| input.resize(chunk, 0.0));
+            self.resample_chunk(total, output);
+        }
+    }
+
+    /// 重采样器每块需要的输入帧数。
+    fn chunk_frames(&self) -> usize {
+        self.fft.as_ref().map_or(0, |fft| fft.input_frames_next())
+    }
+
+    /// 重采样一块输入，跳过延迟后把不超过 `total` 帧的结果交错追加到 `output` 中。
+    fn resample_chunk(&mut self, total: u64, output: &mut Vec<f32>) {
+        let Some(fft) = self.fft.as_mut() else {
+            return;
+        };
+        let chunk = fft.input_frames_next();
+        let (_, frames) = fft
+            .process_into_buffer(&self.input, &mut self.output, None)
+            .expect("缓冲区按重采样器的块长分配");
+        self.input.iter_mut().for_each(|input| {
+            input.drain(..chunk);
+        });
+
+        let skip = self.delay.min(frames);
+        self.delay -= skip;
+        let remaining = usize::try_from(total - self.frames_out).unwrap_or(usize::MAX);
+        let end = frames.min(skip.saturating_add(remaining));
+        for index in skip..end {
+            output.extend(self.output.iter().map(|channel| channel[index]));
+        }
+        self.frames_out += (end - skip) as u64;
+    }
+}
+
+/// 返回一帧采样转换为 `channels` 个声道后第 `i` 个声道的采样。
+fn map_channel(frame: &[f32], i: usize, channels: usize) -> f32 {
+    match frame.len() {
+        n if n == channels => frame[i],
+        // 多声道混合为单声道
+        n if channels == 1 => frame.iter().sum::<f32>() / n as f32,
+        n => frame[i % n],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(sample_rate: u32, channels: u16) -> AudioSpec {
+        AudioSpec {
+            sample_rate,
+            channels,
+        }
+    }
+
+    /// 分块转换 `samples` 并输出尾部。
+    fn resample(from: AudioSpec, to: AudioSpec, samples: &[f32], chunk: usize) -> Vec<f32> {
+        let mut output = Vec::new();
+        let mut resampler = Resampler::new(from, to);
+        for chunk in samples.chunks(chunk) {
+            resampler.process(chunk, &mut output);
+        }
+        resampler.finish(&mut output);
+        output
+    }
+
+    /// 采样率为 `sample_rate` 的正弦波。
+    fn sine(frequency: f32, sample_rate: u32, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| (i as f32 * frequency / sample_rate as f32 * std::f32::consts::TAU).sin())
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_resampler_maps_channels() {
+        let mut output = Vec::new();
+        let mut mono_to_stereo = Resampler::new(spec(8_000, 1), spec(8_000, 2));
+        mono_to_stereo.process(&[0.1, 0.2], &mut output);
+        mono_to_stereo.process(&[0.3], &mut output);
+        assert_eq!(output, vec![0.1, 0.1, 0.2, 0.2, 0.3, 0.3]);
+
+        output.clear();
+        let mut stereo_to_mono = Resampler::new(spec(8_000, 2), spec(8_000, 1));
+        stereo_to_mono.process(&[0.5, 0.25, -0.5, 0.5], &mut output);
+        assert_eq!(output, vec![0.375, 0.0]);
+    }
+
+    #[test]
+    fn test_resampler_is_continuous_across_chunks() {
+        let tone = sine(440.0, 8_000, 5_000);
+
+        // 分块转换与一次转换整段音频的结果相同，时长不变
+        let whole = resample(spec(8_000, 1), spec(12_000, 1), &tone, tone.len());
+        let chunked = resample(spec(8_000, 1), spec(12_000, 1), &tone, 7);
+        assert_eq!(whole.len(), 7_500);
+        assert_eq!(chunked, whole);
+
+        // 去掉延迟后输出与原信号对齐，中间部分与 12 kHz 下的同一正弦波一致
+        let expected = sine(440.0, 12_000, 7_500);
+        assert!(
+            whole[1_000..6_500]
+                .iter()
+                .zip(&expected[1_000..6_500])
+                .all(|(a, b)| (a - b).abs() < 1e-2)
+        );
+
+        let stereo: Vec<f32> = tone.iter().flat_map(|&s| [s, -s]).collect();
+        let whole = resample(spec(8_000, 2), spec(16_000, 2), &stereo, stereo.len());
+        let chunked = resample(spec(8_000, 2), spec(16_000, 2), &stereo, 14);
+        assert_eq!(whole.len(), 20_000);
+        assert_eq!(chunked, whole);
+        assert!(whole.chunks_exact(2).all(|frame| frame[0] == -frame[1]));
+    }
+
+    #[test]
+    fn test_resampler_filters_above_nyquist() {
+        // 16 kHz 转换为 8 kHz 时 6 kHz 的正弦波高于新的奈奎斯特频率，应被滤除而不是混叠为 2 kHz
+        let high = resample(
+            spec(16_000, 1),
+            spec(8_000, 1),
+            &sine(6_000.0, 16_000, 16_000),
+            100,
+        );
+        assert_eq!(high.len(), 8_000);
+        assert!(rms(&high[500..7_500]) < 1e-2);
+
+        // 低于奈奎斯特频率的成分保持原有的幅度
+        let low = resample(
+            spec(16_000, 1),
+            spec(8_000, 1),
+            &sine(1_000.0, 16_000, 16_000),
+            100,
+        );
+        assert!((rms(&low[500..7_500]) - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-2);
+    }
+}
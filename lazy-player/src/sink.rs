@@ -0,0 +1,277 @@
+//! 可插拔的音频输出端模块。
+//!
+//! 播放引擎只依赖 `AudioSink` trait，因此可以在没有声卡的无头环境中
+//! 使用 `NullSink` 或 `WavSink` 运行和测试。
+
+#[cfg(feature = "cpal")]
+mod cpal_sink;
+
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+use log::warn;
+
+#[cfg(feature = "cpal")]
+pub use cpal_sink::CpalSink;
+
+use crate::{decoder::AudioSpec, error::PlayerError};
+
+/// 音频输出端 trait。
+///
+/// 输出端在播放引擎的线程中创建和使用，因此不要求实现 `Send`。
+pub trait AudioSink {
+    /// 以给定格式打开（或重新配置）输出端。
+    fn open(&mut self, spec: AudioSpec) -> Result<(), PlayerError>;
+
+    /// 写入交错排列的 `f32` 采样，可阻塞直到输出端能够接收更多数据。
+    fn write(&mut self, samples: &[f32]) -> Result<(), PlayerError>;
+
+    /// 暂停输出。
+    fn pause(&mut self) {}
+
+    /// 恢复输出。
+    fn resume(&mut self) {}
+
+    /// 丢弃尚未播放的缓冲数据（停止或跳转时调用）。
+    fn clear(&mut self) {}
+
+    /// 确保所有已写入的数据都已输出。
+    fn flush(&mut self) -> Result<(), PlayerError> {
+        Ok(())
+    }
+}
+
+/// 丢弃所有采样的空输出端。
+///
+/// 可选择按实时速度消费数据，使无头环境下的播放进度与真实播放一致。
+#[derive(Default)]
+pub struct NullSink {
+    /// 当前格式
+    spec: Option<AudioSpec>,
+    /// 已写入的总帧数，可在其他线程中读取
+    frames: Arc<AtomicU64>,
+    /// 是否按实时速度消费数据
+    realtime: bool,
+    /// 实时模式下的计时起点
+    clock: Option<Instant>,
+    /// 实时模式下自计时起点以来写入的音频时长
+    written: Duration,
+}
+
+impl NullSink {
+    /// 实时模式下允许提前写入的时长
+    const LEAD: Duration = Duration::from_millis(200);
+
+    /// 创建一个立即消费所有数据的空输出端。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建一个按实时速度消费数据的空输出端。
+    pub fn realtime() -> Self {
+        Self {
+            realtime: true,
+            ..Default::default()
+        }
+    }
+
+    /// 返回已写入帧数的共享计数器。
+    pub fn frame_counter(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.frames)
+    }
+
+    /// 重置实时计时。
+    fn reset_clock(&mut self) {
+        self.clock = None;
+        self.written = Duration::ZERO;
+    }
+}
+
+impl AudioSink for NullSink {
+    fn open(&mut self, spec: AudioSpec) -> Result<(), PlayerError> {
+        self.spec = Some(spec);
+        self.reset_clock();
+        Ok(())
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), PlayerError> {
+        let spec = self
+            .spec
+            .ok_or_else(|| PlayerError::Sink("sink is not open".to_string()))?;
+        let frames = (samples.len() / spec.channels as usize) as u64;
+        self.frames.fetch_add(frames, Ordering::Relaxed);
+
+        if self.realtime {
+            let start = *self.clock.get_or_insert_with(Instant::now);
+            self.written += spec.frames_to_duration(frames);
+            let elapsed = start.elapsed();
+            if self.written > elapsed + Self::LEAD {
+                thread::sleep(self.written - elapsed - Self::LEAD);
+            }
+        }
+        Ok(())
+    }
+
+    fn pause(&mut self) {
+        self.reset_clock();
+    }
+
+    fn clear(&mut self) {
+        self.reset_clock();
+    }
+}
+
+/// 将所有采样写入 32 位浮点 WAV 文件的输出端。
+pub struct WavSink {
+    /// 输出文件路径
+    path: PathBuf,
+    /// WAV 写入器，首次打开时创建
+    writer: Option<WavWriter<BufWriter<File>>>,
+    /// 当前格式
+    spec: Option<AudioSpec>,
+}
+
+impl WavSink {
+    /// 创建一个写入到 `path` 的 WAV 输出端。文件在首次打开时创建。
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            writer: None,
+            spec: None,
+        }
+    }
+
+    /// 完成写入并更新 WAV 文件头。
+    pub fn finalize(&mut self) -> Result<(), PlayerError> {
+        if let Some(writer) = self.writer.take() {
+            writer
+                .finalize()
+                .map_err(|e| PlayerError::Sink(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+impl AudioSink for WavSink {
+    fn open(&mut self, spec: AudioSpec) -> Result<(), PlayerError> {
+        match self.spec {
+            Some(current) if current == spec => Ok(()),
+            // 单个 WAV 文件只能有一种格式
+            Some(current) => Err(PlayerError::Sink(format!(
+                "WAV sink cannot change format from {current:?} to {spec:?}"
+            ))),
+            None => {
+                let wav_spec = WavSpec {
+                    channels: spec.channels,
+                    sample_rate: spec.sample_rate,
+                    bits_per_sample: 32,
+                    sample_format: SampleFormat::Float,
+                };
+                let writer = WavWriter::create(&self.path, wav_spec)
+                    .map_err(|e| PlayerError::Sink(e.to_string()))?;
+                self.writer = Some(writer);
+                self.spec = Some(spec);
+                Ok(())
+            }
+        }
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), PlayerError> {
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| PlayerError::Sink("sink is not open".to_string()))?;
+        samples
+            .iter()
+            .try_for_each(|&s| writer.write_sample(s))
+            .map_err(|e| PlayerError::Sink(e.to_string()))
+    }
+
+    fn flush(&mut self) -> Result<(), PlayerError> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.flush().map_err(|e| PlayerError::Sink(e.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 创建默认的输出端。
+///
+/// 启用 `cpal` 特性且能打开声卡时输出到声卡，否则记录警告并退回到实时的 `NullSink`。
+pub fn default_sink() -> Box<dyn AudioSink> {
+    #[cfg(feature = "cpal")]
+    match CpalSink::new() {
+        Ok(sink) => return Box::new(sink),
+        Err(e) => warn!("Failed to open the audio output, playing into a null sink: {e}"),
+    }
+    #[cfg(not(feature = "cpal"))]
+    warn!("Built without the `cpal` feature, playing into a null sink");
+    Box::new(NullSink::realtime())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: AudioSpec = AudioSpec {
+        sample_rate: 8_000,
+        channels: 2,
+    };
+
+    #[test]
+    fn test_null_sink_counts_frames() {
+        let mut sink = NullSink::new();
+        let counter = sink.frame_counter();
+        assert!(sink.write(&[0.0; 4]).is_err(), "未打开时写入应失败");
+
+        sink.open(SPEC).unwrap();
+        sink.write(&[0.0; 100]).unwrap();
+        sink.write(&[0.0; 20]).unwrap();
+        assert_eq!(counter.load(Ordering::Relaxed), 60);
+    }
+
+    #[test]
+    fn test_null_sink_realtime_paces_output() {
+        let mut sink = NullSink::realtime();
+        sink.open(SPEC).unwrap();
+        let start = Instant::now();
+        // 0.5 秒的音频，减去允许提前写入的 200ms
+        sink.write(&[0.0; 8_000]).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_wav_sink_writes_samples() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.wav");
+
+        let mut sink = WavSink::new(&path);
+        sink.open(SPEC).unwrap();
+        sink.open(SPEC).unwrap();
+        sink.write(&[0.25, -0.25, 0.5, -0.5]).unwrap();
+        assert!(
+            sink.open(AudioSpec {
+                sample_rate: 44_100,
+                channels: 2
+            })
+            .is_err()
+        );
+        sink.finalize().unwrap();
+
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().sample_rate, 8_000);
+        assert_eq!(reader.spec().channels, 2);
+        let samples: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+        assert_eq!(samples, vec![0.25, -0.25, 0.5, -0.5]);
+    }
+}
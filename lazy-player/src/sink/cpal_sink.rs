@@ -0,0 +1,138 @@
+//! 基于 `cpal` 的声卡输出端。
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
+};
+
+use cpal::{
+    BufferSize, Device, SampleRate, Stream, StreamConfig,
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+};
+
+use crate::{decoder::AudioSpec, error::PlayerError, sink::AudioSink};
+
+/// 引擎线程与音频回调之间共享的采样队列。
+type SharedBuffer = Arc<(Mutex<VecDeque<f32>>, Condvar)>;
+
+/// 输出到系统默认声卡的输出端。
+pub struct CpalSink {
+    /// 输出设备
+    device: Device,
+    /// 当前的输出流
+    stream: Option<Stream>,
+    /// 当前格式
+    spec: Option<AudioSpec>,
+    /// 待播放的采样队列
+    buffer: SharedBuffer,
+}
+
+impl CpalSink {
+    /// 缓冲区最多保存的音频时长（毫秒）
+    const BUFFER_MS: usize = 500;
+
+    /// 打开系统默认输出设备，设备无法提供输出格式时返回错误。
+    pub fn new() -> Result<Self, PlayerError> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or_else(|| PlayerError::Sink("no output device available".to_string()))?;
+        device
+            .default_output_config()
+            .map_err(|e| PlayerError::Sink(e.to_string()))?;
+        Ok(Self {
+            device,
+            stream: None,
+            spec: None,
+            buffer: Arc::new((Mutex::new(VecDeque::new()), Condvar::new())),
+        })
+    }
+
+    /// 缓冲区最多保存的采样数。
+    fn capacity(&self) -> usize {
+        self.spec.map_or(0, |spec| {
+            spec.sample_rate as usize * spec.channels as usize * Self::BUFFER_MS / 1000
+        })
+    }
+}
+
+impl AudioSink for CpalSink {
+    fn open(&mut self, spec: AudioSpec) -> Result<(), PlayerError> {
+        if self.spec == Some(spec) && self.stream.is_some() {
+            return Ok(());
+        }
+
+        let config = StreamConfig {
+            channels: spec.channels,
+            sample_rate: SampleRate(spec.sample_rate),
+            buffer_size: BufferSize::Default,
+        };
+        let buffer = Arc::clone(&self.buffer);
+        let stream = self
+            .device
+            .build_output_stream(
+                &config,
+                move |data: &mut [f32], _| {
+                    let (queue, cvar) = &*buffer;
+                    let mut queue = queue.lock().unwrap();
+                    // 数据不足时以静音填充
+                    data.iter_mut()
+                        .for_each(|s| *s = queue.pop_front().unwrap_or(0.0));
+                    cvar.notify_one();
+                },
+                |e| log::error!("Audio stream error: {e}"),
+                None,
+            )
+            .map_err(|e| PlayerError::Sink(e.to_string()))?;
+        stream
+            .play()
+            .map_err(|e| PlayerError::Sink(e.to_string()))?;
+
+        self.stream = Some(stream);
+        self.spec = Some(spec);
+        Ok(())
+    }
+
+    fn write(&mut self, samples: &[f32]) -> Result<(), PlayerError> {
+        let capacity = self.capacity();
+        let (queue, cvar) = &*self.buffer;
+        let mut queue = queue.lock().unwrap();
+        // 缓冲区已满时等待音频回调消费数据
+        while queue.len() + samples.len() > capacity.max(samples.len()) {
+            queue = cvar
+                .wait_timeout(queue, Duration::from_millis(100))
+                .unwrap()
+                .0;
+        }
+        queue.extend(samples);
+        Ok(())
+    }
+
+    fn pause(&mut self) {
+        if let Some(stream) = &self.stream {
+            let _ = stream.pause();
+        }
+    }
+
+    fn resume(&mut self) {
+        if let Some(stream) = &self.stream {
+            let _ = stream.play();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.buffer.0.lock().unwrap().clear();
+    }
+
+    fn flush(&mut self) -> Result<(), PlayerError> {
+        let (queue, cvar) = &*self.buffer;
+        let mut queue = queue.lock().unwrap();
+        while !queue.is_empty() {
+            queue = cvar
+                .wait_timeout(queue, Duration::from_millis(100))
+                .unwrap()
+                .0;
+        }
+        Ok(())
+    }
+}
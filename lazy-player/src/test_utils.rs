@@ -0,0 +1,89 @@
+//! 测试辅助函数。
+
+use std::path::Path;
+
+/// 生成一个包含正弦波的 16 位 WAV 文件，返回写入的帧数。
+pub(crate) fn write_sine_wav(path: &Path, sample_rate: u32, channels: u16, frames: u32) -> u32 {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).unwrap();
+    for i in 0..frames {
+        let t = i as f32 / sample_rate as f32;
+        let sample = ((t * 440.0 * std::f32::consts::TAU).sin() * i16::MAX as f32 * 0.5) as i16;
+        for _ in 0..channels {
+            writer.write_sample(sample).unwrap();
+        }
+    }
+    writer.finalize().unwrap();
+    frames
+}
+
+/// 生成一个包含正弦波的 Ogg Opus 文件，返回编码的帧数（48 kHz）。
+#[cfg(feature = "opus")]
+pub(crate) fn write_sine_opus(path: &Path, channels: u8, frames: u32) -> u32 {
+    use audiopus::{Application, Channels, SampleRate, coder::Encoder};
+    use ogg::{PacketWriteEndInfo, PacketWriter};
+
+    const FRAME_SIZE: usize = 960;
+    let opus_channels = if channels == 1 {
+        Channels::Mono
+    } else {
+        Channels::Stereo
+    };
+    let encoder = Encoder::new(SampleRate::Hz48000, opus_channels, Application::Audio).unwrap();
+    let pre_skip = encoder.lookahead().unwrap() as u16;
+    let mut writer = PacketWriter::new(std::fs::File::create(path).unwrap());
+
+    // 标识头和注释头各占一页
+    let mut head = b"OpusHead".to_vec();
+    head.extend([1, channels]);
+    head.extend(pre_skip.to_le_bytes());
+    head.extend(48_000u32.to_le_bytes());
+    head.extend([0, 0, 0]);
+    writer
+        .write_packet(head.into_boxed_slice(), 1, PacketWriteEndInfo::EndPage, 0)
+        .unwrap();
+    let mut tags = b"OpusTags".to_vec();
+    tags.extend([0; 8]);
+    writer
+        .write_packet(tags.into_boxed_slice(), 1, PacketWriteEndInfo::EndPage, 0)
+        .unwrap();
+
+    // 编码器的输出比输入延迟 `pre_skip` 帧，末尾补零使所有输入都被编码
+    let total = (frames + pre_skip as u32) as u64;
+    let signal: Vec<f32> = (0..total)
+        .flat_map(|i| {
+            let t = i as f32 / 48_000.0;
+            let sample = if i < frames as u64 {
+                (t * 440.0 * std::f32::consts::TAU).sin() * 0.5
+            } else {
+                0.0
+            };
+            std::iter::repeat_n(sample, channels as usize)
+        })
+        .collect();
+    let chunks: Vec<&[f32]> = signal.chunks(FRAME_SIZE * channels as usize).collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let mut input = chunk.to_vec();
+        input.resize(FRAME_SIZE * channels as usize, 0.0);
+        let mut packet = vec![0; 4_000];
+        let len = encoder.encode_float(&input, &mut packet).unwrap();
+        packet.truncate(len);
+
+        // 每个数据包占一页，颗粒位置包括预跳过的帧，最后一页的颗粒位置标记实际的结束位置
+        let granule = (((i + 1) * FRAME_SIZE) as u64).min(total);
+        let end = if i + 1 == chunks.len() {
+            PacketWriteEndInfo::EndStream
+        } else {
+            PacketWriteEndInfo::EndPage
+        };
+        writer
+            .write_packet(packet.into_boxed_slice(), 1, end, granule)
+            .unwrap();
+    }
+    frames
+}
@@ -1,7 +1,8 @@
-mod navbar;
+pub mod navbar;
 mod player;
 mod progress;
 pub mod root;
 mod router_view;
 pub mod traits;
 pub mod types;
+mod views;
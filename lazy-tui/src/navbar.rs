@@ -1,18 +1,21 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, cell::Cell};
 
 use lazy_core::structs::{BorderStyle, TitleStyle, TuiStyle};
-use lazy_core::traits::{HasBorderStyleSetter, HasTuiStyle};
+use lazy_core::theme::TuiTheme;
+use lazy_core::traits::{
+    HasBorderStyleSetter, HasTitleStyleSetter, HasTuiStyle, HasTuiStyleSetter,
+};
 use lazy_macro::DeriveHasTuiStyle;
 use ratatui::{
     Frame,
-    layout::Rect,
-    style::{Color, Modifier, Style, Stylize},
+    layout::{Alignment, Rect},
+    style::{Modifier, Style, Stylize},
     text::{Line, Span},
     widgets::Paragraph,
 };
 
 use crate::traits::TuiEventHandle;
-use crate::types::TuiEnent;
+use crate::types::{EventResult, Mouse, MouseKind, TuiAction, TuiEnent};
 use crate::{
     traits::{RenderTui, TuiBlock},
     types::Direction,
@@ -20,9 +23,9 @@ use crate::{
 
 /// `NavbarItem` 枚举定义了导航栏中所有可能的项目。
 ///
-/// 这个枚举派生了 `Default`, `Clone`, `Copy`, `PartialEq`, `Eq`, 和 `Debug` trait，
-/// 以便进行默认值设置、复制、比较和调试打印。
-#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+/// 这个枚举派生了 `Default`, `Clone`, `Copy`, `PartialEq`, `Eq`, `Hash` 和 `Debug` trait，
+/// 以便进行默认值设置、复制、比较、作为映射表的键和调试打印。
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum NavbarItem {
     /// 播放队列页
     #[default]
@@ -45,7 +48,7 @@ pub enum NavbarItem {
 
 impl NavbarItem {
     /// 切换到下一个导航项。
-    fn next(self) -> Self {
+    pub(crate) fn next(self) -> Self {
         let variants = Self::VARIANTS;
         let current_index = variants.iter().position(|&item| item == self).unwrap_or(0);
         let next_index = (current_index + 1) % variants.len();
@@ -55,7 +58,7 @@ impl NavbarItem {
     /// 切换到上一个导航项。
     ///
     /// 这个方法实现了一个循环切换逻辑，当到达第一个导航项时会重新回到最后一个。
-    fn prev(self) -> Self {
+    pub(crate) fn prev(self) -> Self {
         let variants = Self::VARIANTS;
         let current_index = variants.iter().position(|&item| item == self).unwrap_or(0);
         let prev_index = (current_index + variants.len() - 1) % variants.len();
@@ -94,30 +97,32 @@ pub struct NavbarTui {
     selected_icon: String,
     /// 未选中项的图标
     not_selected_icon: String,
+    /// 上一次渲染时导航项所在的区域，用于鼠标点击
+    area: Cell<Rect>,
+    /// 等待应用程序执行的操作
+    actions: Vec<TuiAction>,
 }
 
 impl Default for NavbarTui {
     /// 创建一个默认的 `Navbar` 实例。
     ///
-    /// 默认实现中，为选中和未选中的导航项设置了不同的背景色、前景色和文本修饰符，
-    /// 以在视觉上区分它们。
+    /// 选中和未选中的导航项的样式由 `apply_theme` 根据当前主题设置，
+    /// 两者的背景色、前景色互换，以在视觉上区分它们。
     fn default() -> Self {
-        Self {
+        let mut navbar = Self {
             title: Default::default(),
             border: Default::default(),
             style: Default::default(),
-            selected: Style::default()
-                .bg(Color::Rgb(130, 170, 255))
-                .fg(Color::Rgb(47, 51, 77))
-                .add_modifier(Modifier::ITALIC),
-            not_selected: Style::default()
-                .bg(Color::Rgb(47, 51, 77))
-                .fg(Color::Rgb(130, 170, 255))
-                .add_modifier(Modifier::ITALIC),
+            selected: Style::default(),
+            not_selected: Style::default(),
             selected_item: Default::default(),
             selected_icon: "".to_string(),
             not_selected_icon: "".to_string(),
-        }
+            area: Cell::new(Rect::default()),
+            actions: Vec::new(),
+        };
+        navbar.apply_theme(&TuiTheme::current());
+        navbar
     }
 }
 
@@ -129,6 +134,7 @@ impl RenderTui for NavbarTui {
     fn render(&self, frame: &mut Frame, rect: Rect) {
         // 获取去掉边框的内部区域
         let inner = self.get_inner(rect);
+        self.area.set(inner);
         // 渲染根组件边框和标题
         frame.render_widget(self.to_block().bg(self.border.bg()), rect);
 
@@ -150,6 +156,21 @@ impl RenderTui for NavbarTui {
     fn as_border_mut(&mut self) -> Option<&mut dyn HasBorderStyleSetter> {
         Some(self)
     }
+
+    /// 重新设置导航栏的样式，选中项和未选中项互换前景色与背景色。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_title_theme(theme);
+        self.apply_border_theme(theme);
+        self.apply_tui_theme(theme);
+        self.selected = Style::default()
+            .bg(theme.blue)
+            .fg(theme.bg_highlight)
+            .add_modifier(Modifier::ITALIC);
+        self.not_selected = Style::default()
+            .bg(theme.bg_highlight)
+            .fg(theme.blue)
+            .add_modifier(Modifier::ITALIC);
+    }
 }
 
 impl TuiEventHandle for NavbarTui {
@@ -157,7 +178,7 @@ impl TuiEventHandle for NavbarTui {
     ///
     /// 此方法作为事件处理的中央分发器。它使用 `delegate_to_widget!` 宏
     /// 来匹配不同的事件，并将它们高效地路由到正确的子组件进行处理。
-    fn event_handle(&mut self, event: TuiEnent) {
+    fn event_handle(&mut self, event: TuiEnent) -> EventResult {
         match event {
             // 当接收到 `Playback` 事件时...
             TuiEnent::Navbar(direction) => {
@@ -166,8 +187,31 @@ impl TuiEventHandle for NavbarTui {
             TuiEnent::NavbarIcon(selected_icon, not_selected_icon) => {
                 self.set_icon(selected_icon, not_selected_icon);
             }
-            _ => (),
+            TuiEnent::SelectView(item) => self.selected_item = item,
+            // 导航栏获得焦点时用左右方向键切换页面
+            TuiEnent::Picker(Direction::Left) => {
+                let item = self.selected_item.prev();
+                self.actions.push(TuiAction::SelectView(item));
+            }
+            TuiEnent::Picker(Direction::Right) => {
+                let item = self.selected_item.next();
+                self.actions.push(TuiAction::SelectView(item));
+            }
+            // 点击导航项时请求切换页面，页面和导航栏收到 `SelectView` 后一起切换
+            TuiEnent::Mouse(mouse) if mouse.kind == MouseKind::Click => {
+                let Some(item) = self.item_at(mouse) else {
+                    return EventResult::Ignored;
+                };
+                self.actions.push(TuiAction::SelectView(item));
+            }
+            _ => return EventResult::Ignored,
         }
+        EventResult::Handled
+    }
+
+    /// 取出等待应用程序执行的操作。
+    fn take_actions(&mut self) -> Vec<TuiAction> {
+        std::mem::take(&mut self.actions)
     }
 }
 impl NavbarTui {
@@ -276,6 +320,30 @@ impl NavbarTui {
         Line::from(spans)
     }
 
+    /// 返回鼠标位置上的导航项。
+    ///
+    /// 每个导航项（分隔符和文本）的宽度相同，整行按对齐方式放置在上一次渲染的区域中。
+    fn item_at(&self, mouse: Mouse) -> Option<NavbarItem> {
+        let area = self.area.get();
+        if !mouse.is_in(area) {
+            return None;
+        }
+        let variants = NavbarItem::VARIANTS;
+        let line_width = self.build_navbar_line(area.width).width() as u16;
+        let item_width = area.width / variants.len() as u16;
+        if line_width == 0 || item_width == 0 {
+            return None;
+        }
+        let start = area.x
+            + match self.tui_alignment() {
+                Alignment::Left => 0,
+                Alignment::Center => area.width.saturating_sub(line_width) / 2,
+                Alignment::Right => area.width.saturating_sub(line_width),
+            };
+        let column = mouse.column.checked_sub(start)?;
+        variants.get((column / item_width) as usize).copied()
+    }
+
     /// 根据给定的方向（左或右）切换导航栏的选中项。
     ///
     /// # Arguments
@@ -285,6 +353,8 @@ impl NavbarTui {
         match direction {
             Direction::Left => self.selected_item = self.selected_item.prev(),
             Direction::Right => self.selected_item = self.selected_item.next(),
+            // 导航栏是水平的，忽略上下方向
+            Direction::Up | Direction::Down => (),
         };
     }
 
@@ -301,9 +371,9 @@ impl NavbarTui {
     ///
     /// # Example
     ///
-    /// ``` no_run
-    /// use lazy_tui::navbar::Navbar;
-    /// let mut navbar = Navbar::default();
+    /// ```
+    /// use lazy_tui::navbar::NavbarTui;
+    /// let mut navbar = NavbarTui::default();
     /// navbar.set_icon(">>", "•");
     /// ```
     pub fn set_icon<'a, T: Into<Cow<'a, str>>>(&mut self, selected_icon: T, not_selected_icon: T) {
@@ -321,7 +391,7 @@ impl NavbarTui {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ratatui::{Terminal, backend::TestBackend};
+    use ratatui::{Terminal, backend::TestBackend, style::Color};
 
     #[test]
     fn test_navbar_item_next_prev() {
@@ -416,6 +486,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_navbar_tui_click_selects_view() {
+        let backend = TestBackend::new(100, 3);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut navbar = NavbarTui::default();
+        terminal.draw(|f| navbar.render(f, f.area())).unwrap();
+
+        // 找到 "Albums" 所在的列并点击
+        let buffer = terminal.backend().buffer();
+        let row = navbar.area.get().y;
+        let symbols: Vec<&str> = (0..100).map(|x| buffer[(x, row)].symbol()).collect();
+        let column = symbols
+            .windows(6)
+            .position(|w| w.concat() == "Albums")
+            .unwrap() as u16;
+        let click = |column| Mouse {
+            kind: MouseKind::Click,
+            column,
+            row,
+        };
+        navbar.event_handle(TuiEnent::Mouse(click(column)));
+        assert_eq!(
+            navbar.take_actions(),
+            vec![TuiAction::SelectView(NavbarItem::Albums)]
+        );
+        assert_eq!(
+            navbar.selected_item,
+            NavbarItem::Queue,
+            "等待应用程序切换页面"
+        );
+
+        navbar.event_handle(TuiEnent::SelectView(NavbarItem::Albums));
+        assert_eq!(navbar.selected_item, NavbarItem::Albums);
+        navbar.event_handle(TuiEnent::Mouse(Mouse {
+            row: row + 5,
+            ..click(column)
+        }));
+        assert!(navbar.take_actions().is_empty());
+
+        // 获得焦点时用左右方向键切换页面，上下方向键不处理
+        navbar.event_handle(TuiEnent::Picker(Direction::Right));
+        navbar.event_handle(TuiEnent::Picker(Direction::Left));
+        assert!(
+            !navbar
+                .event_handle(TuiEnent::Picker(Direction::Down))
+                .is_handled()
+        );
+        assert_eq!(
+            navbar.take_actions(),
+            vec![
+                TuiAction::SelectView(NavbarItem::Albums.next()),
+                TuiAction::SelectView(NavbarItem::Albums.prev())
+            ]
+        );
+    }
+
     #[test]
     fn test_navbar_tui_render_smoke_test() {
         let backend = TestBackend::new(100, 30);
@@ -429,4 +555,3 @@ mod tests {
             .unwrap();
     }
 }
-
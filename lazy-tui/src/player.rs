@@ -11,7 +11,8 @@ mod volume;
 // 从 lazy_core 中导入结构体
 use lazy_core::{
     structs::{BorderStyle, TitleStyle, TuiStyle},
-    traits::HasBorderStyleSetter,
+    theme::TuiTheme,
+    traits::{HasBorderStyleSetter, HasTitleStyleSetter, HasTuiStyleSetter},
 };
 // 导入宏
 use lazy_macro::{DeriveHasTuiStyle, auto_delegate_events};
@@ -28,7 +29,7 @@ use crate::{
         playback_progress::PlaybackProgressTui, track::TrackTui, volume::VolumeTui,
     },
     traits::TuiEventHandle,
-    types::TuiEnent,
+    types::{EventResult, TuiAction, TuiEnent},
 };
 // 从当前 crate 中导入 traits
 use crate::traits::{HasWidgets, RenderTui, TuiBlock};
@@ -123,6 +124,14 @@ impl RenderTui for PlayerTui {
     fn as_border_mut(&mut self) -> Option<&mut dyn HasBorderStyleSetter> {
         Some(self)
     }
+
+    /// 重新设置播放器的样式，并转发给所有子组件。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_title_theme(theme);
+        self.apply_border_theme(theme);
+        self.apply_tui_theme(theme);
+        self.widgets.iter_mut().for_each(|f| f.apply_theme(theme));
+    }
 }
 
 impl HasWidgets for PlayerTui {
@@ -138,10 +147,21 @@ impl HasWidgets for PlayerTui {
 
 #[auto_delegate_events(
     TuiEnent::Playback=>(PlaybackTui,toggle_state()),
+    TuiEnent::PlaybackState(state) => (PlaybackTui,set_playback_state(state)),
     TuiEnent::Volume(delta) => (VolumeTui,adjust_volume(delta)),
-    TuiEnent::PlaybackMode => (PlaybackModeTui,toggle_mode()),
+    TuiEnent::PlaybackMode(mode) => (PlaybackModeTui,set_mode(mode)),
     TuiEnent::Artist(artist) => (ArtistTui,set_artist(artist)),
     TuiEnent::Track(track) => (TrackTui,set_track(track)),
-    TuiEnent::PlaybackProgress(duration, progress) => (PlaybackProgressTui,set_progress(progress); set_duration(duration))
+    TuiEnent::PlaybackProgress(progress, duration) => (PlaybackProgressTui,set_progress(progress); set_duration(duration)),
+    TuiEnent::Seek(position) => (PlaybackProgressTui,set_progress(position)),
+    TuiEnent::Mouse(mouse) => (VolumeTui,mouse(mouse)),
+    TuiEnent::Picker(direction) => (VolumeTui,pick(direction))
 )]
-impl TuiEventHandle for PlayerTui {}
+impl TuiEventHandle for PlayerTui {
+    /// 取出子组件产生的操作（在音量上滚动滚轮或获得焦点时用方向键调整音量）。
+    fn take_actions(&mut self) -> Vec<TuiAction> {
+        self.get_widget_mut::<VolumeTui>()
+            .map(VolumeTui::take_actions)
+            .unwrap_or_default()
+    }
+}
@@ -1,7 +1,11 @@
 //! `ArtistTui` 模块，用于在 TUI 中显示当前播放的歌手信息。
 
 // 从 lazy_core 中导入 TuiStyle 结构体和 HasTuiStyle trait
-use lazy_core::{structs::TuiStyle, traits::HasTuiStyle};
+use lazy_core::{
+    structs::TuiStyle,
+    theme::TuiTheme,
+    traits::{HasTuiStyle, HasTuiStyleSetter},
+};
 // 导入宏，用于自动派生 trait
 use lazy_macro::DeriveHasTuiStyle;
 use std::borrow::Cow;
@@ -54,6 +58,11 @@ impl RenderTui for ArtistTui {
         // 在 frame 的指定 rect 区域渲染 widget
         frame.render_widget(widget, rect);
     }
+
+    /// 用主题的颜色重新设置歌手名称的样式。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_tui_theme(theme);
+    }
 }
 
 impl ArtistTui {
@@ -1,4 +1,9 @@
-use lazy_core::{structs::TuiStyle, traits::HasTuiStyle};
+use lazy_core::{
+    structs::TuiStyle,
+    theme::TuiTheme,
+    traits::{HasTuiStyle, HasTuiStyleSetter},
+    types::PlaybackState,
+};
 use lazy_macro::DeriveHasTuiStyle;
 use ratatui::{
     Frame,
@@ -8,15 +13,6 @@ use ratatui::{
 
 use crate::traits::RenderTui;
 
-/// 播放状态枚举
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-pub enum PlaybackState {
-    Playing,
-    Paused,
-    #[default]
-    Stopped,
-}
-
 /// 播放状态 TUI 组件
 #[derive(DeriveHasTuiStyle)]
 pub struct PlaybackTui {
@@ -42,6 +38,11 @@ impl RenderTui for PlaybackTui {
             .alignment(self.tui_alignment());
         frame.render_widget(widget, rect);
     }
+
+    /// 用主题的颜色重新设置播放状态的样式。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_tui_theme(theme);
+    }
 }
 
 impl PlaybackTui {
@@ -54,6 +55,7 @@ impl PlaybackTui {
     }
 
     /// 获取当前播放状态
+    #[cfg(test)]
     pub(crate) fn state(&self) -> PlaybackState {
         self.state
     }
@@ -140,4 +142,3 @@ mod tests {
             .unwrap();
     }
 }
-
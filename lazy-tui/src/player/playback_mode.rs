@@ -2,56 +2,21 @@
 
 use crate::traits::RenderTui;
 // 从 lazy_core 中导入 TuiStyle 结构体和 HasTuiStyle trait
-use lazy_core::{structs::TuiStyle, traits::HasTuiStyle};
+use lazy_core::{
+    structs::TuiStyle,
+    theme::TuiTheme,
+    traits::{HasTuiStyle, HasTuiStyleSetter},
+    types::PlaybackMode,
+};
 use lazy_macro::DeriveHasTuiStyle;
 use ratatui::{
     Frame,
     layout::{Alignment, Rect},
-    style::{Color, Style, Stylize},
+    style::Style,
     text::{Line, Span},
     widgets::Paragraph,
 };
 
-/// 定义了不同的播放模式。
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
-pub enum PlaybackMode {
-    /// **列表循环**: 播放完列表最后一首后，从第一首开始继续播放。
-    #[default]
-    Repeat,
-    /// **随机播放**: 随机播放列表中的曲目。
-    Random,
-    /// **消费模式**: 播放过的曲目将从列表中移除（或标记为不再播放）。
-    Consume,
-    /// **单曲循环**: 单独重复播放当前曲目。
-    Single,
-}
-
-impl PlaybackMode {
-    /// 包含所有播放模式的常量数组，用于迭代。
-    pub(crate) const VARIANTS: &'static [PlaybackMode] = &[
-        PlaybackMode::Repeat,
-        PlaybackMode::Random,
-        PlaybackMode::Consume,
-        PlaybackMode::Single,
-    ];
-
-    /// 返回一个包含所有播放模式的静态切片，方便在 UI 中显示。
-    pub(crate) fn variants() -> &'static [PlaybackMode] {
-        Self::VARIANTS
-    }
-
-    /// 切换到下一个播放模式。
-    /// 这是一个循环切换，例如 `Repeat` -> `Random` -> `Consume` -> `Single` -> `Repeat`。
-    fn next(self) -> Self {
-        match self {
-            PlaybackMode::Repeat => PlaybackMode::Random,
-            PlaybackMode::Random => PlaybackMode::Consume,
-            PlaybackMode::Consume => PlaybackMode::Single,
-            PlaybackMode::Single => PlaybackMode::Repeat,
-        }
-    }
-}
-
 /// `PlaybackModeTui` 是一个 TUI 组件，用于渲染播放模式列表。
 ///
 /// 它会高亮显示当前的播放模式。
@@ -61,6 +26,10 @@ pub struct PlaybackModeTui {
     mode: PlaybackMode,
     /// 组件的 TUI 样式。
     style: TuiStyle,
+    /// 非激活模式的样式。
+    inactive: Style,
+    /// 模式之间分隔符的样式。
+    separator: Style,
 }
 
 impl Default for PlaybackModeTui {
@@ -70,10 +39,14 @@ impl Default for PlaybackModeTui {
         let mut style = TuiStyle::default();
         // 默认文本右对齐
         style.set_alignment(Alignment::Right);
-        Self {
+        let mut tui = Self {
             mode: Default::default(),
             style,
-        }
+            inactive: Style::default(),
+            separator: Style::default(),
+        };
+        tui.apply_theme(&TuiTheme::current());
+        tui
     }
 }
 
@@ -88,12 +61,19 @@ impl RenderTui for PlaybackModeTui {
 
         frame.render_widget(widget, rect);
     }
+
+    /// 用主题的颜色重新设置样式，非激活的模式使用较暗的前景色。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_tui_theme(theme);
+        self.inactive = Style::default().fg(theme.fg_dark);
+        self.separator = Style::default().fg(theme.fg);
+    }
 }
 
 impl PlaybackModeTui {
     /// 根据当前播放模式，构建一个高亮显示当前模式的 `Line`。
     fn build_mode_line(&self) -> Line<'_> {
-        let variants = PlaybackMode::variants();
+        let variants = PlaybackMode::VARIANTS;
         let mut spans = Vec::new();
         for (i, mode) in variants.iter().enumerate() {
             // 判断是否是当前激活的模式
@@ -101,31 +81,27 @@ impl PlaybackModeTui {
                 // 激活模式使用默认前景色（通常更亮）
                 self.tui_style()
             } else {
-                // 非激活模式使用灰色，以示区别
-                Style::default().fg(Color::Gray)
+                // 非激活模式使用较暗的颜色，以示区别
+                self.inactive
             };
 
             spans.push(Span::styled(format!("{:?}", mode), style));
 
             // 在模式之间添加分隔符，除了最后一个
             if i < variants.len() - 1 {
-                spans.push(Span::raw(" | ").fg(Color::White));
+                spans.push(Span::styled(" | ", self.separator));
             }
         }
         Line::from(spans)
     }
 
-    /// 切换到下一个播放模式。
-    pub(crate) fn toggle_mode(&mut self) {
-        self.mode = self.mode.next();
-    }
-
     /// 设置指定的播放模式。
     pub(crate) fn set_mode(&mut self, mode: PlaybackMode) {
         self.mode = mode;
     }
 
     /// 获取当前的播放模式。
+    #[cfg(test)]
     pub(crate) fn mode(&self) -> PlaybackMode {
         self.mode
     }
@@ -136,14 +112,6 @@ mod tests {
     use super::*;
     use ratatui::{Terminal, backend::TestBackend};
 
-    #[test]
-    fn test_playback_mode_next() {
-        assert_eq!(PlaybackMode::Repeat.next(), PlaybackMode::Random);
-        assert_eq!(PlaybackMode::Random.next(), PlaybackMode::Consume);
-        assert_eq!(PlaybackMode::Consume.next(), PlaybackMode::Single);
-        assert_eq!(PlaybackMode::Single.next(), PlaybackMode::Repeat);
-    }
-
     #[test]
     fn test_playback_mode_tui_default() {
         let pbm_tui = PlaybackModeTui::default();
@@ -151,23 +119,6 @@ mod tests {
         assert_eq!(pbm_tui.tui_alignment(), Alignment::Right);
     }
 
-    #[test]
-    fn test_playback_mode_tui_toggle_mode() {
-        let mut pbm_tui = PlaybackModeTui::default(); // Starts at Repeat
-
-        pbm_tui.toggle_mode();
-        assert_eq!(pbm_tui.mode(), PlaybackMode::Random);
-
-        pbm_tui.toggle_mode();
-        assert_eq!(pbm_tui.mode(), PlaybackMode::Consume);
-
-        pbm_tui.toggle_mode();
-        assert_eq!(pbm_tui.mode(), PlaybackMode::Single);
-
-        pbm_tui.toggle_mode();
-        assert_eq!(pbm_tui.mode(), PlaybackMode::Repeat); // Cycles back
-    }
-
     #[test]
     fn test_playback_mode_tui_set_mode() {
         let mut pbm_tui = PlaybackModeTui::default();
@@ -193,7 +144,7 @@ mod tests {
 
     #[test]
     fn test_playback_mode_tui_build_mode_line_highlighting() {
-        let modes = PlaybackMode::variants();
+        let modes = PlaybackMode::VARIANTS;
         for &active_mode in modes {
             let mut pbm_tui = PlaybackModeTui::default();
             pbm_tui.set_mode(active_mode);
@@ -222,8 +173,8 @@ mod tests {
                 } else {
                     assert_eq!(
                         spans[i * 2].style.fg,
-                        Some(Color::Gray),
-                        "Inactive mode {:?} should have gray foreground color",
+                        pbm_tui.inactive.fg,
+                        "Inactive mode {:?} should have dimmed foreground color",
                         mode
                     );
                 }
@@ -237,12 +188,11 @@ mod tests {
                     );
                     assert_eq!(
                         spans[i * 2 + 1].style.fg,
-                        Some(Color::White),
-                        "Separator should be white"
+                        pbm_tui.separator.fg,
+                        "Separator should use the separator color"
                     );
                 }
             }
         }
     }
 }
-
@@ -1,7 +1,11 @@
 //! `PlaybackProgressTui` 模块，用于在 TUI 中显示播放进度。
 
 use crate::traits::RenderTui;
-use lazy_core::{structs::TuiStyle, traits::HasTuiStyle};
+use lazy_core::{
+    structs::TuiStyle,
+    theme::TuiTheme,
+    traits::{HasTuiStyle, HasTuiStyleSetter},
+};
 use lazy_macro::DeriveHasTuiStyle;
 use ratatui::{
     Frame,
@@ -21,6 +25,8 @@ pub struct PlaybackProgressTui {
     progress: Duration,
     /// 当前曲目的总时长。
     duration: Duration,
+    /// 分隔符的颜色。
+    separator: Color,
 }
 
 impl Default for PlaybackProgressTui {
@@ -30,11 +36,14 @@ impl Default for PlaybackProgressTui {
         let mut style = TuiStyle::default();
         // 默认文本左对齐
         style.set_alignment(Alignment::Left);
-        Self {
+        let mut tui = Self {
             style,
             progress: Duration::ZERO,
             duration: Duration::ZERO,
-        }
+            separator: Color::Reset,
+        };
+        tui.apply_theme(&TuiTheme::current());
+        tui
     }
 }
 
@@ -47,7 +56,7 @@ impl RenderTui for PlaybackProgressTui {
             // 第一个片段：当前进度
             Span::raw(Self::format_duration(self.progress)).fg(self.style.fg()),
             // 第二个片段：分隔符
-            Span::raw(" / ").fg(Color::White),
+            Span::raw(" / ").fg(self.separator),
             // 第三个片段：总时长
             Span::raw(Self::format_duration(self.duration)).fg(self.style.fg()),
         ]);
@@ -60,6 +69,12 @@ impl RenderTui for PlaybackProgressTui {
         // 在指定区域渲染小部件
         frame.render_widget(widget, rect);
     }
+
+    /// 用主题的颜色重新设置样式，分隔符使用普通前景色。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_tui_theme(theme);
+        self.separator = theme.fg;
+    }
 }
 
 impl PlaybackProgressTui {
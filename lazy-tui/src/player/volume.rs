@@ -1,4 +1,10 @@
-use lazy_core::{structs::TuiStyle, traits::HasTuiStyle};
+use std::cell::Cell;
+
+use lazy_core::{
+    structs::TuiStyle,
+    theme::TuiTheme,
+    traits::{HasTuiStyle, HasTuiStyleSetter},
+};
 use lazy_macro::DeriveHasTuiStyle;
 use ratatui::{
     Frame,
@@ -6,7 +12,10 @@ use ratatui::{
     widgets::Paragraph,
 };
 
-use crate::traits::RenderTui;
+use crate::{
+    traits::RenderTui,
+    types::{Direction, EventResult, Mouse, MouseKind, TuiAction},
+};
 
 /// 用于在 TUI 界面显示音量信息的组件
 #[derive(DeriveHasTuiStyle)]
@@ -15,18 +24,28 @@ pub struct VolumeTui {
     volume: u8,
     /// TUI 样式
     style: TuiStyle,
+    /// 上一次渲染时所在的区域，用于鼠标滚动
+    area: Cell<Rect>,
+    /// 等待应用程序执行的操作
+    actions: Vec<TuiAction>,
 }
 
 impl Default for VolumeTui {
     fn default() -> Self {
         let mut style = TuiStyle::default();
         style.set_alignment(Alignment::Right);
-        Self { style, volume: 50 }
+        Self {
+            style,
+            volume: 50,
+            area: Cell::new(Rect::default()),
+            actions: Vec::new(),
+        }
     }
 }
 
 impl RenderTui for VolumeTui {
     fn render(&self, frame: &mut Frame, rect: Rect) {
+        self.area.set(rect);
         // 根据当前音量自动选择图标
         let status_icon = Self::pick_icon(self.volume, &Self::VOLUME_STATUS);
         let bar_icon = Self::pick_icon(self.volume, &Self::ICONS_BLOCK);
@@ -38,6 +57,11 @@ impl RenderTui for VolumeTui {
             .alignment(self.tui_alignment());
         frame.render_widget(volume, rect);
     }
+
+    /// 用主题的颜色重新设置音量的样式。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_tui_theme(theme);
+    }
 }
 
 impl VolumeTui {
@@ -56,6 +80,9 @@ impl VolumeTui {
 
     const MAX_VOLUME: u8 = 100;
 
+    /// 在音量上滚动一格滚轮或播放器获得焦点时按一次上下方向键，音量的变化量
+    const STEP: i8 = 5;
+
     /// 根据音量值从数组中选取对应图标
     fn pick_icon<'a>(volume: u8, icons: &'a [&'a str]) -> &'a str {
         let len = icons.len();
@@ -67,6 +94,7 @@ impl VolumeTui {
     }
 
     /// 直接设置音量值
+    #[cfg(test)]
     pub(crate) fn set_volume(&mut self, volume: u8) {
         self.volume = volume.min(Self::MAX_VOLUME);
     }
@@ -77,7 +105,39 @@ impl VolumeTui {
         self.volume = new.clamp(0, Self::MAX_VOLUME as i16) as u8;
     }
 
+    /// 在音量上向上滚动滚轮时请求应用程序增大音量，向下滚动时减小音量。
+    ///
+    /// 只处理音量上的滚轮事件，其他鼠标事件交给其他组件。
+    pub(crate) fn mouse(&mut self, mouse: Mouse) -> EventResult {
+        if !mouse.is_in(self.area.get()) {
+            return EventResult::Ignored;
+        }
+        match mouse.kind {
+            MouseKind::ScrollUp => self.actions.push(TuiAction::Volume(Self::STEP)),
+            MouseKind::ScrollDown => self.actions.push(TuiAction::Volume(-Self::STEP)),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Handled
+    }
+
+    /// 播放器获得焦点时，向上选择请求增大音量，向下选择请求减小音量。
+    pub(crate) fn pick(&mut self, direction: Direction) -> EventResult {
+        let delta = match direction {
+            Direction::Up => Self::STEP,
+            Direction::Down => -Self::STEP,
+            Direction::Left | Direction::Right => return EventResult::Ignored,
+        };
+        self.actions.push(TuiAction::Volume(delta));
+        EventResult::Handled
+    }
+
+    /// 取出等待应用程序执行的操作。
+    pub(crate) fn take_actions(&mut self) -> Vec<TuiAction> {
+        std::mem::take(&mut self.actions)
+    }
+
     /// 获取当前音量值
+    #[cfg(test)]
     pub(crate) fn volume(&self) -> u8 {
         self.volume
     }
@@ -139,6 +199,41 @@ mod test {
         assert_eq!(volume.volume(), 80);
     }
 
+    #[test]
+    fn test_volume_tui_scroll() {
+        let mut volume = VolumeTui::default();
+        let backend = TestBackend::new(40, 1);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| volume.render(f, f.area())).unwrap();
+        let scroll = |kind, row| Mouse {
+            kind,
+            column: 30,
+            row,
+        };
+
+        volume.mouse(scroll(MouseKind::ScrollUp, 0));
+        volume.mouse(scroll(MouseKind::ScrollDown, 0));
+        volume.mouse(scroll(MouseKind::Click, 0));
+        volume.mouse(scroll(MouseKind::ScrollUp, 3));
+        assert_eq!(
+            volume.take_actions(),
+            vec![TuiAction::Volume(5), TuiAction::Volume(-5)]
+        );
+        assert_eq!(volume.volume(), 50, "音量由应用程序调整");
+    }
+
+    #[test]
+    fn test_volume_tui_pick() {
+        let mut volume = VolumeTui::default();
+        assert_eq!(volume.pick(Direction::Up), EventResult::Handled);
+        assert_eq!(volume.pick(Direction::Down), EventResult::Handled);
+        assert_eq!(volume.pick(Direction::Left), EventResult::Ignored);
+        assert_eq!(
+            volume.take_actions(),
+            vec![TuiAction::Volume(5), TuiAction::Volume(-5)]
+        );
+    }
+
     #[test]
     fn test_pick_icon_logic() {
         // 测试 ICONS_BLOCK (6个图标) 的边界情况
@@ -3,18 +3,24 @@
 //! `ProgressTui` 结构体负责渲染一个带有圆角的进度条。
 //! 它使用 `ratatui` 库中的 `Gauge` 小部件来显示进度。
 
+use std::{cell::Cell, time::Duration};
+
 use lazy_core::{
     structs::{BorderStyle, TitleStyle, TuiStyle},
-    traits::{HasBorderStyleSetter, HasTuiStyle},
+    theme::TuiTheme,
+    traits::{HasBorderStyleSetter, HasTitleStyleSetter, HasTuiStyle, HasTuiStyleSetter},
 };
 use lazy_macro::DeriveHasTuiStyle;
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
-    style::{Color, Stylize},
+    style::Stylize,
     widgets::{Gauge, Paragraph},
 };
 
-use crate::traits::{RenderTui, TuiBlock};
+use crate::{
+    traits::{RenderTui, TuiBlock, TuiEventHandle},
+    types::{Direction, EventResult, Mouse, MouseKind, TuiAction, TuiEnent},
+};
 
 /// 一个用于显示进度条的 TUI 组件。
 ///
@@ -26,20 +32,28 @@ pub struct ProgressTui {
     border: BorderStyle, // 边框样式
     style: TuiStyle,     // 通用样式（颜色、对齐等）
     ratio: f64,
+    duration: Duration,      // 当前曲目的总时长，用于跳转时计算比率
+    area: Cell<Rect>,        // 上一次渲染时进度条（包括两端的圆角）所在的区域，用于鼠标点击
+    bar: Cell<Rect>,         // 上一次渲染时计量器所在的区域，点击位置按它换算为播放位置
+    actions: Vec<TuiAction>, // 等待应用程序执行的操作
 }
 
 impl Default for ProgressTui {
     /// 创建一个具有默认样式的新的 `ProgressTui`。
     ///
     fn default() -> Self {
-        let mut style = TuiStyle::default();
-        style.set_bg(Color::Rgb(47, 51, 77));
-        Self {
+        let mut progress = Self {
             title: Default::default(),
-            style,
+            style: Default::default(),
             border: Default::default(),
             ratio: 0.0,
-        }
+            duration: Duration::ZERO,
+            area: Cell::new(Rect::default()),
+            bar: Cell::new(Rect::default()),
+            actions: Vec::new(),
+        };
+        progress.apply_theme(&TuiTheme::current());
+        progress
     }
 }
 
@@ -76,14 +90,54 @@ impl RenderTui for ProgressTui {
         ])
         .split(inner);
 
+        self.area.set(inner);
+        self.bar.set(row[1]);
         frame.render_widget(left_haft_circle, row[0]);
         frame.render_widget(bars, row[1]);
         frame.render_widget(right_haft_circle, row[2]);
     }
 
+    fn as_event(&self) -> Option<&dyn TuiEventHandle> {
+        Some(self)
+    }
+
+    fn as_event_mut(&mut self) -> Option<&mut dyn TuiEventHandle> {
+        Some(self)
+    }
+
     fn as_border_mut(&mut self) -> Option<&mut dyn HasBorderStyleSetter> {
         Some(self)
     }
+
+    /// 重新设置进度条的样式，未播放的部分使用高亮背景色。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_title_theme(theme);
+        self.apply_border_theme(theme);
+        self.apply_tui_theme(theme);
+        self.style.set_bg(theme.bg_highlight);
+    }
+}
+
+impl TuiEventHandle for ProgressTui {
+    /// 根据播放进度和跳转事件更新进度比率。
+    fn event_handle(&mut self, event: TuiEnent) -> EventResult {
+        match event {
+            TuiEnent::PlaybackProgress(progress, duration) => self.set_progress(progress, duration),
+            TuiEnent::Seek(position) => self.set_progress(position, self.duration),
+            TuiEnent::Mouse(mouse) => return self.mouse(mouse),
+            // 进度条获得焦点时用左右方向键快退、快进
+            TuiEnent::Picker(direction @ (Direction::Left | Direction::Right)) => {
+                self.actions.push(TuiAction::Seek(direction))
+            }
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Handled
+    }
+
+    /// 取出等待应用程序执行的操作。
+    fn take_actions(&mut self) -> Vec<TuiAction> {
+        std::mem::take(&mut self.actions)
+    }
 }
 
 impl ProgressTui {
@@ -95,6 +149,7 @@ impl ProgressTui {
     }
 
     /// 返回进度条的当前进度比率。
+    #[cfg(test)]
     pub(crate) fn ratio(&self) -> f64 {
         self.ratio
     }
@@ -102,12 +157,45 @@ impl ProgressTui {
     pub(crate) fn reset_ratio(&mut self) {
         self.ratio = 0.0;
     }
+
+    /// 点击或拖动进度条时请求应用程序跳转到对应的位置，总时长未知时忽略。
+    ///
+    /// 点击左右两端的圆角分别跳转到开头和结尾。
+    fn mouse(&mut self, mouse: Mouse) -> EventResult {
+        if !matches!(mouse.kind, MouseKind::Click | MouseKind::Drag)
+            || !mouse.is_in(self.area.get())
+            || self.duration.is_zero()
+        {
+            return EventResult::Ignored;
+        }
+        let bar = self.bar.get();
+        let ratio = if bar.width == 0 {
+            0.0
+        } else {
+            (f64::from(mouse.column) - f64::from(bar.x)) / f64::from(bar.width)
+        };
+        let position = self.duration.mul_f64(ratio.clamp(0.0, 1.0));
+        self.actions.push(TuiAction::SeekTo(position));
+        EventResult::Handled
+    }
+
+    /// 根据当前播放时间和总时长设置进度比率。
+    ///
+    /// 总时长未知（为零）时重置进度。
+    pub(crate) fn set_progress(&mut self, progress: Duration, duration: Duration) {
+        self.duration = duration;
+        if duration.is_zero() {
+            self.reset_ratio();
+        } else {
+            self.set_ratio(progress.as_secs_f64() / duration.as_secs_f64());
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ratatui::{Terminal, backend::TestBackend};
+    use ratatui::{Terminal, backend::TestBackend, style::Color};
 
     #[test]
     fn test_progress_tui_default() {
@@ -139,6 +227,80 @@ mod tests {
         assert_eq!(progress.ratio(), 0.0);
     }
 
+    #[test]
+    fn test_progress_tui_playback_progress_event() {
+        let mut progress = ProgressTui::default();
+        progress.event_handle(TuiEnent::PlaybackProgress(
+            Duration::from_secs(30),
+            Duration::from_secs(120),
+        ));
+        assert_eq!(progress.ratio(), 0.25);
+
+        // 跳转时沿用已知的总时长
+        progress.event_handle(TuiEnent::Seek(Duration::from_secs(60)));
+        assert_eq!(progress.ratio(), 0.5);
+
+        // 总时长未知时重置进度
+        progress.event_handle(TuiEnent::PlaybackProgress(
+            Duration::from_secs(30),
+            Duration::ZERO,
+        ));
+        assert_eq!(progress.ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_progress_tui_click_seeks() {
+        let backend = TestBackend::new(100, 3);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut progress = ProgressTui::default();
+        terminal.draw(|f| progress.render(f, f.area())).unwrap();
+        let bar = progress.bar.get();
+        let mouse = |kind, column| {
+            TuiEnent::Mouse(Mouse {
+                kind,
+                column,
+                row: bar.y,
+            })
+        };
+
+        // 总时长未知时不能跳转
+        progress.event_handle(mouse(MouseKind::Click, bar.x));
+        assert!(progress.take_actions().is_empty());
+
+        progress.event_handle(TuiEnent::PlaybackProgress(
+            Duration::ZERO,
+            Duration::from_secs(bar.width.into()),
+        ));
+        progress.event_handle(mouse(MouseKind::Click, bar.x + bar.width / 4));
+        progress.event_handle(mouse(MouseKind::Drag, bar.x + bar.width / 2));
+        progress.event_handle(mouse(MouseKind::Click, bar.x - 1));
+        progress.event_handle(mouse(MouseKind::ScrollUp, bar.x));
+        assert_eq!(
+            progress.take_actions(),
+            vec![
+                TuiAction::SeekTo(Duration::from_secs((bar.width / 4).into())),
+                TuiAction::SeekTo(Duration::from_secs((bar.width / 2).into())),
+                TuiAction::SeekTo(Duration::ZERO),
+            ]
+        );
+
+        // 获得焦点时用左右方向键快进和快退
+        progress.event_handle(TuiEnent::Picker(Direction::Right));
+        progress.event_handle(TuiEnent::Picker(Direction::Left));
+        assert!(
+            !progress
+                .event_handle(TuiEnent::Picker(Direction::Up))
+                .is_handled()
+        );
+        assert_eq!(
+            progress.take_actions(),
+            vec![
+                TuiAction::Seek(Direction::Right),
+                TuiAction::Seek(Direction::Left)
+            ]
+        );
+    }
+
     #[test]
     fn test_progress_tui_render_smoke_test() {
         let backend = TestBackend::new(100, 30);
@@ -194,4 +356,3 @@ mod tests {
         );
     }
 }
-
@@ -11,7 +11,8 @@ use ratatui::{
 // 从 lazy_core 中导入所需的结构体和 traits
 use lazy_core::{
     structs::{BorderStyle, TitleStyle, TuiStyle},
-    traits::HasBorderStyleSetter,
+    theme::TuiTheme,
+    traits::{HasBorderStyleSetter, HasTitleStyleSetter, HasTuiStyleSetter},
 };
 
 // 从当前 crate 中导入所需的组件和 traits
@@ -21,24 +22,32 @@ use crate::{
     progress::ProgressTui,
     router_view::RouterViewTui,
     traits::{HasWidgets, RenderTui, TuiBlock, TuiEventHandle},
-    types::TuiEnent, // RenderTui 用于渲染，TuiBlock 用于生成边框块
+    types::{Direction, EventResult, Focus, Mouse, MouseKind, TuiAction, TuiEnent}, // RenderTui 用于渲染，TuiBlock 用于生成边框块
 };
 
 /// `RootTui` 是根 TUI 组件，作为整个播放器界面的容器。
 ///
 /// 它包含了 `PlayerTui` 和 `ProgressTui`，并负责渲染整个界面。
+///
+/// 同一时间只有一个子组件获得焦点，边框使用高亮的颜色：键盘输入先交给它处理，
+/// 它不处理时再交给当前页面。焦点可以用 `TuiEnent::Focus` 循环切换，
+/// 先经过获得焦点的子组件内部的各个区域（例如媒体库页的各栏），再移到下一个子组件；
+/// 点击子组件时焦点也会移到这个组件上。
 #[derive(DeriveHasTuiStyle)]
 pub struct RootTui {
     title: TitleStyle,                // 根组件标题样式
     border: BorderStyle,              // 根组件边框样式
     style: TuiStyle,                  // 根组件通用样式（颜色、对齐等）
     widgets: Vec<Box<dyn RenderTui>>, // 包含的子组件
+    focused: usize,                   // 获得焦点的子组件在 `widgets` 中的索引
+    theme: TuiTheme,                  // 当前主题，焦点移动时用来恢复和高亮边框
+    dirty: bool,                      // 上次绘制之后界面是否发生了变化
 }
 
 impl Default for RootTui {
     /// 创建一个默认的 `RootTui` 实例。
     fn default() -> Self {
-        Self {
+        let mut root = Self {
             title: Default::default(),
             border: Default::default(),
             style: Default::default(),
@@ -49,7 +58,15 @@ impl Default for RootTui {
                 Box::new(RouterViewTui::default()),
                 Box::new(ProgressTui::default()),
             ],
-        }
+            focused: 0,
+            theme: TuiTheme::current(),
+            // 第一帧总是需要绘制
+            dirty: true,
+        };
+        // 默认由页面获得焦点
+        root.focused = root.page_index();
+        root.highlight_focus();
+        root
     }
 }
 
@@ -95,6 +112,157 @@ impl RootTui {
         }
     }
 
+    /// 返回获得焦点的位置：当前显示的页面，以及它是否正在输入文本。
+    pub fn focus(&self) -> Focus {
+        Focus {
+            view: self
+                .get_widget::<RouterViewTui>()
+                .map(RouterViewTui::active)
+                .unwrap_or_default(),
+            editing: self.is_editing(),
+        }
+    }
+
+    /// 标记界面需要重新绘制，例如终端大小改变时。
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// 返回上次绘制之后界面是否发生了变化。
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// 返回界面是否需要重新绘制，并清除标记。
+    ///
+    /// 应在绘制整个界面之前调用。
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// 返回页面容器 `RouterViewTui` 在 `widgets` 中的索引。
+    fn page_index(&self) -> usize {
+        self.widgets
+            .iter()
+            .position(|f| f.as_any().is::<RouterViewTui>())
+            .unwrap_or_default()
+    }
+
+    /// 把焦点移到下一个（`Right`）或上一个（`Left`）区域，到达末尾时回到开头。
+    ///
+    /// 获得焦点的子组件内部还有下一个区域时（例如媒体库页的下一栏）只在它内部移动，
+    /// 否则移到下一个能处理事件的子组件，并从它的第一个（或最后一个）区域开始。
+    fn move_focus(&mut self, direction: Direction) {
+        if matches!(direction, Direction::Up | Direction::Down) {
+            return;
+        }
+        if self
+            .widgets
+            .get_mut(self.focused)
+            .and_then(|f| f.as_event_mut())
+            .is_some_and(|f| f.shift_focus(direction))
+        {
+            return;
+        }
+
+        let focusable: Vec<usize> = (0..self.widgets.len())
+            .filter(|&i| self.widgets[i].as_event().is_some())
+            .collect();
+        let len = focusable.len();
+        let current = focusable
+            .iter()
+            .position(|&i| i == self.focused)
+            .unwrap_or_default();
+        let next = match direction {
+            Direction::Right => (current + 1) % len,
+            Direction::Left => (current + len - 1) % len,
+            Direction::Up | Direction::Down => return,
+        };
+        self.set_focused(focusable[next]);
+        if let Some(widget) = self.widgets[focusable[next]].as_event_mut() {
+            widget.enter_focus(direction);
+        }
+    }
+
+    /// 把焦点交给指定的子组件。
+    fn set_focused(&mut self, index: usize) {
+        if index == self.focused {
+            return;
+        }
+        // 用主题重新设置失去焦点的组件，恢复它原来的边框颜色
+        if let Some(widget) = self.widgets.get_mut(self.focused) {
+            widget.apply_theme(&self.theme);
+        }
+        self.focused = index;
+        self.highlight_focus();
+    }
+
+    /// 用主题的强调色设置获得焦点的子组件的边框。
+    fn highlight_focus(&mut self) {
+        let color = self.theme.orange;
+        if let Some(border) = self
+            .widgets
+            .get_mut(self.focused)
+            .and_then(|f| f.as_border_mut())
+        {
+            border.set_border_fg(color);
+        }
+    }
+
+    /// 把鼠标事件依次交给子组件，直到鼠标位置上的组件处理了它；被点击的组件获得焦点。
+    fn mouse(&mut self, mouse: Mouse) -> EventResult {
+        for index in 0..self.widgets.len() {
+            let Some(widget) = self.widgets[index].as_event_mut() else {
+                continue;
+            };
+            if widget.event_handle(TuiEnent::Mouse(mouse)).is_handled() {
+                if matches!(mouse.kind, MouseKind::Click | MouseKind::DoubleClick) {
+                    self.set_focused(index);
+                }
+                return EventResult::Handled;
+            }
+        }
+        EventResult::Ignored
+    }
+
+    /// 把事件交给子组件处理，返回处理结果。
+    fn dispatch(&mut self, event: TuiEnent) -> EventResult {
+        match event {
+            // 切换主题时由 `apply_theme` 遍历整个组件树，包括不处理事件的组件。
+            TuiEnent::Theme(theme) => {
+                self.apply_theme(&theme);
+                return EventResult::Handled;
+            }
+            TuiEnent::Focus(direction) => {
+                self.move_focus(direction);
+                return EventResult::Handled;
+            }
+            TuiEnent::Mouse(mouse) => return self.mouse(mouse),
+            _ => (),
+        }
+
+        if event.is_input() {
+            let result = self
+                .widgets
+                .get_mut(self.focused)
+                .and_then(|f| f.as_event_mut())
+                .map_or(EventResult::Ignored, |f| f.event_handle(event.clone()));
+            if result.is_handled() {
+                return result;
+            }
+            // 获得焦点的子组件不处理或要求父组件处理时，交给后备处理
+            return self.fallback_handle(event).merge(result);
+        }
+
+        // 通过 as_event_mut 动态地检查组件是否能处理事件。
+        self.widgets
+            .iter_mut()
+            .filter_map(|f| f.as_event_mut())
+            .fold(EventResult::Ignored, |result, f| {
+                result.merge(f.event_handle(event.clone()))
+            })
+    }
+
     /// 更新进度条组件的进度。
     ///
     /// # Arguments
@@ -143,24 +311,255 @@ impl RenderTui for RootTui {
     fn as_event_mut(&mut self) -> Option<&mut dyn TuiEventHandle> {
         Some(self)
     }
+
+    /// 重新设置根组件的样式，并转发给所有子组件。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.theme = *theme;
+        self.apply_title_theme(theme);
+        self.apply_border_theme(theme);
+        self.apply_tui_theme(theme);
+        self.widgets.iter_mut().for_each(|f| f.apply_theme(theme));
+        self.highlight_focus();
+    }
 }
 
 impl TuiEventHandle for RootTui {
     /// 处理 TUI 事件。
     ///
-    /// 根据事件类型，将事件委托给相应的子组件处理。
+    /// 键盘输入先交给获得焦点的子组件，它不处理时交给当前页面；鼠标事件交给鼠标位置上的子组件；
+    /// 其余事件广播给所有子组件，让它们自行处理。
     ///
     /// # Arguments
     ///
     /// * `event`: TUI 事件。
-    fn event_handle(&mut self, event: TuiEnent) {
-        // 将事件广播给所有子组件，让它们自行处理。
-        self.widgets.iter_mut().for_each(|f| {
-            // 通过 as_enent_mut 动态地检查组件是否能处理事件。
-            if let Some(tui_enent) = f.as_event_mut() {
-                // 如果可以，就调用其 enent_handle 方法。
-                tui_enent.event_handle(event.clone());
+    fn event_handle(&mut self, event: TuiEnent) -> EventResult {
+        // 组件只在处理事件时改变状态，没有组件处理的事件不需要重新绘制
+        let result = self.dispatch(event);
+        if result.is_handled() {
+            self.dirty = true;
+        }
+        result
+    }
+
+    /// 把获得焦点的子组件没有处理的输入事件交给当前页面；页面本身获得焦点时不再重复处理。
+    fn fallback_handle(&mut self, event: TuiEnent) -> EventResult {
+        let page = self.page_index();
+        if page == self.focused {
+            return EventResult::Ignored;
+        }
+        self.widgets[page]
+            .as_event_mut()
+            .map_or(EventResult::Ignored, |f| f.event_handle(event))
+    }
+
+    /// 任一子组件正在接收文本输入时返回 `true`。
+    fn is_editing(&self) -> bool {
+        self.widgets
+            .iter()
+            .filter_map(|f| f.as_event())
+            .any(|f| f.is_editing())
+    }
+
+    /// 收集所有子组件产生的操作。
+    fn take_actions(&mut self) -> Vec<TuiAction> {
+        self.widgets
+            .iter_mut()
+            .filter_map(|f| f.as_event_mut())
+            .flat_map(|f| f.take_actions())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{navbar::NavbarItem, types::TextInput};
+    use lazy_core::{
+        track::Track,
+        traits::{HasBorderStyle, HasTitleStyle, HasTuiStyle},
+    };
+    use lazy_library::index::Library;
+    use ratatui::style::Color;
+    use std::time::Duration;
+
+    #[test]
+    fn test_root_focus_follows_active_view() {
+        let mut root = RootTui::default();
+        assert_eq!(root.focus(), Focus::default());
+
+        // 搜索页在导航栏的最后，显示时默认开始输入
+        root.event_handle(TuiEnent::Navbar(Direction::Left));
+        assert_eq!(
+            root.focus(),
+            Focus {
+                view: NavbarItem::Search,
+                editing: true
             }
-        });
+        );
+        root.event_handle(TuiEnent::TextInput(TextInput::Cancel));
+        assert!(!root.focus().editing);
+    }
+
+    #[test]
+    fn test_root_theme_restyles_widgets() {
+        let mut root = RootTui::default();
+        let theme = TuiTheme {
+            bg: Color::Black,
+            blue: Color::LightBlue,
+            bg_highlight: Color::DarkGray,
+            ..TuiTheme::default()
+        };
+        root.event_handle(TuiEnent::Theme(theme));
+
+        assert_eq!(root.border_style().fg, Some(Color::LightBlue));
+        assert_eq!(root.title_style().bg, Some(Color::Black));
+        // 嵌套的容器和不处理事件的组件也会重新设置样式
+        let player = root.get_widget::<PlayerTui>().unwrap();
+        assert_eq!(player.tui_style().fg, Some(Color::LightBlue));
+        let progress = root.get_widget::<ProgressTui>().unwrap();
+        assert_eq!(progress.tui_style().bg, Some(Color::DarkGray));
+    }
+
+    #[test]
+    fn test_root_reports_unhandled_events() {
+        let mut root = RootTui::default();
+        // 播放器通过 `auto_delegate_events` 处理音量，没有映射的事件不处理
+        assert_eq!(root.event_handle(TuiEnent::Volume(5)), EventResult::Handled);
+        let player = root.get_widget_mut::<PlayerTui>().unwrap();
+        assert_eq!(player.event_handle(TuiEnent::Clear), EventResult::Ignored);
+
+        // 播放队列为空时清空队列没有效果
+        assert_eq!(root.event_handle(TuiEnent::Clear), EventResult::Ignored);
+        assert_eq!(
+            root.event_handle(TuiEnent::Picker(Direction::Down)),
+            EventResult::Handled
+        );
+        // 还没有渲染过，鼠标不在任何组件上
+        let click = Mouse {
+            kind: MouseKind::Click,
+            column: 1,
+            row: 1,
+        };
+        assert_eq!(
+            root.event_handle(TuiEnent::Mouse(click)),
+            EventResult::Ignored
+        );
+    }
+
+    #[test]
+    fn test_root_focus_ring() {
+        let mut root = RootTui::default();
+        let theme = TuiTheme::default();
+        root.event_handle(TuiEnent::Theme(theme));
+        // 默认由页面获得焦点，焦点所在组件的边框高亮
+        assert_eq!(root.focused, root.page_index());
+        let page = root.get_widget::<RouterViewTui>().unwrap();
+        assert_eq!(page.border_style().fg, Some(theme.orange));
+
+        // 进度条之后回到播放器，失去焦点的组件恢复原来的边框颜色
+        root.event_handle(TuiEnent::Focus(Direction::Right));
+        assert!(root.widgets[root.focused].as_any().is::<ProgressTui>());
+        root.event_handle(TuiEnent::Focus(Direction::Right));
+        assert!(root.widgets[root.focused].as_any().is::<PlayerTui>());
+        let page = root.get_widget::<RouterViewTui>().unwrap();
+        assert_eq!(page.border_style().fg, Some(theme.blue));
+        root.event_handle(TuiEnent::Focus(Direction::Right));
+        assert!(root.widgets[root.focused].as_any().is::<NavbarTui>());
+        let navbar = root.get_widget::<NavbarTui>().unwrap();
+        assert_eq!(navbar.border_style().fg, Some(theme.orange));
+
+        // 键盘输入先交给导航栏，导航栏不处理时交给当前页面
+        assert!(
+            root.event_handle(TuiEnent::Picker(Direction::Right))
+                .is_handled()
+        );
+        assert_eq!(
+            root.take_actions(),
+            vec![TuiAction::SelectView(NavbarItem::Queue.next())]
+        );
+        assert!(
+            root.event_handle(TuiEnent::Picker(Direction::Down))
+                .is_handled()
+        );
+        assert!(root.take_actions().is_empty());
+
+        root.event_handle(TuiEnent::Focus(Direction::Left));
+        assert!(root.widgets[root.focused].as_any().is::<PlayerTui>());
+    }
+
+    #[test]
+    fn test_root_focus_path_enters_panes() {
+        let mut root = RootTui::default();
+        let track = |path: &str, album: &str| Track {
+            artist: Some("A".to_string()),
+            album: Some(album.to_string()),
+            ..Track::new(path)
+        };
+        let library = Library::from_tracks([
+            track("/m/x/1.flac", "X"),
+            track("/m/x/2.flac", "X"),
+            track("/m/y/1.flac", "Y"),
+        ]);
+        root.event_handle(TuiEnent::SelectView(NavbarItem::Artists));
+        root.event_handle(TuiEnent::LibraryUpdated(&library));
+        // 播放的曲目数说明了焦点所在的栏：艺术家、专辑、曲目
+        let play = |root: &mut RootTui| {
+            root.event_handle(TuiEnent::PlaySelected);
+            match root.take_actions().as_slice() {
+                [TuiAction::Enqueue(paths, _)] => paths.len(),
+                actions => panic!("unexpected actions {actions:?}"),
+            }
+        };
+        assert_eq!(play(&mut root), 3);
+
+        // 焦点先经过页面的各栏，再移到进度条
+        root.event_handle(TuiEnent::Focus(Direction::Right));
+        assert_eq!(play(&mut root), 2);
+        root.event_handle(TuiEnent::Focus(Direction::Right));
+        assert_eq!(play(&mut root), 1);
+        assert_eq!(root.focused, root.page_index());
+        root.event_handle(TuiEnent::Focus(Direction::Right));
+        assert!(root.widgets[root.focused].as_any().is::<ProgressTui>());
+
+        // 反向移回页面时从最后一栏开始
+        root.event_handle(TuiEnent::Focus(Direction::Left));
+        assert_eq!(root.focused, root.page_index());
+        assert_eq!(play(&mut root), 1);
+        root.event_handle(TuiEnent::Focus(Direction::Left));
+        root.event_handle(TuiEnent::Focus(Direction::Left));
+        assert_eq!(play(&mut root), 3);
+        root.event_handle(TuiEnent::Focus(Direction::Left));
+        assert!(root.widgets[root.focused].as_any().is::<NavbarTui>());
+
+        // 正向移回页面时从第一栏开始
+        root.event_handle(TuiEnent::Focus(Direction::Right));
+        assert_eq!(play(&mut root), 3);
+    }
+
+    #[test]
+    fn test_root_dirty_tracking() {
+        let mut root = RootTui::default();
+        // 第一帧需要绘制，绘制之后没有变化就不再绘制
+        assert!(root.take_dirty());
+        assert!(!root.is_dirty());
+        assert!(!root.take_dirty());
+
+        root.event_handle(TuiEnent::PlaybackProgress(
+            Duration::from_secs(1),
+            Duration::from_secs(2),
+        ));
+        assert!(root.is_dirty());
+        assert!(root.take_dirty());
+        assert!(!root.is_dirty());
+
+        root.event_handle(TuiEnent::Theme(TuiTheme::default()));
+        assert!(root.take_dirty());
+
+        // 没有组件处理的事件不需要重新绘制
+        assert_eq!(root.event_handle(TuiEnent::Clear), EventResult::Ignored);
+        assert!(!root.is_dirty());
+
+        root.mark_dirty();
+        assert!(root.take_dirty());
     }
 }
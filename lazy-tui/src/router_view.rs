@@ -1,15 +1,25 @@
 //! 视图路由组件模块，定义了 TUI 的主内容区域，可根据状态切换不同的子视图。
 
-use lazy_core::structs::{BorderStyle, TitleStyle, TuiStyle};
+use lazy_core::{
+    structs::{BorderStyle, TitleStyle, TuiStyle},
+    theme::TuiTheme,
+    traits::{HasBorderStyleSetter, HasTitleStyleSetter, HasTuiStyleSetter},
+};
 use lazy_macro::DeriveHasTuiStyle;
 use ratatui::{Frame, layout::Rect};
 
 use crate::{
+    navbar::NavbarItem,
     traits::{HasWidgets, RenderTui, TuiBlock, TuiEventHandle},
-    types::TuiEnent,
+    types::{Direction, EventResult, TuiAction, TuiEnent},
+    views::{DirectoriesTui, Grouping, LibraryTui, LogsTui, PlaylistsTui, QueueTui, SearchTui},
 };
 
 /// `RouterViewTui` 是一个多功能视图容器，扮演“视图路由”的角色。
+///
+/// 它为每个 `NavbarItem` 持有一个子视图（顺序与 `NavbarItem::VARIANTS` 一致），
+/// 只渲染当前激活的视图，并在收到 `TuiEnent::Navbar` 时与导航栏同步切换。
+/// 切换时子视图不会被重新创建，因此滚动位置和选中项等状态会被保留。
 #[derive(DeriveHasTuiStyle)]
 pub struct RouterViewTui {
     /// 组件的标题样式。
@@ -20,6 +30,8 @@ pub struct RouterViewTui {
     style: TuiStyle,
     /// 包含的所有可切换的子组件（视图）。
     widgets: Vec<Box<dyn RenderTui>>,
+    /// 当前激活的导航项。
+    active: NavbarItem,
 }
 
 impl Default for RouterViewTui {
@@ -29,9 +41,58 @@ impl Default for RouterViewTui {
             title: Default::default(),
             border: Default::default(),
             style: Default::default(),
-            widgets: vec![],
+            widgets: NavbarItem::VARIANTS
+                .iter()
+                .map(|&item| Self::view_for(item))
+                .collect(),
+            active: Default::default(),
+        }
+    }
+}
+
+impl RouterViewTui {
+    /// 创建导航项对应的视图。
+    fn view_for(item: NavbarItem) -> Box<dyn RenderTui> {
+        match item {
+            NavbarItem::Queue => Box::new(QueueTui::default()),
+            NavbarItem::Logs => Box::new(LogsTui::default()),
+            NavbarItem::Directories => Box::new(DirectoriesTui::default()),
+            NavbarItem::Artists => Box::new(LibraryTui::new(Grouping::Artist)),
+            NavbarItem::AlbumArtists => Box::new(LibraryTui::new(Grouping::AlbumArtist)),
+            NavbarItem::Albums => Box::new(LibraryTui::new(Grouping::Album)),
+            NavbarItem::Playlists => Box::new(PlaylistsTui::default()),
+            NavbarItem::Search => Box::new(SearchTui::default()),
+        }
+    }
+
+    /// 根据方向切换激活的视图，与 `NavbarTui::toggle_navbar` 保持一致。
+    fn switch(&mut self, direction: Direction) {
+        match direction {
+            Direction::Left => self.active = self.active.prev(),
+            Direction::Right => self.active = self.active.next(),
+            // 导航栏是水平的，忽略上下方向
+            Direction::Up | Direction::Down => (),
         }
     }
+
+    /// 返回当前激活的导航项。
+    pub(crate) fn active(&self) -> NavbarItem {
+        self.active
+    }
+
+    /// 返回当前激活视图在 `widgets` 中的索引。
+    fn active_index(&self) -> usize {
+        NavbarItem::VARIANTS
+            .iter()
+            .position(|&item| item == self.active)
+            .unwrap_or(0)
+    }
+
+    /// 获取当前激活的视图。
+    fn active_view_mut(&mut self) -> Option<&mut Box<dyn RenderTui>> {
+        let index = self.active_index();
+        self.widgets.get_mut(index)
+    }
 }
 
 /// 为 `RouterViewTui` 实现 `RenderTui` trait，使其能够被渲染。
@@ -43,7 +104,10 @@ impl RenderTui for RouterViewTui {
         // 渲染根组件边框和标题
         frame.render_widget(self.to_block(), rect);
 
-        // TODO: 在此实现活动子组件的渲染逻辑
+        // 只渲染当前激活的子组件
+        if let Some(view) = self.widgets.get(self.active_index()) {
+            view.render(frame, inner);
+        }
     }
 
     fn as_event(&self) -> Option<&dyn crate::traits::TuiEventHandle> {
@@ -57,6 +121,14 @@ impl RenderTui for RouterViewTui {
     fn as_border_mut(&mut self) -> Option<&mut dyn lazy_core::traits::HasBorderStyleSetter> {
         Some(self)
     }
+
+    /// 重新设置容器的样式，并转发给所有视图（包括不可见的视图）。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_title_theme(theme);
+        self.apply_border_theme(theme);
+        self.apply_tui_theme(theme);
+        self.widgets.iter_mut().for_each(|f| f.apply_theme(theme));
+    }
 }
 
 /// 为 `RouterViewTui` 实现 `HasWidgets` trait，使其能够管理子组件。
@@ -73,5 +145,218 @@ impl HasWidgets for RouterViewTui {
 }
 
 impl TuiEventHandle for RouterViewTui {
-    fn event_handle(&mut self, event: TuiEnent) {}
+    /// 处理 TUI 事件。
+    ///
+    /// `Navbar` 和 `SelectView` 事件用于切换视图；输入事件（包括鼠标操作）只交给当前激活的视图；
+    /// 其余事件广播给所有视图，使不可见的视图也能保持最新状态。
+    /// 当前视图返回的 `Bubble` 原样返回给父组件。
+    fn event_handle(&mut self, event: TuiEnent) -> EventResult {
+        match event {
+            TuiEnent::Navbar(direction) => {
+                self.switch(direction);
+                return EventResult::Handled;
+            }
+            TuiEnent::SelectView(item) => {
+                self.active = item;
+                return EventResult::Handled;
+            }
+            _ => (),
+        }
+
+        if event.is_input() {
+            return self
+                .active_view_mut()
+                .and_then(|f| f.as_event_mut())
+                .map_or(EventResult::Ignored, |view| view.event_handle(event));
+        }
+
+        self.widgets
+            .iter_mut()
+            .filter_map(|f| f.as_event_mut())
+            .fold(EventResult::Ignored, |result, view| {
+                result.merge(view.event_handle(event.clone()))
+            })
+    }
+
+    /// 在当前激活的视图内部移动焦点。
+    fn shift_focus(&mut self, direction: Direction) -> bool {
+        self.active_view_mut()
+            .and_then(|f| f.as_event_mut())
+            .is_some_and(|view| view.shift_focus(direction))
+    }
+
+    /// 把移入的焦点交给当前激活的视图。
+    fn enter_focus(&mut self, direction: Direction) {
+        if let Some(view) = self.active_view_mut().and_then(|f| f.as_event_mut()) {
+            view.enter_focus(direction);
+        }
+    }
+
+    /// 当前激活的视图正在接收文本输入时返回 `true`。
+    fn is_editing(&self) -> bool {
+        self.widgets
+            .get(self.active_index())
+            .and_then(|f| f.as_event())
+            .is_some_and(|f| f.is_editing())
+    }
+
+    /// 收集所有视图产生的操作。
+    fn take_actions(&mut self) -> Vec<TuiAction> {
+        self.widgets
+            .iter_mut()
+            .filter_map(|f| f.as_event_mut())
+            .flat_map(|f| f.take_actions())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lazy_library::index::Library;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    /// 记录收到的选择事件的测试视图。
+    #[derive(Default)]
+    struct CountingView {
+        /// 当前选中项
+        selected: usize,
+        /// 收到的媒体库更新次数
+        updates: usize,
+    }
+
+    impl RenderTui for CountingView {
+        fn render(&self, _frame: &mut Frame, _rect: Rect) {}
+
+        fn as_event_mut(&mut self) -> Option<&mut dyn TuiEventHandle> {
+            Some(self)
+        }
+    }
+
+    impl TuiEventHandle for CountingView {
+        fn event_handle(&mut self, event: TuiEnent) -> EventResult {
+            match event {
+                TuiEnent::Picker(Direction::Down) => self.selected += 1,
+                TuiEnent::LibraryUpdated(_) => self.updates += 1,
+                // 已经在最上面，交给父组件处理
+                TuiEnent::Picker(Direction::Up) => return EventResult::Bubble,
+                _ => return EventResult::Ignored,
+            }
+            EventResult::Handled
+        }
+    }
+
+    /// 创建一个所有视图都是 `CountingView` 的路由。
+    fn counting_router() -> RouterViewTui {
+        RouterViewTui {
+            widgets: NavbarItem::VARIANTS
+                .iter()
+                .map(|_| Box::new(CountingView::default()) as Box<dyn RenderTui>)
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// 获取指定索引的测试视图。
+    fn view(router: &RouterViewTui, index: usize) -> &CountingView {
+        router.widgets[index]
+            .as_any()
+            .downcast_ref::<CountingView>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_router_view_has_one_view_per_navbar_item() {
+        let router = RouterViewTui::default();
+        assert_eq!(router.widgets.len(), NavbarItem::VARIANTS.len());
+        assert_eq!(router.active, NavbarItem::Queue);
+        assert!(router.widgets[0].as_any().is::<QueueTui>());
+        assert!(router.widgets[1].as_any().is::<LogsTui>());
+        assert!(router.widgets[2].as_any().is::<DirectoriesTui>());
+        let groupings: Vec<_> = router.widgets[3..6]
+            .iter()
+            .map(|w| w.as_any().downcast_ref::<LibraryTui>().unwrap().grouping())
+            .collect();
+        assert_eq!(
+            groupings,
+            [Grouping::Artist, Grouping::AlbumArtist, Grouping::Album]
+        );
+        assert!(router.widgets[6].as_any().is::<PlaylistsTui>());
+        assert!(router.widgets[7].as_any().is::<SearchTui>());
+    }
+
+    #[test]
+    fn test_router_view_switches_with_navbar() {
+        let mut router = RouterViewTui::default();
+        router.event_handle(TuiEnent::Navbar(Direction::Right));
+        assert_eq!(router.active, NavbarItem::Logs);
+        router.event_handle(TuiEnent::Navbar(Direction::Left));
+        router.event_handle(TuiEnent::Navbar(Direction::Left));
+        assert_eq!(router.active, NavbarItem::Search);
+        router.event_handle(TuiEnent::Navbar(Direction::Up));
+        assert_eq!(router.active, NavbarItem::Search);
+        router.event_handle(TuiEnent::SelectView(NavbarItem::Albums));
+        assert_eq!(router.active, NavbarItem::Albums);
+    }
+
+    #[test]
+    fn test_router_view_routes_input_to_active_view() {
+        let mut router = counting_router();
+        router.event_handle(TuiEnent::Picker(Direction::Down));
+        router.event_handle(TuiEnent::Picker(Direction::Down));
+
+        // 切换到下一页再选择，然后切回来，原页面的状态保持不变
+        router.event_handle(TuiEnent::Navbar(Direction::Right));
+        router.event_handle(TuiEnent::Picker(Direction::Down));
+        router.event_handle(TuiEnent::Navbar(Direction::Left));
+
+        assert_eq!(view(&router, 0).selected, 2);
+        assert_eq!(view(&router, 1).selected, 1);
+        assert_eq!(view(&router, 2).selected, 0);
+    }
+
+    #[test]
+    fn test_router_view_reports_active_view_result() {
+        let mut router = counting_router();
+        assert_eq!(
+            router.event_handle(TuiEnent::Picker(Direction::Down)),
+            EventResult::Handled
+        );
+        assert_eq!(
+            router.event_handle(TuiEnent::Picker(Direction::Up)),
+            EventResult::Bubble
+        );
+        assert_eq!(router.event_handle(TuiEnent::Clear), EventResult::Ignored);
+        assert_eq!(
+            router.event_handle(TuiEnent::LibraryUpdated(&Library::default())),
+            EventResult::Handled
+        );
+        // 只有当前视图收到了输入事件
+        assert_eq!(view(&router, 0).selected, 1);
+        assert_eq!(view(&router, 1).selected, 0);
+    }
+
+    #[test]
+    fn test_router_view_broadcasts_other_events() {
+        let mut router = counting_router();
+        router.event_handle(TuiEnent::LibraryUpdated(&Library::default()));
+        for index in 0..NavbarItem::VARIANTS.len() {
+            assert_eq!(view(&router, index).updates, 1);
+        }
+    }
+
+    #[test]
+    fn test_router_view_renders_active_view() {
+        let backend = TestBackend::new(40, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut router = RouterViewTui::default();
+        router.event_handle(TuiEnent::Navbar(Direction::Right));
+
+        terminal.draw(|f| router.render(f, f.area())).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let content: String = buffer.content().iter().map(|c| c.symbol()).collect();
+        assert!(content.contains("Logs"));
+        assert!(!content.contains("Queue"));
+    }
 }
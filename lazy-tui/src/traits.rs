@@ -1,10 +1,13 @@
 //! 该模块定义了 TUI 组件的 traits。
 
-use lazy_core::traits::{HasBorderStyle, HasBorderStyleSetter, HasTitleStyle, HasTuiStyle};
+use lazy_core::{
+    theme::TuiTheme,
+    traits::{HasBorderStyle, HasBorderStyleSetter, HasTitleStyle, HasTuiStyle},
+};
 use ratatui::{Frame, layout::Rect, widgets::Block};
 use std::any::Any;
 
-use crate::types::TuiEnent;
+use crate::types::{Direction, EventResult, TuiAction, TuiEnent};
 
 /// 可渲染为 `ratatui` `Block` 的组件的 trait。
 pub trait TuiBlock: HasTitleStyle + HasBorderStyle + HasTuiStyle {
@@ -77,6 +80,12 @@ pub trait RenderTui: Downcast {
     fn as_border_mut(&mut self) -> Option<&mut dyn HasBorderStyleSetter> {
         None
     }
+
+    /// 用主题的颜色重新设置组件的样式。
+    ///
+    /// 收到 `TuiEnent::Theme` 时由 `RootTui` 调用。默认不做任何事，
+    /// 有样式的组件应重写此方法，包含子组件的容器应转发给所有子组件。
+    fn apply_theme(&mut self, _theme: &TuiTheme) {}
 }
 
 /// 包含其他小部件的组件的 trait。
@@ -102,5 +111,45 @@ pub trait HasWidgets {
 }
 
 pub trait TuiEventHandle {
-    fn event_handle(&mut self, event: TuiEnent);
+    /// 处理事件，返回事件是否被处理。
+    ///
+    /// 容器把输入事件依次交给子组件，第一个返回 `Handled` 的子组件之后不再传递；
+    /// 子组件返回 `Bubble` 时，容器调用自己的 `fallback_handle`，或者把 `Bubble` 继续返回给父组件。
+    fn event_handle(&mut self, event: TuiEnent) -> EventResult;
+
+    /// 处理子组件没有处理的事件。
+    ///
+    /// 默认不处理任何事件，需要后备处理的容器应重写此方法。
+    fn fallback_handle(&mut self, _event: TuiEnent) -> EventResult {
+        EventResult::Ignored
+    }
+
+    /// 把焦点移到组件内部的下一个（`Right`）或上一个（`Left`）区域，返回焦点是否移动了。
+    ///
+    /// 焦点是从根组件到最内层区域的一条路径：容器先让获得焦点的子组件移动，
+    /// 子组件已经在最后一个（或第一个）区域时返回 `false`，容器再把焦点交给下一个子组件。
+    /// 默认返回 `false`，只有一个区域的组件不需要重写。
+    fn shift_focus(&mut self, _direction: Direction) -> bool {
+        false
+    }
+
+    /// 焦点从外部移入组件：`Right` 移到第一个区域，`Left` 移到最后一个区域。
+    ///
+    /// 默认不做任何事，有多个区域的组件和包含子组件的容器应重写此方法。
+    fn enter_focus(&mut self, _direction: Direction) {}
+
+    /// 组件是否正在接收文本输入。
+    ///
+    /// 为真时应用程序把字符键作为 `TuiEnent::TextInput` 发送，而不是按键绑定的命令。
+    /// 默认返回 `false`，包含子组件的容器应转发给可见的子组件。
+    fn is_editing(&self) -> bool {
+        false
+    }
+
+    /// 取出组件在处理事件时产生的 `TuiAction`。
+    ///
+    /// 默认没有任何操作，产生操作的组件和包含子组件的容器应重写此方法。
+    fn take_actions(&mut self) -> Vec<TuiAction> {
+        Vec::new()
+    }
 }
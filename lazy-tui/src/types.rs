@@ -1,4 +1,19 @@
-use std::{borrow::Cow, time::Duration};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use lazy_core::{
+    logger::LogRecord,
+    theme::TuiTheme,
+    track::Track,
+    types::{DirEntry, PlaybackMode, PlaybackState},
+};
+use lazy_library::{index::Library, playlist::Playlist, search::SearchResults};
+use ratatui::layout::{Position, Rect};
+
+use crate::navbar::NavbarItem;
 
 /// TUI 事件枚举
 ///
@@ -7,6 +22,8 @@ use std::{borrow::Cow, time::Duration};
 pub enum TuiEnent<'a> {
     /// 切换播放/暂停状态
     Playback,
+    /// 设置播放引擎报告的真实播放状态
+    PlaybackState(PlaybackState),
     /// 调整音量
     ///
     /// `i8` 表示音量变化的增量或绝对值。
@@ -15,23 +32,303 @@ pub enum TuiEnent<'a> {
     ///
     /// 第一个 `Duration` 是当前播放时间，第二个是总时长。
     PlaybackProgress(Duration, Duration),
-    /// 切换播放模式（如循环、随机等）
-    PlaybackMode,
+    /// 设置当前的播放模式（如循环、随机等）
+    PlaybackMode(PlaybackMode),
     /// 更新艺术家信息
     Artist(Cow<'a, str>),
     /// 更新曲目信息
     Track(Cow<'a, str>),
     /// 导航栏切换
     Navbar(Direction),
+    /// 切换到指定的页面（例如点击导航栏的标签）
+    SelectView(NavbarItem),
+    /// 把焦点移到下一个（`Right`）或上一个（`Left`）组件，有多栏的页面先在栏之间移动
+    Focus(Direction),
     /// 导航栏图标设置
     NavbarIcon(Cow<'a, str>, Cow<'a, str>),
+    /// 跳转到指定播放位置
+    Seek(Duration),
+    /// 切换到上一首（`Left`）或下一首（`Right`）
+    SkipTrack(Direction),
+    /// 在当前视图中向上（`Up`）或向下（`Down`）移动选中项；
+    /// 树形视图中 `Right` 展开选中项，`Left` 折叠选中项或跳到上一级
+    Picker(Direction),
+    /// 在树形视图中跳到选中项的上一级
+    Parent,
+    /// 播放当前视图中的选中项
+    PlaySelected,
+    /// 删除当前视图中的选中项
+    DeleteSelected,
+    /// 将当前视图中的选中项上移（`Up`）或下移（`Down`）
+    MoveSelected(Direction),
+    /// 清空当前视图的内容（例如清空播放队列）
+    Clear,
+    /// 将当前视图中的选中项添加到播放队列末尾
+    AddSelected,
+    /// 用当前视图中的选中项替换播放队列
+    ReplaceWithSelected,
+    /// 切换当前视图的排序方式
+    CycleSort,
+    /// 在当前视图中新建一项（例如新建播放列表）
+    Create,
+    /// 重命名当前视图中的选中项
+    Rename,
+    /// 将播放队列保存为播放列表
+    SaveQueue,
+    /// 开始输入搜索文本
+    Find,
+    /// 切换当前视图显示的最低日志级别
+    CycleLevel,
+    /// 切换当前视图是否跟随最新的内容
+    ToggleFollow,
+    /// 正在输入文本时的按键
+    TextInput(TextInput),
+    /// 鼠标操作，由位于鼠标位置的组件处理
+    Mouse(Mouse),
+    /// 播放队列发生变化（曲目增删、移动或当前曲目切换）
+    ///
+    /// 只发送变化的部分，页面据此更新自己的副本，不需要每次复制整个队列。
+    Queue(QueueChange<'a>),
+    /// 设置音乐库根目录
+    MusicRoots(Cow<'a, [PathBuf]>),
+    /// 目录的内容（已按显示顺序排序）
+    DirectoryListing(Cow<'a, Path>, Cow<'a, [DirEntry]>),
+    /// 媒体库内容发生变化（加载完成、扫描完成或音乐目录中的文件被添加、删除、修改）
+    ///
+    /// 页面只在处理事件时借用媒体库，从中汇总自己显示的分组和专辑，不保留媒体库的引用，
+    /// 因此应用程序可以直接修改媒体库而不需要复制曲目数据。
+    LibraryUpdated(&'a Library),
+    /// 媒体库中指定路径的曲目，路径是请求时的顺序，媒体库中没有的曲目会被跳过
+    LibraryTracks(Cow<'a, [PathBuf]>, Cow<'a, [Track]>),
+    /// 播放列表目录中的播放列表文件（已按名称排序）
+    Playlists(Cow<'a, [PathBuf]>),
+    /// 播放列表的内容，以及文件不存在的条目的索引
+    PlaylistLoaded(Cow<'a, Playlist>, Cow<'a, [usize]>),
+    /// 搜索结果，`SearchResults::query` 是对应的搜索文本
+    SearchResults(Cow<'a, SearchResults>),
+    /// 新记录的日志（按时间顺序）
+    Logs(Cow<'a, [LogRecord]>),
+    /// 切换主题，所有组件用主题的颜色重新设置自己的样式
+    Theme(TuiTheme),
+}
+
+impl TuiEnent<'_> {
+    /// 判断事件是否是用户对当前页面的输入操作。
+    ///
+    /// 输入事件只会交给当前显示的页面处理，其余事件会广播给所有页面，
+    /// 使不可见的页面也能保持最新状态。
+    pub fn is_input(&self) -> bool {
+        matches!(
+            self,
+            TuiEnent::Picker(_)
+                | TuiEnent::PlaySelected
+                | TuiEnent::DeleteSelected
+                | TuiEnent::MoveSelected(_)
+                | TuiEnent::Clear
+                | TuiEnent::Parent
+                | TuiEnent::AddSelected
+                | TuiEnent::ReplaceWithSelected
+                | TuiEnent::CycleSort
+                | TuiEnent::Create
+                | TuiEnent::Rename
+                | TuiEnent::SaveQueue
+                | TuiEnent::Find
+                | TuiEnent::CycleLevel
+                | TuiEnent::ToggleFollow
+                | TuiEnent::TextInput(_)
+                | TuiEnent::Mouse(_)
+        )
+    }
+}
+
+/// 播放队列的一处变化
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueueChange<'a> {
+    /// 在队尾添加了曲目
+    Appended(Cow<'a, [Track]>),
+    /// 移除了指定索引的曲目
+    Removed(usize),
+    /// 曲目从第一个索引移动到第二个索引，其余曲目依次顺移
+    Moved(usize, usize),
+    /// 指定索引的曲目信息发生了变化（例如媒体库重新读取了标签）
+    Updated(Cow<'a, [(usize, Track)]>),
+    /// 清空了队列
+    Cleared,
+    /// 当前播放的曲目变为指定索引，`None` 表示没有当前曲目
+    Current(Option<usize>),
+}
+
+/// 组件处理事件的结果
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EventResult {
+    /// 事件已被处理，容器不再把输入事件交给其他子组件
+    Handled,
+    /// 组件不处理这种事件
+    #[default]
+    Ignored,
+    /// 事件是发给这个组件的，但它在当前状态下无法处理，交给父组件的后备处理
+    Bubble,
+}
+
+impl EventResult {
+    /// 事件是否已被处理。
+    pub fn is_handled(self) -> bool {
+        self == EventResult::Handled
+    }
+
+    /// 合并多个子组件处理同一个事件的结果：任一子组件处理了事件即为已处理，
+    /// 其次是交给父组件，所有子组件都不处理时为不处理。
+    pub fn merge(self, other: EventResult) -> EventResult {
+        match (self, other) {
+            (EventResult::Handled, _) | (_, EventResult::Handled) => EventResult::Handled,
+            (EventResult::Bubble, _) | (_, EventResult::Bubble) => EventResult::Bubble,
+            _ => EventResult::Ignored,
+        }
+    }
+}
+
+impl From<()> for EventResult {
+    /// 不返回结果的处理方法总是处理了事件。
+    fn from(_: ()) -> Self {
+        EventResult::Handled
+    }
+}
+
+impl From<bool> for EventResult {
+    /// `true` 表示处理了事件，`false` 表示不处理。
+    fn from(handled: bool) -> Self {
+        if handled {
+            EventResult::Handled
+        } else {
+            EventResult::Ignored
+        }
+    }
 }
 
 /// 代表导航方向的枚举
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Direction {
     /// 向左
     Left,
     /// 向右
     Right,
+    /// 向上
+    Up,
+    /// 向下
+    Down,
+}
+
+/// TUI 请求应用程序执行的操作
+///
+/// 组件在处理事件时无法直接访问播放器，因此将需要执行的操作暂存起来，
+/// 由应用程序通过 `TuiEventHandle::take_actions` 统一取出并执行。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TuiAction {
+    /// 播放队列中指定索引的曲目
+    PlayQueueIndex(usize),
+    /// 从队列中移除指定索引的曲目
+    RemoveQueueIndex(usize),
+    /// 将队列中的曲目从第一个索引移动到第二个索引
+    MoveQueueIndex(usize, usize),
+    /// 清空播放队列
+    ClearQueue,
+    /// 读取目录的内容，结果通过 `TuiEnent::DirectoryListing` 返回
+    ListDirectory(PathBuf),
+    /// 从媒体库中读取指定路径的曲目，结果通过 `TuiEnent::LibraryTracks` 返回
+    LoadTracks(Vec<PathBuf>),
+    /// 将文件或目录（递归）加入播放队列
+    Enqueue(Vec<PathBuf>, EnqueueMode),
+    /// 读取播放列表，结果通过 `TuiEnent::PlaylistLoaded` 返回
+    LoadPlaylist(PathBuf),
+    /// 在播放列表目录中新建指定名称的空播放列表
+    CreatePlaylist(String),
+    /// 重命名播放列表
+    RenamePlaylist(PathBuf, String),
+    /// 删除播放列表
+    DeletePlaylist(PathBuf),
+    /// 保存修改后的播放列表
+    SavePlaylist(Playlist),
+    /// 将播放队列保存为指定名称的播放列表，同名的播放列表会被覆盖
+    SaveQueueAsPlaylist(String),
+    /// 在后台搜索媒体库，结果通过 `TuiEnent::SearchResults` 返回；文本为空时清空结果
+    Search(String),
+    /// 切换到指定的页面
+    SelectView(NavbarItem),
+    /// 跳转到当前曲目的指定位置
+    SeekTo(Duration),
+    /// 快进（`Right`）或快退（`Left`）一个步长
+    Seek(Direction),
+    /// 调整音量
+    Volume(i8),
+}
+
+/// 将曲目加入播放队列的方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnqueueMode {
+    /// 添加到队列末尾
+    Append,
+    /// 清空队列后添加，并从第一首开始播放
+    Replace,
+    /// 添加到队列末尾，并立即播放添加的第一首
+    Play,
+}
+
+/// 输入文本时的按键
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextInput {
+    /// 输入一个字符
+    Char(char),
+    /// 删除最后一个字符
+    Backspace,
+    /// 确认输入
+    Submit,
+    /// 取消输入
+    Cancel,
+}
+
+/// 鼠标操作的类型
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseKind {
+    /// 按下左键
+    Click,
+    /// 在同一位置快速按下两次左键，第一次按下时已经发送过 `Click`
+    DoubleClick,
+    /// 按住左键拖动
+    Drag,
+    /// 向上滚动滚轮
+    ScrollUp,
+    /// 向下滚动滚轮
+    ScrollDown,
+}
+
+/// 鼠标操作及其在终端中的位置
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Mouse {
+    /// 操作的类型
+    pub kind: MouseKind,
+    /// 所在的列
+    pub column: u16,
+    /// 所在的行
+    pub row: u16,
+}
+
+impl Mouse {
+    /// 返回鼠标所在的位置。
+    pub fn position(&self) -> Position {
+        Position::new(self.column, self.row)
+    }
+
+    /// 判断鼠标是否位于区域内。
+    pub fn is_in(&self, area: Rect) -> bool {
+        area.contains(self.position())
+    }
+}
+
+/// 获得焦点的位置，应用程序据此选择生效的按键映射层
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Focus {
+    /// 当前显示的页面
+    pub view: NavbarItem,
+    /// 页面是否正在输入文本
+    pub editing: bool,
 }
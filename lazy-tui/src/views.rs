@@ -0,0 +1,134 @@
+//! 页面模块，包含 `RouterViewTui` 中每个导航项对应的页面。
+
+use std::{cell::Cell, time::Duration};
+
+use ratatui::layout::Rect;
+
+use crate::types::{Direction, Mouse, MouseKind};
+
+// 导入子模块
+mod directories;
+mod library;
+mod logs;
+mod playlists;
+mod prompt;
+mod queue;
+mod search;
+
+pub(crate) use directories::DirectoriesTui;
+pub(crate) use library::{Grouping, LibraryTui};
+pub(crate) use logs::LogsTui;
+pub(crate) use playlists::PlaylistsTui;
+pub(crate) use prompt::{Prompt, PromptResult};
+pub(crate) use queue::QueueTui;
+pub(crate) use search::SearchTui;
+
+/// 根据可见行数调整列表的滚动位置，使选中项始终可见，返回调整后的滚动位置。
+///
+/// 页面在 `render` 中只有不可变引用，因此滚动位置保存在 `Cell` 中。
+pub(crate) fn scroll_offset(
+    offset: &Cell<usize>,
+    selected: usize,
+    len: usize,
+    height: usize,
+) -> usize {
+    let mut value = offset.get().min(len.saturating_sub(1));
+    if selected < value {
+        value = selected;
+    } else if height > 0 && selected >= value + height {
+        value = selected + 1 - height;
+    }
+    offset.set(value);
+    value
+}
+
+/// 鼠标在列表上的操作。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ListMouse {
+    /// 点击了一项，选中它
+    Select(usize),
+    /// 双击了一项，播放它
+    Play(usize),
+    /// 在列表上滚动滚轮，向上（`Up`）或向下（`Down`）移动选中项
+    Scroll(Direction),
+}
+
+/// 将鼠标操作转换为列表上的操作，鼠标不在列表上或点击了最后一项之后的空白处时返回 `None`。
+///
+/// `rows` 是上一次渲染时列表的行（不含表头）所在的区域，`offset` 是第一行可见项的索引。
+pub(crate) fn list_mouse(mouse: Mouse, rows: Rect, offset: usize, len: usize) -> Option<ListMouse> {
+    if !mouse.is_in(rows) {
+        return None;
+    }
+    let index = offset + (mouse.row - rows.y) as usize;
+    match mouse.kind {
+        MouseKind::Click if index < len => Some(ListMouse::Select(index)),
+        MouseKind::DoubleClick if index < len => Some(ListMouse::Play(index)),
+        MouseKind::ScrollUp => Some(ListMouse::Scroll(Direction::Up)),
+        MouseKind::ScrollDown => Some(ListMouse::Scroll(Direction::Down)),
+        _ => None,
+    }
+}
+
+/// 将时长格式化为 `MM:SS`，超过一小时时为 `H:MM:SS`。
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let total_seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (
+        total_seconds / 3600,
+        total_seconds / 60 % 60,
+        total_seconds % 60,
+    );
+    if hours > 0 {
+        format!("{hours}:{minutes:0>2}:{seconds:0>2}")
+    } else {
+        format!("{minutes:0>2}:{seconds:0>2}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::ZERO), "00:00");
+        assert_eq!(format_duration(Duration::from_secs(754)), "12:34");
+        assert_eq!(format_duration(Duration::from_secs(3600 + 5)), "1:00:05");
+    }
+
+    #[test]
+    fn test_scroll_offset() {
+        let offset = Cell::new(0);
+        assert_eq!(scroll_offset(&offset, 2, 10, 4), 0);
+        assert_eq!(scroll_offset(&offset, 6, 10, 4), 3);
+        assert_eq!(scroll_offset(&offset, 4, 10, 4), 3, "选中项可见时不滚动");
+        assert_eq!(scroll_offset(&offset, 1, 10, 4), 1);
+        assert_eq!(scroll_offset(&offset, 0, 0, 4), 0);
+    }
+
+    #[test]
+    fn test_list_mouse() {
+        let rows = Rect::new(2, 5, 20, 4);
+        let mouse = |kind, row| Mouse {
+            kind,
+            column: 3,
+            row,
+        };
+        assert_eq!(
+            list_mouse(mouse(MouseKind::Click, 6), rows, 10, 20),
+            Some(ListMouse::Select(11))
+        );
+        assert_eq!(
+            list_mouse(mouse(MouseKind::DoubleClick, 5), rows, 10, 20),
+            Some(ListMouse::Play(10))
+        );
+        assert_eq!(
+            list_mouse(mouse(MouseKind::ScrollDown, 8), rows, 0, 0),
+            Some(ListMouse::Scroll(Direction::Down))
+        );
+        // 最后一项之后的空白处、列表之外和拖动都不是列表操作
+        assert_eq!(list_mouse(mouse(MouseKind::Click, 8), rows, 0, 3), None);
+        assert_eq!(list_mouse(mouse(MouseKind::Click, 9), rows, 0, 20), None);
+        assert_eq!(list_mouse(mouse(MouseKind::Drag, 6), rows, 0, 20), None);
+    }
+}
@@ -0,0 +1,507 @@
+//! `DirectoriesTui` 模块，目录页。
+//!
+//! 以树的形式浏览音乐库根目录，显示子目录和文件，不受支持的文件会被标记出来。
+//! 页面不直接访问文件系统：展开目录时通过 `TuiAction::ListDirectory` 请求应用程序读取，
+//! 结果通过 `TuiEnent::DirectoryListing` 返回。
+
+use std::{
+    cell::Cell,
+    path::{Path, PathBuf},
+};
+
+// 从 lazy_core 中导入目录条目、TuiStyle 结构体和 HasTuiStyle trait
+use lazy_core::{
+    structs::TuiStyle,
+    theme::TuiTheme,
+    traits::{HasTuiStyle, HasTuiStyleSetter},
+    types::{DirEntry, EntryKind},
+};
+// 导入宏，用于自动派生 trait
+use lazy_macro::DeriveHasTuiStyle;
+// 从 ratatui 中导入所需的组件和布局
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{List, ListState, Paragraph},
+};
+
+// 从当前 crate 中导入所需的 traits 和类型
+use crate::{
+    traits::{RenderTui, TuiEventHandle},
+    types::{Direction, EnqueueMode, EventResult, Mouse, TuiAction, TuiEnent},
+    views::{ListMouse, list_mouse, scroll_offset},
+};
+
+/// 目录树中一个可见的节点。
+#[derive(Debug)]
+struct Node {
+    entry: DirEntry, // 对应的目录条目
+    depth: usize,    // 节点深度，根目录为 0
+    expanded: bool,  // 目录是否已展开
+}
+
+impl Node {
+    /// 创建一个折叠的节点。
+    fn new(entry: DirEntry, depth: usize) -> Self {
+        Self {
+            entry,
+            depth,
+            expanded: false,
+        }
+    }
+
+    /// 节点是否是目录。
+    fn is_dir(&self) -> bool {
+        self.entry.kind == EntryKind::Directory
+    }
+}
+
+/// `DirectoriesTui` 是目录页。
+#[derive(DeriveHasTuiStyle)]
+pub struct DirectoriesTui {
+    nodes: Vec<Node>,        // 按显示顺序排列的可见节点（已展开目录的子节点紧跟在其后）
+    selected: usize,         // 选中节点的索引
+    offset: Cell<usize>,     // 第一行可见节点的索引
+    rows: Cell<Rect>,        // 上一次渲染时目录树所在的区域，用于鼠标点击
+    actions: Vec<TuiAction>, // 等待应用程序执行的操作
+    style: TuiStyle,         // TUI 样式（颜色、对齐方式等）
+    directory: Style,        // 目录的样式
+    unsupported: Style,      // 不受支持的文件的样式
+    selected_style: Style,   // 选中行的样式
+}
+
+impl Default for DirectoriesTui {
+    /// 创建一个默认的 `DirectoriesTui` 实例。
+    fn default() -> Self {
+        let mut style = TuiStyle::default();
+        style.set_alignment(Alignment::Center);
+        style.set_modifier(Modifier::empty());
+        let mut directories = Self {
+            nodes: Vec::new(),
+            selected: 0,
+            offset: Cell::new(0),
+            rows: Cell::new(Rect::default()),
+            actions: Vec::new(),
+            style,
+            directory: Style::default(),
+            unsupported: Style::default(),
+            selected_style: Style::default(),
+        };
+        directories.apply_theme(&TuiTheme::current());
+        directories
+    }
+}
+
+impl DirectoriesTui {
+    /// 设置音乐库根目录，所有根目录初始为折叠状态。
+    fn set_roots(&mut self, roots: &[PathBuf]) {
+        self.nodes = roots
+            .iter()
+            .map(|root| Node::new(DirEntry::new(root, EntryKind::Directory), 0))
+            .collect();
+        self.selected = 0;
+    }
+
+    /// 返回节点子树（不包括节点本身）的结束位置。
+    fn subtree_end(&self, index: usize) -> usize {
+        let depth = self.nodes[index].depth;
+        self.nodes[index + 1..]
+            .iter()
+            .position(|node| node.depth <= depth)
+            .map_or(self.nodes.len(), |i| index + 1 + i)
+    }
+
+    /// 展开选中的目录，并请求应用程序读取其内容。
+    fn expand(&mut self) {
+        let Some(node) = self.nodes.get_mut(self.selected) else {
+            return;
+        };
+        if node.is_dir() && !node.expanded {
+            node.expanded = true;
+            self.actions
+                .push(TuiAction::ListDirectory(node.entry.path.clone()));
+        }
+    }
+
+    /// 折叠选中的目录。
+    fn collapse(&mut self) {
+        let end = self.subtree_end(self.selected);
+        self.nodes.drain(self.selected + 1..end);
+        self.nodes[self.selected].expanded = false;
+    }
+
+    /// 跳到选中节点的上一级目录。
+    fn parent(&mut self) {
+        let Some(depth) = self.nodes.get(self.selected).map(|n| n.depth) else {
+            return;
+        };
+        if let Some(index) = self.nodes[..self.selected]
+            .iter()
+            .rposition(|node| node.depth < depth)
+        {
+            self.selected = index;
+        }
+    }
+
+    /// 向上或向下移动选中项，向右展开，向左折叠或跳到上一级。
+    fn pick(&mut self, direction: Direction) {
+        match direction {
+            Direction::Up => self.selected = self.selected.saturating_sub(1),
+            Direction::Down if self.selected + 1 < self.nodes.len() => self.selected += 1,
+            Direction::Down => (),
+            Direction::Right => self.expand(),
+            Direction::Left => match self.nodes.get(self.selected) {
+                Some(node) if node.expanded => self.collapse(),
+                Some(_) => self.parent(),
+                None => (),
+            },
+        }
+    }
+
+    /// 用目录的内容替换对应节点的子节点；目录已被折叠时忽略。
+    fn set_listing(&mut self, path: &Path, entries: &[DirEntry]) {
+        let Some(index) = self
+            .nodes
+            .iter()
+            .position(|node| node.expanded && node.entry.path == path)
+        else {
+            return;
+        };
+        let end = self.subtree_end(index);
+        let depth = self.nodes[index].depth + 1;
+        let children = entries.iter().map(|e| Node::new(e.clone(), depth));
+        let removed = self.nodes.splice(index + 1..end, children).count();
+
+        // 选中项在被替换的子树中时回到目录本身，在其后时随之移动
+        if self.selected > index && self.selected < end {
+            self.selected = index;
+        } else if self.selected >= end {
+            self.selected = self.selected + entries.len() - removed;
+        }
+    }
+
+    /// 选中项是目录时展开或折叠，是音频文件时加入队列并播放。
+    fn activate(&mut self) {
+        match self.nodes.get(self.selected) {
+            Some(node) if node.expanded => self.collapse(),
+            Some(node) if node.is_dir() => self.expand(),
+            Some(_) => self.enqueue(EnqueueMode::Play),
+            None => (),
+        }
+    }
+
+    /// 处理鼠标操作：点击选中节点，双击展开或折叠目录、播放文件，滚动滚轮移动选中项。
+    fn mouse(&mut self, mouse: Mouse) -> EventResult {
+        let len = self.nodes.len();
+        let Some(action) = list_mouse(mouse, self.rows.get(), self.offset.get(), len) else {
+            return EventResult::Ignored;
+        };
+        match action {
+            ListMouse::Select(index) => self.selected = index,
+            ListMouse::Play(index) => {
+                self.selected = index;
+                self.activate();
+            }
+            ListMouse::Scroll(direction) => self.pick(direction),
+        }
+        EventResult::Handled
+    }
+
+    /// 请求应用程序将选中的目录（递归）或文件加入队列，不受支持的文件会被忽略。
+    fn enqueue(&mut self, mode: EnqueueMode) {
+        if let Some(node) = self.nodes.get(self.selected)
+            && node.entry.kind != EntryKind::Unsupported
+        {
+            self.actions
+                .push(TuiAction::Enqueue(vec![node.entry.path.clone()], mode));
+        }
+    }
+
+    /// 生成节点对应的一行。
+    fn line<'a>(&self, node: &'a Node) -> Line<'a> {
+        let indent = "  ".repeat(node.depth);
+        let (icon, style) = match node.entry.kind {
+            EntryKind::Directory if node.expanded => ("▾ ", self.directory),
+            EntryKind::Directory => ("▸ ", self.directory),
+            EntryKind::Audio => ("♪ ", self.tui_style()),
+            EntryKind::Unsupported => ("✗ ", self.unsupported),
+        };
+        // 根目录显示完整路径，其余显示文件名
+        let name = if node.depth == 0 {
+            node.entry.path.to_string_lossy()
+        } else {
+            node.entry.name()
+        };
+        Line::from(vec![
+            Span::raw(indent),
+            Span::styled(icon, style),
+            Span::styled(name, style),
+        ])
+    }
+}
+
+impl RenderTui for DirectoriesTui {
+    /// 渲染目录树，只为可见的行生成内容。
+    fn render(&self, frame: &mut Frame, rect: Rect) {
+        self.rows.set(rect);
+        if self.nodes.is_empty() {
+            let hint = Paragraph::new("No music folders")
+                .style(self.tui_style())
+                .alignment(self.tui_alignment());
+            frame.render_widget(hint, rect);
+            return;
+        }
+
+        let height = rect.height as usize;
+        let offset = scroll_offset(&self.offset, self.selected, self.nodes.len(), height);
+        let end = (offset + height).min(self.nodes.len());
+        let list = List::new(self.nodes[offset..end].iter().map(|n| self.line(n)))
+            .style(self.tui_style())
+            .highlight_style(self.selected_style);
+
+        let mut state = ListState::default().with_selected(Some(self.selected - offset));
+        frame.render_stateful_widget(list, rect, &mut state);
+    }
+
+    fn as_event_mut(&mut self) -> Option<&mut dyn TuiEventHandle> {
+        Some(self)
+    }
+
+    /// 重新设置页面的样式。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_tui_theme(theme);
+        self.directory = Style::default()
+            .fg(theme.blue1)
+            .add_modifier(Modifier::BOLD);
+        self.unsupported = Style::default()
+            .fg(theme.dark3)
+            .add_modifier(Modifier::CROSSED_OUT);
+        self.selected_style = Style::default().bg(theme.bg_highlight);
+    }
+}
+
+impl TuiEventHandle for DirectoriesTui {
+    /// 处理 TUI 事件。
+    fn event_handle(&mut self, event: TuiEnent) -> EventResult {
+        match event {
+            TuiEnent::MusicRoots(roots) => self.set_roots(&roots),
+            TuiEnent::DirectoryListing(path, entries) => self.set_listing(&path, &entries),
+            TuiEnent::Picker(direction) => self.pick(direction),
+            TuiEnent::Parent => self.parent(),
+            TuiEnent::PlaySelected => self.activate(),
+            TuiEnent::AddSelected => self.enqueue(EnqueueMode::Append),
+            TuiEnent::ReplaceWithSelected => self.enqueue(EnqueueMode::Replace),
+            TuiEnent::Mouse(mouse) => return self.mouse(mouse),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Handled
+    }
+
+    /// 取出等待应用程序执行的操作。
+    fn take_actions(&mut self) -> Vec<TuiAction> {
+        std::mem::take(&mut self.actions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MouseKind;
+    use ratatui::{Terminal, backend::TestBackend};
+    use std::borrow::Cow;
+
+    fn dir(path: &str) -> DirEntry {
+        DirEntry::new(path, EntryKind::Directory)
+    }
+
+    fn file(path: &str) -> DirEntry {
+        let kind = if path.ends_with(".flac") {
+            EntryKind::Audio
+        } else {
+            EntryKind::Unsupported
+        };
+        DirEntry::new(path, kind)
+    }
+
+    /// 模拟应用程序：处理 `ListDirectory` 请求并返回目录内容。
+    fn respond(tui: &mut DirectoriesTui) -> Vec<TuiAction> {
+        let mut other = Vec::new();
+        for action in tui.take_actions() {
+            match action {
+                TuiAction::ListDirectory(path) => {
+                    let entries = match path.to_str().unwrap() {
+                        "/music" => vec![dir("/music/A"), file("/music/01.flac")],
+                        "/music/A" => vec![file("/music/A/01.flac"), file("/music/A/cover.jpg")],
+                        _ => vec![],
+                    };
+                    tui.event_handle(TuiEnent::DirectoryListing(
+                        Cow::Owned(path),
+                        Cow::Owned(entries),
+                    ));
+                }
+                action => other.push(action),
+            }
+        }
+        other
+    }
+
+    fn names(tui: &DirectoriesTui) -> Vec<String> {
+        tui.nodes
+            .iter()
+            .map(|n| n.entry.path.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn tree() -> DirectoriesTui {
+        let mut tui = DirectoriesTui::default();
+        let roots = [PathBuf::from("/music"), PathBuf::from("/other")];
+        tui.event_handle(TuiEnent::MusicRoots(Cow::Borrowed(&roots)));
+        tui
+    }
+
+    #[test]
+    fn test_directories_tui_expand_and_collapse() {
+        let mut tui = tree();
+        tui.event_handle(TuiEnent::PlaySelected);
+        respond(&mut tui);
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        tui.event_handle(TuiEnent::Picker(Direction::Right));
+        respond(&mut tui);
+        assert_eq!(
+            names(&tui),
+            vec![
+                "/music",
+                "/music/A",
+                "/music/A/01.flac",
+                "/music/A/cover.jpg",
+                "/music/01.flac",
+                "/other"
+            ]
+        );
+
+        // 在文件上向左跳到上一级，再向左折叠目录
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        tui.event_handle(TuiEnent::Picker(Direction::Left));
+        assert_eq!(tui.selected, 1);
+        tui.event_handle(TuiEnent::Picker(Direction::Left));
+        assert_eq!(
+            names(&tui),
+            vec!["/music", "/music/A", "/music/01.flac", "/other"]
+        );
+
+        tui.event_handle(TuiEnent::Parent);
+        assert_eq!(tui.selected, 0);
+        tui.event_handle(TuiEnent::PlaySelected);
+        assert_eq!(names(&tui), vec!["/music", "/other"]);
+    }
+
+    #[test]
+    fn test_directories_tui_ignores_stale_listing() {
+        let mut tui = tree();
+        tui.event_handle(TuiEnent::Picker(Direction::Right));
+        // 应用程序返回结果之前目录已被折叠
+        tui.event_handle(TuiEnent::Picker(Direction::Left));
+        respond(&mut tui);
+        assert_eq!(names(&tui), vec!["/music", "/other"]);
+    }
+
+    #[test]
+    fn test_directories_tui_enqueue_actions() {
+        let mut tui = tree();
+        tui.event_handle(TuiEnent::AddSelected);
+        tui.event_handle(TuiEnent::ReplaceWithSelected);
+        tui.event_handle(TuiEnent::Picker(Direction::Right));
+        let mut actions = respond(&mut tui);
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        tui.event_handle(TuiEnent::PlaySelected);
+        actions.extend(respond(&mut tui));
+        assert_eq!(
+            actions,
+            vec![
+                TuiAction::Enqueue(vec!["/music".into()], EnqueueMode::Append),
+                TuiAction::Enqueue(vec!["/music".into()], EnqueueMode::Replace),
+                TuiAction::Enqueue(vec!["/music/01.flac".into()], EnqueueMode::Play),
+            ]
+        );
+
+        // 不受支持的文件不会加入队列
+        tui.event_handle(TuiEnent::Picker(Direction::Up));
+        tui.event_handle(TuiEnent::Picker(Direction::Right));
+        respond(&mut tui);
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        tui.event_handle(TuiEnent::AddSelected);
+        assert_eq!(tui.nodes[tui.selected].entry.name(), "cover.jpg");
+        assert!(respond(&mut tui).is_empty());
+    }
+
+    #[test]
+    fn test_directories_tui_mouse() {
+        let mut terminal = Terminal::new(TestBackend::new(30, 6)).unwrap();
+        let mut tui = tree();
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+        let mouse = |kind, row| {
+            TuiEnent::Mouse(Mouse {
+                kind,
+                column: 2,
+                row,
+            })
+        };
+
+        // 双击目录展开，双击文件播放
+        tui.event_handle(mouse(MouseKind::DoubleClick, 0));
+        respond(&mut tui);
+        tui.event_handle(mouse(MouseKind::Click, 3));
+        assert_eq!(tui.selected, 3);
+        tui.event_handle(mouse(MouseKind::DoubleClick, 2));
+        assert_eq!(
+            respond(&mut tui),
+            vec![TuiAction::Enqueue(
+                vec!["/music/01.flac".into()],
+                EnqueueMode::Play
+            )]
+        );
+    }
+
+    #[test]
+    fn test_directories_tui_render() {
+        let backend = TestBackend::new(30, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut tui = tree();
+        tui.event_handle(TuiEnent::Picker(Direction::Right));
+        respond(&mut tui);
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        tui.event_handle(TuiEnent::Picker(Direction::Right));
+        respond(&mut tui);
+
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| -> String {
+            (0..30)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        };
+        assert_eq!(line(0), "▾ /music");
+        assert_eq!(line(1), "  ▾ A");
+        assert_eq!(line(2), "    ♪ 01.flac");
+        assert_eq!(line(3), "    ✗ cover.jpg");
+        assert!(buffer[(6, 3)].modifier.contains(Modifier::CROSSED_OUT));
+    }
+
+    #[test]
+    fn test_directories_tui_render_without_roots() {
+        let backend = TestBackend::new(30, 1);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let tui = DirectoriesTui::default();
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        let line: String = (0..30).map(|x| buffer[(x, 0)].symbol()).collect();
+        assert_eq!(line.trim(), "No music folders");
+    }
+}
@@ -0,0 +1,861 @@
+//! `LibraryTui` 模块，艺术家、专辑艺术家和专辑页。
+//!
+//! 三个页面共用同一个多栏下钻视图：艺术家（或专辑艺术家）→ 专辑 → 曲目，专辑页则只有专辑 → 曲目两栏。
+//! 每一栏都可以播放、添加或替换播放队列。
+//!
+//! 页面不持有媒体库：媒体库变化时从 `TuiEnent::LibraryUpdated` 借用的索引中汇总分组和专辑，
+//! 选中其他专辑时通过 `TuiAction::LoadTracks` 请求专辑的曲目，结果通过 `TuiEnent::LibraryTracks` 返回。
+
+use std::{borrow::Cow, cell::Cell, collections::BTreeMap, path::PathBuf};
+
+// 从 lazy_core 中导入曲目、TuiStyle 结构体和 HasTuiStyle trait
+use lazy_core::{
+    structs::TuiStyle,
+    theme::TuiTheme,
+    track::Track,
+    traits::{HasTuiStyle, HasTuiStyleSetter},
+};
+// 从 lazy_library 中导入媒体库索引和专辑汇总
+use lazy_library::index::{Album, AlbumSort, Library, summarize_albums};
+// 导入宏，用于自动派生 trait
+use lazy_macro::DeriveHasTuiStyle;
+// 从 ratatui 中导入所需的组件和布局
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Cell as TableCell, Paragraph, Row, Table, TableState},
+};
+
+// 从当前 crate 中导入所需的 traits 和类型
+use crate::{
+    traits::{RenderTui, TuiEventHandle},
+    types::{Direction, EnqueueMode, EventResult, Mouse, TuiAction, TuiEnent},
+    views::{ListMouse, format_duration, list_mouse, scroll_offset},
+};
+
+/// 第一栏的分组方式。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grouping {
+    /// 按曲目艺术家分组
+    Artist,
+    /// 按专辑艺术家分组，合辑归入专辑艺术家而不是各曲目的艺术家
+    AlbumArtist,
+    /// 不分组，直接列出所有专辑
+    Album,
+}
+
+/// 下钻视图中的一栏。
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Pane {
+    Groups, // 艺术家或专辑艺术家
+    Albums, // 专辑
+    Tracks, // 曲目
+}
+
+/// 一栏的选中项和滚动位置。
+#[derive(Default)]
+struct Selection {
+    selected: usize,     // 选中项的索引
+    offset: Cell<usize>, // 第一行可见项的索引
+    rows: Cell<Rect>,    // 上一次渲染时这一栏的行所在的区域，用于鼠标点击
+}
+
+/// `LibraryTui` 是艺术家、专辑艺术家和专辑页。
+#[derive(DeriveHasTuiStyle)]
+pub struct LibraryTui {
+    grouping: Grouping,            // 第一栏的分组方式
+    is_empty: bool,                // 媒体库是否为空
+    groups: Vec<String>,           // 艺术家或专辑艺术家
+    group_albums: Vec<Vec<Album>>, // 每个分组的专辑，按 `groups` 的顺序；专辑页只有一组
+    tracks: Vec<Track>,            // 选中专辑的曲目，由应用程序按需发送
+    selections: [Selection; 3],    // 每一栏的选中项，按 `Pane` 的顺序
+    focus: Pane,                   // 当前操作的一栏
+    sort: AlbumSort,               // 专辑的排序方式
+    actions: Vec<TuiAction>,       // 等待应用程序执行的操作
+    style: TuiStyle,               // TUI 样式（颜色、对齐方式等）
+    header: Style,                 // 栏标题的样式
+    focused: Style,                // 当前栏标题的样式
+    dimmed: Style,                 // 次要信息（年份、曲目数、时长）的样式
+    selected_style: Style,         // 当前栏选中行的样式
+    inactive_selected: Style,      // 其他栏选中行的样式
+}
+
+impl LibraryTui {
+    /// 创建指定分组方式的页面。
+    pub(crate) fn new(grouping: Grouping) -> Self {
+        let mut style = TuiStyle::default();
+        style.set_alignment(Alignment::Center);
+        style.set_modifier(Modifier::empty());
+        let mut page = Self {
+            grouping,
+            is_empty: true,
+            groups: Vec::new(),
+            group_albums: Vec::new(),
+            tracks: Vec::new(),
+            selections: Default::default(),
+            focus: Self::first_pane(grouping),
+            sort: AlbumSort::default(),
+            actions: Vec::new(),
+            style,
+            header: Style::default(),
+            focused: Style::default(),
+            dimmed: Style::default(),
+            selected_style: Style::default(),
+            inactive_selected: Style::default(),
+        };
+        page.apply_theme(&TuiTheme::current());
+        page
+    }
+
+    /// 获取页面的分组方式。
+    #[cfg(test)]
+    pub(crate) fn grouping(&self) -> Grouping {
+        self.grouping
+    }
+
+    /// 分组方式对应的第一栏，专辑页没有分组栏。
+    fn first_pane(grouping: Grouping) -> Pane {
+        match grouping {
+            Grouping::Artist | Grouping::AlbumArtist => Pane::Groups,
+            Grouping::Album => Pane::Albums,
+        }
+    }
+
+    /// 页面显示的所有栏。
+    fn panes(&self) -> &'static [Pane] {
+        match self.grouping {
+            Grouping::Artist | Grouping::AlbumArtist => &[Pane::Groups, Pane::Albums, Pane::Tracks],
+            Grouping::Album => &[Pane::Albums, Pane::Tracks],
+        }
+    }
+
+    /// 获取一栏的选中项。
+    fn selection(&self, pane: Pane) -> &Selection {
+        &self.selections[pane as usize]
+    }
+
+    /// 获取一栏选中项的索引。
+    fn selected(&self, pane: Pane) -> usize {
+        self.selection(pane).selected
+    }
+
+    /// 设置一栏选中项的索引。
+    fn select(&mut self, pane: Pane, index: usize) {
+        self.selections[pane as usize].selected = index;
+    }
+
+    /// 设置一栏选中项的索引，选中的分组或专辑变化后刷新下一级。
+    fn select_and_refresh(&mut self, pane: Pane, index: usize) {
+        if self.selected(pane) == index {
+            return;
+        }
+        self.select(pane, index);
+        match pane {
+            Pane::Groups => self.load_albums(),
+            Pane::Albums => self.load_tracks(),
+            Pane::Tracks => (),
+        }
+    }
+
+    /// 获取一栏的条目数量。
+    fn len(&self, pane: Pane) -> usize {
+        match pane {
+            Pane::Groups => self.groups.len(),
+            Pane::Albums => self.albums().len(),
+            Pane::Tracks => self.tracks.len(),
+        }
+    }
+
+    /// 更新媒体库，尽量保持原来选中的分组、专辑和曲目。
+    ///
+    /// 所有分组的专辑在这里一次汇总完，之后切换分组不需要再访问媒体库。
+    fn set_library(&mut self, library: &Library) {
+        let group = self.groups.get(self.selected(Pane::Groups)).cloned();
+        let album = self
+            .selected_album()
+            .map(|a| (a.title.clone(), a.artist.clone()));
+        let track = self
+            .tracks
+            .get(self.selected(Pane::Tracks))
+            .map(|t| t.path.clone());
+
+        // 按分组汇总曲目，专辑页的所有曲目属于同一组；没有艺术家标签的曲目不属于任何艺术家
+        let mut groups: BTreeMap<Option<&str>, Vec<&Track>> = BTreeMap::new();
+        for track in library.tracks() {
+            let key = match self.grouping {
+                Grouping::Artist => track.artist.as_deref(),
+                Grouping::AlbumArtist => track.album_artist_or_artist(),
+                Grouping::Album => None,
+            };
+            groups.entry(key).or_default().push(track);
+        }
+        if self.grouping != Grouping::Album {
+            groups.remove(&None);
+        }
+        self.is_empty = library.is_empty();
+        self.groups = groups
+            .keys()
+            .flatten()
+            .map(|name| name.to_string())
+            .collect();
+        self.group_albums = groups
+            .into_values()
+            .map(|tracks| {
+                let mut albums = summarize_albums(tracks);
+                self.sort.sort(&mut albums);
+                albums
+            })
+            .collect();
+
+        let index = group
+            .and_then(|g| self.groups.iter().position(|name| *name == g))
+            .unwrap_or(0);
+        self.select(Pane::Groups, index.min(self.groups.len().saturating_sub(1)));
+        let index = album
+            .and_then(|(title, artist)| {
+                self.albums()
+                    .iter()
+                    .position(|a| a.title == title && a.artist == artist)
+            })
+            .unwrap_or(0);
+        self.select(Pane::Albums, index);
+
+        // 媒体库就在手边，直接读取选中专辑的曲目
+        self.tracks = self
+            .selected_album()
+            .map(|album| {
+                album
+                    .tracks
+                    .iter()
+                    .filter_map(|path| library.get(path).cloned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let index = track
+            .and_then(|p| self.tracks.iter().position(|t| t.path == p))
+            .unwrap_or(0);
+        self.select(Pane::Tracks, index);
+        // 当前栏变为空时回到第一栏
+        if self.len(self.focus) == 0 {
+            self.focus = Self::first_pane(self.grouping);
+        }
+    }
+
+    /// 选中的分组变化后选中第一张专辑，并请求它的曲目。
+    fn load_albums(&mut self) {
+        self.select(Pane::Albums, 0);
+        self.load_tracks();
+    }
+
+    /// 选中的专辑变化后清空曲目栏，并请求应用程序发送专辑的曲目。
+    fn load_tracks(&mut self) {
+        self.tracks.clear();
+        self.select(Pane::Tracks, 0);
+        if let Some(album) = self.selected_album() {
+            let paths = album.tracks.clone();
+            self.actions.push(TuiAction::LoadTracks(paths));
+        }
+    }
+
+    /// 收到应用程序发送的曲目，只接受当前选中专辑的曲目。
+    fn set_tracks(&mut self, paths: &[PathBuf], tracks: Cow<'_, [Track]>) -> EventResult {
+        if self
+            .selected_album()
+            .is_none_or(|album| album.tracks != paths)
+        {
+            return EventResult::Ignored;
+        }
+        self.tracks = tracks.into_owned();
+        self.select(Pane::Tracks, 0);
+        EventResult::Handled
+    }
+
+    /// 选中分组的专辑，专辑页为所有专辑。
+    fn albums(&self) -> &[Album] {
+        self.group_albums
+            .get(self.selected(Pane::Groups))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// 获取选中的专辑。
+    fn selected_album(&self) -> Option<&Album> {
+        self.albums().get(self.selected(Pane::Albums))
+    }
+
+    /// 在当前栏中向上或向下移动选中项，向右进入下一栏，向左回到上一栏。
+    fn pick(&mut self, direction: Direction) {
+        let pane = self.focus;
+        let selected = self.selected(pane);
+        match direction {
+            Direction::Up if selected > 0 => self.select_and_refresh(pane, selected - 1),
+            Direction::Down if selected + 1 < self.len(pane) => {
+                self.select_and_refresh(pane, selected + 1)
+            }
+            Direction::Left => self.parent(),
+            Direction::Right => {
+                if let Some(&next) = self.panes().iter().find(|&&p| p > pane)
+                    && self.len(next) > 0
+                {
+                    self.focus = next;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// 处理鼠标操作：鼠标所在的栏成为当前栏，点击选中一项，双击播放，滚动滚轮移动选中项。
+    fn mouse(&mut self, mouse: Mouse) -> EventResult {
+        for &pane in self.panes() {
+            let selection = self.selection(pane);
+            let (rows, offset) = (selection.rows.get(), selection.offset.get());
+            let Some(action) = list_mouse(mouse, rows, offset, self.len(pane)) else {
+                continue;
+            };
+            self.focus = pane;
+            match action {
+                ListMouse::Select(index) => self.select_and_refresh(pane, index),
+                ListMouse::Play(index) => {
+                    self.select_and_refresh(pane, index);
+                    self.enqueue(EnqueueMode::Play);
+                }
+                ListMouse::Scroll(direction) => self.pick(direction),
+            }
+            return EventResult::Handled;
+        }
+        EventResult::Ignored
+    }
+
+    /// 回到上一栏。
+    fn parent(&mut self) {
+        if let Some(&prev) = self.panes().iter().rev().find(|&&p| p < self.focus) {
+            self.focus = prev;
+        }
+    }
+
+    /// 切换专辑的排序方式，保持选中的专辑不变。
+    fn cycle_sort(&mut self) {
+        let selected = self.selected_album().map(|a| a.tracks.clone());
+        self.sort = self.sort.next();
+        for albums in &mut self.group_albums {
+            self.sort.sort(albums);
+        }
+        if let Some(index) =
+            selected.and_then(|tracks| self.albums().iter().position(|a| a.tracks == tracks))
+        {
+            self.select(Pane::Albums, index);
+        }
+    }
+
+    /// 当前栏选中项包含的曲目：分组为其所有专辑的曲目，专辑为专辑的曲目，曲目为其本身。
+    fn selected_paths(&self) -> Vec<PathBuf> {
+        match self.focus {
+            Pane::Groups => self
+                .albums()
+                .iter()
+                .flat_map(|album| album.tracks.iter().cloned())
+                .collect(),
+            Pane::Albums => self
+                .selected_album()
+                .map(|album| album.tracks.clone())
+                .unwrap_or_default(),
+            Pane::Tracks => self
+                .tracks
+                .get(self.selected(Pane::Tracks))
+                .map(|track| vec![track.path.clone()])
+                .unwrap_or_default(),
+        }
+    }
+
+    /// 请求应用程序将当前栏的选中项加入队列。
+    fn enqueue(&mut self, mode: EnqueueMode) {
+        let paths = self.selected_paths();
+        if !paths.is_empty() {
+            self.actions.push(TuiAction::Enqueue(paths, mode));
+        }
+    }
+
+    /// 栏的标题。
+    fn title(&self, pane: Pane) -> String {
+        match pane {
+            Pane::Groups if self.grouping == Grouping::AlbumArtist => "Album Artists".to_string(),
+            Pane::Groups => "Artists".to_string(),
+            Pane::Albums => {
+                let sort = match self.sort {
+                    AlbumSort::Title => "title",
+                    AlbumSort::Year => "year",
+                    AlbumSort::TrackCount => "tracks",
+                    AlbumSort::Duration => "duration",
+                };
+                format!("Albums · by {sort}")
+            }
+            Pane::Tracks => "Tracks".to_string(),
+        }
+    }
+
+    /// 生成一栏中的一行。
+    fn row(&self, pane: Pane, index: usize) -> Row<'_> {
+        let duration = |d| TableCell::from(format_duration(d)).style(self.dimmed);
+        match pane {
+            Pane::Groups => Row::new([TableCell::from(self.groups[index].as_str())]),
+            Pane::Albums => {
+                let album = &self.albums()[index];
+                let mut cells = vec![TableCell::from(album.title.as_deref().unwrap_or("Unknown"))];
+                if self.grouping == Grouping::Album {
+                    cells.push(TableCell::from(album.artist.as_deref().unwrap_or_default()));
+                }
+                cells.extend([
+                    TableCell::from(album.year.map(|y| y.to_string()).unwrap_or_default())
+                        .style(self.dimmed),
+                    TableCell::from(format!("{:>3}", album.track_count())).style(self.dimmed),
+                    duration(album.duration),
+                ]);
+                Row::new(cells)
+            }
+            Pane::Tracks => {
+                let track = &self.tracks[index];
+                Row::new([
+                    TableCell::from(
+                        track
+                            .track_number
+                            .map(|n| format!("{n:>2}"))
+                            .unwrap_or_default(),
+                    )
+                    .style(self.dimmed),
+                    TableCell::from(track.display_title()),
+                    track.duration.map(duration).unwrap_or_default(),
+                ])
+            }
+        }
+    }
+
+    /// 一栏中各列的宽度。
+    fn widths(&self, pane: Pane) -> Vec<Constraint> {
+        match pane {
+            Pane::Groups => vec![Constraint::Fill(1)],
+            Pane::Albums if self.grouping == Grouping::Album => vec![
+                Constraint::Fill(3),
+                Constraint::Fill(2),
+                Constraint::Length(4),
+                Constraint::Length(3),
+                Constraint::Length(8),
+            ],
+            Pane::Albums => vec![
+                Constraint::Fill(1),
+                Constraint::Length(4),
+                Constraint::Length(3),
+                Constraint::Length(8),
+            ],
+            Pane::Tracks => vec![
+                Constraint::Length(2),
+                Constraint::Fill(1),
+                Constraint::Length(8),
+            ],
+        }
+    }
+
+    /// 渲染一栏，只为可见的行生成内容。
+    fn render_pane(&self, frame: &mut Frame, pane: Pane, rect: Rect) {
+        let is_focused = pane == self.focus;
+        let title_style = if is_focused {
+            self.focused
+        } else {
+            self.header
+        };
+        let block = Block::new()
+            .title(self.title(pane))
+            .title_style(title_style);
+        let inner = block.inner(rect);
+        frame.render_widget(block, rect);
+
+        let len = self.len(pane);
+        let height = inner.height as usize;
+        let selection = self.selection(pane);
+        selection.rows.set(inner);
+        let offset = scroll_offset(&selection.offset, selection.selected, len, height);
+        let end = (offset + height).min(len);
+        let table = Table::new((offset..end).map(|i| self.row(pane, i)), self.widths(pane))
+            .style(self.tui_style())
+            .row_highlight_style(if is_focused {
+                self.selected_style
+            } else {
+                self.inactive_selected
+            });
+
+        let mut state =
+            TableState::default().with_selected((len > 0).then(|| selection.selected - offset));
+        frame.render_stateful_widget(table, inner, &mut state);
+    }
+}
+
+impl RenderTui for LibraryTui {
+    /// 并排渲染每一栏，媒体库为空时显示提示。
+    fn render(&self, frame: &mut Frame, rect: Rect) {
+        if self.is_empty {
+            let hint = Paragraph::new("Library is empty")
+                .style(self.tui_style())
+                .alignment(self.tui_alignment());
+            frame.render_widget(hint, rect);
+            return;
+        }
+
+        let constraints: &[Constraint] = match self.grouping {
+            Grouping::Artist | Grouping::AlbumArtist => &[
+                Constraint::Percentage(25),
+                Constraint::Percentage(40),
+                Constraint::Fill(1),
+            ],
+            Grouping::Album => &[Constraint::Percentage(55), Constraint::Fill(1)],
+        };
+        let areas = Layout::horizontal(constraints).spacing(1).split(rect);
+        for (&pane, &area) in self.panes().iter().zip(areas.iter()) {
+            self.render_pane(frame, pane, area);
+        }
+    }
+
+    fn as_event_mut(&mut self) -> Option<&mut dyn TuiEventHandle> {
+        Some(self)
+    }
+
+    /// 重新设置页面的样式。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_tui_theme(theme);
+        self.header = Style::default().fg(theme.fg_dark);
+        self.focused = Style::default().fg(theme.blue).add_modifier(Modifier::BOLD);
+        self.dimmed = Style::default().fg(theme.fg_dark);
+        self.selected_style = Style::default().bg(theme.bg_highlight);
+        self.inactive_selected = Style::default().bg(theme.bg_dark);
+    }
+}
+
+impl TuiEventHandle for LibraryTui {
+    /// 处理 TUI 事件。
+    fn event_handle(&mut self, event: TuiEnent) -> EventResult {
+        match event {
+            TuiEnent::LibraryUpdated(library) => self.set_library(library),
+            TuiEnent::LibraryTracks(paths, tracks) => return self.set_tracks(&paths, tracks),
+            TuiEnent::Picker(direction) => self.pick(direction),
+            TuiEnent::Parent => self.parent(),
+            TuiEnent::CycleSort => self.cycle_sort(),
+            TuiEnent::PlaySelected => self.enqueue(EnqueueMode::Play),
+            TuiEnent::AddSelected => self.enqueue(EnqueueMode::Append),
+            TuiEnent::ReplaceWithSelected => self.enqueue(EnqueueMode::Replace),
+            TuiEnent::Mouse(mouse) => return self.mouse(mouse),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Handled
+    }
+
+    /// 在栏之间移动焦点，与左右方向键相同。
+    fn shift_focus(&mut self, direction: Direction) -> bool {
+        let focus = self.focus;
+        match direction {
+            Direction::Left => self.parent(),
+            Direction::Right => self.pick(direction),
+            Direction::Up | Direction::Down => (),
+        }
+        self.focus != focus
+    }
+
+    /// 焦点移入时从第一栏，或者从最后一个有内容的栏开始。
+    fn enter_focus(&mut self, direction: Direction) {
+        let first = Self::first_pane(self.grouping);
+        self.focus = match direction {
+            Direction::Left => self
+                .panes()
+                .iter()
+                .rev()
+                .find(|&&pane| self.len(pane) > 0)
+                .copied()
+                .unwrap_or(first),
+            _ => first,
+        };
+    }
+
+    /// 取出等待应用程序执行的操作。
+    fn take_actions(&mut self) -> Vec<TuiAction> {
+        std::mem::take(&mut self.actions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MouseKind;
+    use ratatui::{Terminal, backend::TestBackend};
+    use std::time::Duration;
+
+    fn track(path: &str, artist: &str, album: &str, number: u32, year: i32) -> Track {
+        Track {
+            title: Some(format!("{album} {number}")),
+            artist: Some(artist.to_string()),
+            album: Some(album.to_string()),
+            track_number: Some(number),
+            year: Some(year),
+            duration: Some(Duration::from_secs(60)),
+            ..Track::new(path)
+        }
+    }
+
+    /// 两位艺术家各有专辑，B 还出现在一张合辑中。
+    fn library() -> Library {
+        let compilation = |path, artist, number| Track {
+            album_artist: Some("Various Artists".to_string()),
+            ..track(path, artist, "Hits", number, 2010)
+        };
+        Library::from_tracks([
+            track("/m/a/x/1.flac", "A", "X", 1, 1999),
+            track("/m/a/x/2.flac", "A", "X", 2, 1999),
+            track("/m/a/y/1.flac", "A", "Y", 1, 1990),
+            track("/m/b/z/1.flac", "B", "Z", 1, 2005),
+            compilation("/m/va/1.flac", "B", 1),
+            compilation("/m/va/2.flac", "C", 2),
+        ])
+    }
+
+    fn library_tui(grouping: Grouping) -> LibraryTui {
+        let mut tui = LibraryTui::new(grouping);
+        tui.event_handle(TuiEnent::LibraryUpdated(&library()));
+        tui
+    }
+
+    /// 处理事件，并像应用程序一样用媒体库回答页面读取曲目的请求，返回其余的操作。
+    fn send(tui: &mut LibraryTui, event: TuiEnent) -> Vec<TuiAction> {
+        tui.event_handle(event);
+        let library = library();
+        let mut actions = Vec::new();
+        for action in tui.take_actions() {
+            match action {
+                TuiAction::LoadTracks(paths) => {
+                    let tracks: Vec<_> = paths
+                        .iter()
+                        .filter_map(|path| library.get(path).cloned())
+                        .collect();
+                    tui.event_handle(TuiEnent::LibraryTracks(
+                        Cow::Owned(paths),
+                        Cow::Owned(tracks),
+                    ));
+                }
+                action => actions.push(action),
+            }
+        }
+        actions
+    }
+
+    fn album_titles(tui: &LibraryTui) -> Vec<&str> {
+        tui.albums()
+            .iter()
+            .map(|a| a.title.as_deref().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_library_tui_drill_down() {
+        let mut tui = library_tui(Grouping::Artist);
+        assert_eq!(tui.groups, ["A", "B", "C"]);
+        assert_eq!(album_titles(&tui), ["X", "Y"]);
+        assert_eq!(tui.tracks.len(), 2);
+
+        send(&mut tui, TuiEnent::Picker(Direction::Down));
+        assert_eq!(
+            album_titles(&tui),
+            ["Hits", "Z"],
+            "艺术家页按曲目艺术家列出合辑"
+        );
+        send(&mut tui, TuiEnent::Picker(Direction::Right));
+        send(&mut tui, TuiEnent::Picker(Direction::Down));
+        send(&mut tui, TuiEnent::Picker(Direction::Right));
+        assert_eq!(tui.focus, Pane::Tracks);
+        assert_eq!(tui.tracks[0].path, PathBuf::from("/m/b/z/1.flac"));
+
+        send(&mut tui, TuiEnent::Picker(Direction::Right));
+        assert_eq!(tui.focus, Pane::Tracks, "最后一栏不能再向右");
+        send(&mut tui, TuiEnent::Parent);
+        send(&mut tui, TuiEnent::Picker(Direction::Left));
+        send(&mut tui, TuiEnent::Picker(Direction::Left));
+        assert_eq!(tui.focus, Pane::Groups);
+    }
+
+    #[test]
+    fn test_library_tui_focus_path() {
+        let mut tui = library_tui(Grouping::Artist);
+        assert!(tui.shift_focus(Direction::Right));
+        assert!(tui.shift_focus(Direction::Right));
+        assert_eq!(tui.focus, Pane::Tracks);
+        assert!(!tui.shift_focus(Direction::Right), "最后一栏交给父组件");
+        assert!(tui.shift_focus(Direction::Left));
+        assert!(tui.shift_focus(Direction::Left));
+        assert!(!tui.shift_focus(Direction::Left));
+        assert_eq!(tui.focus, Pane::Groups);
+
+        tui.enter_focus(Direction::Left);
+        assert_eq!(tui.focus, Pane::Tracks);
+        tui.enter_focus(Direction::Right);
+        assert_eq!(tui.focus, Pane::Groups);
+
+        // 专辑页从专辑栏开始，空的栏不获得焦点
+        let mut tui = LibraryTui::new(Grouping::Album);
+        tui.enter_focus(Direction::Left);
+        assert_eq!(tui.focus, Pane::Albums);
+        assert!(!tui.shift_focus(Direction::Right));
+    }
+
+    #[test]
+    fn test_library_tui_groups_compilations_by_album_artist() {
+        let mut tui = library_tui(Grouping::AlbumArtist);
+        assert_eq!(tui.groups, ["A", "B", "Various Artists"]);
+        send(&mut tui, TuiEnent::Picker(Direction::Down));
+        assert_eq!(album_titles(&tui), ["Z"]);
+        send(&mut tui, TuiEnent::Picker(Direction::Down));
+        assert_eq!(album_titles(&tui), ["Hits"]);
+        assert_eq!(
+            tui.albums()[0].track_count(),
+            2,
+            "合辑中不同艺术家的曲目归为一张专辑"
+        );
+
+        let tui = library_tui(Grouping::Album);
+        assert_eq!(tui.focus, Pane::Albums);
+        assert_eq!(album_titles(&tui), ["Hits", "X", "Y", "Z"]);
+    }
+
+    #[test]
+    fn test_library_tui_actions_on_every_level() {
+        let mut tui = library_tui(Grouping::Artist);
+        let actions: Vec<_> = [
+            TuiEnent::PlaySelected,
+            TuiEnent::Picker(Direction::Right),
+            TuiEnent::Picker(Direction::Down),
+            TuiEnent::AddSelected,
+            TuiEnent::Picker(Direction::Right),
+            TuiEnent::ReplaceWithSelected,
+        ]
+        .into_iter()
+        .flat_map(|event| send(&mut tui, event))
+        .collect();
+        let paths = |paths: &[&str]| paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+        assert_eq!(
+            actions,
+            vec![
+                TuiAction::Enqueue(
+                    paths(&["/m/a/x/1.flac", "/m/a/x/2.flac", "/m/a/y/1.flac"]),
+                    EnqueueMode::Play
+                ),
+                TuiAction::Enqueue(paths(&["/m/a/y/1.flac"]), EnqueueMode::Append),
+                TuiAction::Enqueue(paths(&["/m/a/y/1.flac"]), EnqueueMode::Replace),
+            ]
+        );
+
+        let mut empty = LibraryTui::new(Grouping::Artist);
+        empty.event_handle(TuiEnent::PlaySelected);
+        assert!(empty.take_actions().is_empty());
+    }
+
+    #[test]
+    fn test_library_tui_sort_and_update_keep_selection() {
+        let mut tui = library_tui(Grouping::Album);
+        send(&mut tui, TuiEnent::Picker(Direction::Down));
+        send(&mut tui, TuiEnent::CycleSort);
+        assert_eq!(tui.sort, AlbumSort::Year);
+        assert_eq!(album_titles(&tui), ["Y", "X", "Z", "Hits"]);
+        assert_eq!(tui.selected_album().unwrap().title.as_deref(), Some("X"));
+
+        send(&mut tui, TuiEnent::CycleSort);
+        assert_eq!(album_titles(&tui), ["Hits", "X", "Y", "Z"]);
+
+        // 媒体库更新后仍然选中原来的专辑
+        let mut library = library();
+        library.insert(track("/m/d/w/1.flac", "D", "W", 1, 2020));
+        send(&mut tui, TuiEnent::LibraryUpdated(&library));
+        assert_eq!(tui.selected_album().unwrap().title.as_deref(), Some("X"));
+        assert_eq!(tui.albums().len(), 5);
+    }
+
+    #[test]
+    fn test_library_tui_requests_album_tracks() {
+        let mut tui = library_tui(Grouping::Album);
+        assert_eq!(tui.tracks.len(), 2, "更新媒体库时直接读取选中专辑的曲目");
+        assert!(tui.take_actions().is_empty());
+
+        // 选中其他专辑时向应用程序请求曲目，只接受当前专辑的结果
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        assert!(tui.tracks.is_empty());
+        let x = vec![PathBuf::from("/m/a/x/1.flac"), "/m/a/x/2.flac".into()];
+        assert_eq!(tui.take_actions(), [TuiAction::LoadTracks(x.clone())]);
+        let stale = [PathBuf::from("/m/b/z/1.flac")];
+        let result = tui.event_handle(TuiEnent::LibraryTracks(
+            Cow::Borrowed(&stale),
+            Cow::Owned(vec![track("/m/b/z/1.flac", "B", "Z", 1, 2005)]),
+        ));
+        assert_eq!(result, EventResult::Ignored);
+        assert!(tui.tracks.is_empty());
+
+        let library = library();
+        let tracks: Vec<_> = x.iter().map(|p| library.get(p).unwrap().clone()).collect();
+        tui.event_handle(TuiEnent::LibraryTracks(
+            Cow::Borrowed(&x),
+            Cow::Owned(tracks),
+        ));
+        assert_eq!(tui.tracks[1].path, x[1]);
+    }
+
+    #[test]
+    fn test_library_tui_mouse() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 6)).unwrap();
+        let mut tui = library_tui(Grouping::Artist);
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+        let mouse = |tui: &LibraryTui, kind, pane: Pane, row| {
+            let rows = tui.selection(pane).rows.get();
+            TuiEnent::Mouse(Mouse {
+                kind,
+                column: rows.x,
+                row: rows.y + row,
+            })
+        };
+
+        // 点击分组刷新专辑，点击专辑使专辑栏成为当前栏并刷新曲目
+        let event = mouse(&tui, MouseKind::Click, Pane::Groups, 1);
+        send(&mut tui, event);
+        assert_eq!(album_titles(&tui), ["Hits", "Z"]);
+        let event = mouse(&tui, MouseKind::Click, Pane::Albums, 1);
+        send(&mut tui, event);
+        assert_eq!(tui.focus, Pane::Albums);
+        assert_eq!(tui.tracks[0].path, PathBuf::from("/m/b/z/1.flac"));
+
+        let event = mouse(&tui, MouseKind::DoubleClick, Pane::Tracks, 0);
+        let actions = send(&mut tui, event);
+        assert_eq!(tui.focus, Pane::Tracks);
+        assert_eq!(
+            actions,
+            vec![TuiAction::Enqueue(
+                vec!["/m/b/z/1.flac".into()],
+                EnqueueMode::Play
+            )]
+        );
+    }
+
+    #[test]
+    fn test_library_tui_render() {
+        let backend = TestBackend::new(80, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let tui = library_tui(Grouping::Artist);
+
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| -> String { (0..80).map(|x| buffer[(x, y)].symbol()).collect() };
+        assert!(line(0).starts_with("Artists"));
+        assert!(line(0).contains("Albums · by title"));
+        assert!(line(0).contains("Tracks"));
+        assert!(line(1).starts_with("A "));
+        assert!(line(1).contains("X") && line(1).contains("1999") && line(1).contains("02:00"));
+        assert!(line(2).contains("Y") && line(2).contains("1990"));
+        assert!(line(1).contains(" 1 X 1"));
+
+        let empty = LibraryTui::new(Grouping::Album);
+        terminal.draw(|f| empty.render(f, f.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        let content: String = buffer.content().iter().map(|c| c.symbol()).collect();
+        assert!(content.contains("Library is empty"));
+    }
+}
@@ -0,0 +1,374 @@
+//! `LogsTui` 模块，日志页。
+//!
+//! 页面保存应用程序发送来的最近日志，按最低级别过滤后显示。跟随模式下选中项始终是最新的一条，
+//! 向上移动时停止跟随，移动到最后一条时重新开始跟随。
+
+use std::{cell::Cell, collections::VecDeque};
+
+// 从 lazy_core 中导入日志、TuiStyle 结构体和 HasTuiStyle trait
+use lazy_core::{
+    logger::{DEFAULT_CAPACITY, Level, LevelFilter, LogRecord},
+    structs::TuiStyle,
+    theme::TuiTheme,
+    traits::{HasTuiStyle, HasTuiStyleSetter},
+};
+// 导入宏，用于自动派生 trait
+use lazy_macro::DeriveHasTuiStyle;
+// 从 ratatui 中导入所需的组件和布局
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Cell as TableCell, Paragraph, Row, Table, TableState},
+};
+
+// 从当前 crate 中导入所需的 traits 和类型
+use crate::{
+    traits::{RenderTui, TuiEventHandle},
+    types::{Direction, EventResult, Mouse, TuiEnent},
+    views::{ListMouse, list_mouse, scroll_offset},
+};
+
+/// `LogsTui` 是日志页。
+#[derive(DeriveHasTuiStyle)]
+pub struct LogsTui {
+    records: VecDeque<LogRecord>, // 最近的日志
+    capacity: usize,              // 最多保存的日志条数
+    level: LevelFilter,           // 显示的最低日志级别
+    selected: usize,              // 选中项在过滤后的日志中的索引
+    offset: Cell<usize>,          // 滚动位置
+    rows: Cell<Rect>,             // 上一次渲染时日志表格所在的区域，用于鼠标点击
+    follow: bool,                 // 是否跟随最新的日志
+    style: TuiStyle,              // TUI 样式（颜色、对齐方式等）
+    header: Style,                // 标题的样式
+    dimmed: Style,                // 时间、模块和调试日志的样式
+    warn_style: Style,            // 警告的样式
+    error_style: Style,           // 错误的样式
+    selected_style: Style,        // 选中行的样式
+}
+
+impl Default for LogsTui {
+    /// 创建一个默认的 `LogsTui` 实例，显示所有级别并跟随最新的日志。
+    fn default() -> Self {
+        let mut style = TuiStyle::default();
+        style.set_alignment(Alignment::Center);
+        style.set_modifier(Modifier::empty());
+        let mut logs = Self {
+            records: VecDeque::new(),
+            capacity: DEFAULT_CAPACITY,
+            level: LevelFilter::Trace,
+            selected: 0,
+            offset: Cell::new(0),
+            rows: Cell::new(Rect::default()),
+            follow: true,
+            style,
+            header: Style::default(),
+            dimmed: Style::default(),
+            warn_style: Style::default(),
+            error_style: Style::default(),
+            selected_style: Style::default(),
+        };
+        logs.apply_theme(&TuiTheme::current());
+        logs
+    }
+}
+
+impl LogsTui {
+    /// 获取按级别过滤后的日志。
+    fn visible(&self) -> impl Iterator<Item = &LogRecord> {
+        self.records.iter().filter(|r| r.level <= self.level)
+    }
+
+    /// 获取过滤后的日志条数。
+    fn visible_len(&self) -> usize {
+        self.visible().count()
+    }
+
+    /// 添加新的日志，超过容量时丢弃最早的日志，并保持选中的日志不变。
+    fn push_records(&mut self, records: &[LogRecord]) {
+        for record in records {
+            if self.records.len() == self.capacity
+                && let Some(dropped) = self.records.pop_front()
+                && dropped.level <= self.level
+            {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            self.records.push_back(record.clone());
+        }
+        self.clamp_selection();
+    }
+
+    /// 跟随模式下选中最后一条日志，否则使选中项不超出范围。
+    fn clamp_selection(&mut self) {
+        let last = self.visible_len().saturating_sub(1);
+        self.selected = if self.follow {
+            last
+        } else {
+            self.selected.min(last)
+        };
+    }
+
+    /// 移动选中项，向上时停止跟随，移动到最后一条时重新跟随。
+    fn pick(&mut self, direction: Direction) {
+        let last = self.visible_len().saturating_sub(1);
+        match direction {
+            Direction::Up => {
+                self.selected = self.selected.saturating_sub(1);
+                self.follow = false;
+            }
+            Direction::Down => self.selected = (self.selected + 1).min(last),
+            Direction::Left | Direction::Right => return,
+        }
+        if self.selected == last {
+            self.follow = true;
+        }
+    }
+
+    /// 处理鼠标操作：点击选中日志，滚动滚轮移动选中项；选中最后一条时重新跟随。
+    fn mouse(&mut self, mouse: Mouse) -> EventResult {
+        let len = self.visible_len();
+        let Some(action) = list_mouse(mouse, self.rows.get(), self.offset.get(), len) else {
+            return EventResult::Ignored;
+        };
+        match action {
+            ListMouse::Select(index) | ListMouse::Play(index) => {
+                self.selected = index;
+                self.follow = index + 1 == len;
+            }
+            ListMouse::Scroll(direction) => self.pick(direction),
+        }
+        EventResult::Handled
+    }
+
+    /// 切换到下一个最低日志级别：全部 → 调试 → 信息 → 警告 → 错误 → 全部。
+    fn cycle_level(&mut self) {
+        self.level = match self.level {
+            LevelFilter::Off | LevelFilter::Trace => LevelFilter::Debug,
+            LevelFilter::Debug => LevelFilter::Info,
+            LevelFilter::Info => LevelFilter::Warn,
+            LevelFilter::Warn => LevelFilter::Error,
+            LevelFilter::Error => LevelFilter::Trace,
+        };
+        self.clamp_selection();
+    }
+
+    /// 切换是否跟随最新的日志。
+    fn toggle_follow(&mut self) {
+        self.follow = !self.follow;
+        self.clamp_selection();
+    }
+
+    /// 生成页面的标题，显示日志级别和跟随状态。
+    fn title(&self) -> String {
+        let level = match self.level {
+            LevelFilter::Off | LevelFilter::Trace => "all".to_string(),
+            level => format!("{}+", level.as_str().to_lowercase()),
+        };
+        let follow = if self.follow { " · following" } else { "" };
+        format!("Logs · {level}{follow}")
+    }
+
+    /// 生成一条日志的行。
+    fn record_row<'a>(&self, record: &'a LogRecord) -> Row<'a> {
+        let level_style = match record.level {
+            Level::Error => self.error_style,
+            Level::Warn => self.warn_style,
+            Level::Info => Style::default(),
+            Level::Debug | Level::Trace => self.dimmed,
+        };
+        Row::new([
+            TableCell::from(record.clock()).style(self.dimmed),
+            TableCell::from(record.level.as_str()).style(level_style),
+            TableCell::from(record.target.as_str()).style(self.dimmed),
+            TableCell::from(record.message.as_str()).style(level_style),
+        ])
+    }
+}
+
+impl RenderTui for LogsTui {
+    /// 渲染标题和日志，只为可见的行生成内容。
+    fn render(&self, frame: &mut Frame, rect: Rect) {
+        let block = Block::new().title(self.title()).title_style(self.header);
+        let inner = block.inner(rect);
+        frame.render_widget(block, rect);
+        self.rows.set(inner);
+
+        let len = self.visible_len();
+        if len == 0 {
+            let hint = Paragraph::new("No log messages")
+                .style(self.tui_style())
+                .alignment(self.tui_alignment());
+            frame.render_widget(hint, inner);
+            return;
+        }
+
+        let height = inner.height as usize;
+        let offset = scroll_offset(&self.offset, self.selected, len, height);
+        let rows: Vec<Row> = self
+            .visible()
+            .skip(offset)
+            .take(height)
+            .map(|record| self.record_row(record))
+            .collect();
+        let widths = [
+            Constraint::Length(12),
+            Constraint::Length(5),
+            Constraint::Length(24),
+            Constraint::Fill(1),
+        ];
+        let table = Table::new(rows, widths)
+            .column_spacing(1)
+            .style(self.tui_style())
+            .row_highlight_style(self.selected_style);
+        let mut state = TableState::default().with_selected(Some(self.selected - offset));
+        frame.render_stateful_widget(table, inner, &mut state);
+    }
+
+    fn as_event(&self) -> Option<&dyn TuiEventHandle> {
+        Some(self)
+    }
+
+    fn as_event_mut(&mut self) -> Option<&mut dyn TuiEventHandle> {
+        Some(self)
+    }
+
+    /// 重新设置页面的样式，警告和错误分别使用黄色和红色。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_tui_theme(theme);
+        self.header = Style::default().fg(theme.blue).add_modifier(Modifier::BOLD);
+        self.dimmed = Style::default().fg(theme.fg_dark);
+        self.warn_style = Style::default().fg(theme.yellow);
+        self.error_style = Style::default().fg(theme.red);
+        self.selected_style = Style::default().bg(theme.bg_highlight);
+    }
+}
+
+impl TuiEventHandle for LogsTui {
+    /// 处理 TUI 事件。
+    fn event_handle(&mut self, event: TuiEnent) -> EventResult {
+        match event {
+            TuiEnent::Logs(records) => self.push_records(&records),
+            TuiEnent::Picker(direction) => self.pick(direction),
+            TuiEnent::CycleLevel => self.cycle_level(),
+            TuiEnent::ToggleFollow => self.toggle_follow(),
+            TuiEnent::Clear => {
+                self.records.clear();
+                self.selected = 0;
+            }
+            TuiEnent::Mouse(mouse) => return self.mouse(mouse),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Handled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend, style::Color};
+    use std::{
+        borrow::Cow,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    fn record(level: Level, message: &str) -> LogRecord {
+        LogRecord {
+            time: UNIX_EPOCH + Duration::from_secs(3600),
+            ..LogRecord::new(level, "lazy_app::app", message)
+        }
+    }
+
+    fn logs_tui(records: &[LogRecord]) -> LogsTui {
+        let mut tui = LogsTui::default();
+        tui.event_handle(TuiEnent::Logs(Cow::Borrowed(records)));
+        tui
+    }
+
+    #[test]
+    fn test_logs_tui_follow_and_scroll() {
+        let mut tui = logs_tui(&[
+            record(Level::Info, "a"),
+            record(Level::Debug, "b"),
+            record(Level::Warn, "c"),
+        ]);
+        assert_eq!(tui.selected, 2);
+
+        // 向上移动停止跟随，新的日志不会改变选中项
+        tui.event_handle(TuiEnent::Picker(Direction::Up));
+        assert!(!tui.follow);
+        tui.event_handle(TuiEnent::Logs(Cow::Owned(vec![record(Level::Error, "d")])));
+        assert_eq!(tui.selected, 1);
+
+        // 移动到最后一条时重新跟随
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        assert!(tui.follow);
+        assert_eq!(tui.selected, 3);
+        tui.event_handle(TuiEnent::ToggleFollow);
+        assert!(!tui.follow);
+
+        // 超过容量时丢弃最早的日志，选中的日志不变
+        tui.capacity = 4;
+        tui.event_handle(TuiEnent::Picker(Direction::Up));
+        tui.event_handle(TuiEnent::Logs(Cow::Owned(vec![record(Level::Info, "e")])));
+        assert_eq!(tui.records.len(), 4);
+        assert_eq!(tui.visible().nth(tui.selected).unwrap().message, "c");
+    }
+
+    #[test]
+    fn test_logs_tui_level_filter() {
+        let mut tui = logs_tui(&[
+            record(Level::Info, "a"),
+            record(Level::Debug, "b"),
+            record(Level::Warn, "c"),
+            record(Level::Error, "d"),
+        ]);
+        let messages =
+            |tui: &LogsTui| -> Vec<String> { tui.visible().map(|r| r.message.clone()).collect() };
+        tui.event_handle(TuiEnent::CycleLevel);
+        assert_eq!(messages(&tui), ["a", "b", "c", "d"]);
+        tui.event_handle(TuiEnent::CycleLevel);
+        assert_eq!(messages(&tui), ["a", "c", "d"]);
+        tui.event_handle(TuiEnent::CycleLevel);
+        assert_eq!(messages(&tui), ["c", "d"]);
+        assert_eq!(tui.selected, 1);
+        tui.event_handle(TuiEnent::CycleLevel);
+        tui.event_handle(TuiEnent::CycleLevel);
+        assert_eq!(tui.level, LevelFilter::Trace);
+    }
+
+    #[test]
+    fn test_logs_tui_render() {
+        let backend = TestBackend::new(60, 4);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut tui = logs_tui(&[
+            record(Level::Info, "first"),
+            record(Level::Warn, "second"),
+            record(Level::Error, "third"),
+        ]);
+        tui.event_handle(TuiEnent::CycleLevel);
+        tui.event_handle(TuiEnent::CycleLevel);
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| -> String { (0..60).map(|x| buffer[(x, y)].symbol()).collect() };
+        assert!(line(0).starts_with("Logs · info+ · following"));
+        assert!(
+            line(1).starts_with("01:00:00.000 INFO  lazy_app::app") && line(1).contains("first")
+        );
+        assert!(line(2).contains("WARN ") && line(2).contains("second"));
+        let x = line(3).find("third").unwrap() as u16;
+        assert_eq!(buffer[(x, 3)].fg, Color::Rgb(255, 117, 127));
+
+        tui.event_handle(TuiEnent::Clear);
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(content.contains("No log messages"));
+    }
+}
@@ -0,0 +1,804 @@
+//! `PlaylistsTui` 模块，播放列表页。
+//!
+//! 左栏列出播放列表目录中的播放列表，右栏显示选中播放列表的内容，文件不存在的条目会被标记出来。
+//! 页面可以新建、重命名、删除播放列表，删除或移动其中的条目，把播放队列保存为播放列表，
+//! 以及把播放列表追加到队列或替换队列。所有文件操作都通过 `TuiAction` 请求应用程序执行。
+
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    path::{Path, PathBuf},
+};
+
+// 从 lazy_core 中导入 TuiStyle 结构体和 HasTuiStyle trait
+use lazy_core::{
+    structs::TuiStyle,
+    theme::TuiTheme,
+    traits::{HasTuiStyle, HasTuiStyleSetter},
+};
+// 从 lazy_library 中导入播放列表
+use lazy_library::playlist::{Playlist, PlaylistEntry, playlist_name};
+// 导入宏，用于自动派生 trait
+use lazy_macro::DeriveHasTuiStyle;
+// 从 ratatui 中导入所需的组件和布局
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Cell as TableCell, Paragraph, Row, Table, TableState},
+};
+
+// 从当前 crate 中导入所需的 traits 和类型
+use crate::{
+    traits::{RenderTui, TuiEventHandle},
+    types::{Direction, EnqueueMode, EventResult, Mouse, TextInput, TuiAction, TuiEnent},
+    views::{ListMouse, Prompt, PromptResult, format_duration, list_mouse, scroll_offset},
+};
+
+/// 播放列表页中的一栏。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pane {
+    Playlists, // 播放列表
+    Entries,   // 选中播放列表的条目
+}
+
+/// 等待用户输入的操作。
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Pending {
+    Create,          // 新建播放列表，输入名称
+    Rename(PathBuf), // 重命名播放列表，输入新名称
+    Delete(PathBuf), // 删除播放列表，输入 `y` 确认
+    SaveQueue,       // 将播放队列保存为播放列表，输入名称
+}
+
+/// `PlaylistsTui` 是播放列表页。
+#[derive(DeriveHasTuiStyle)]
+pub struct PlaylistsTui {
+    playlists: Vec<PathBuf>,           // 播放列表文件
+    playlist: Option<Playlist>,        // 选中播放列表的内容，读取完成前为 `None`
+    missing: Vec<usize>,               // 文件不存在的条目的索引
+    selected: usize,                   // 选中播放列表的索引
+    entry: usize,                      // 选中条目的索引
+    offsets: [Cell<usize>; 2],         // 两栏的滚动位置
+    rows: [Cell<Rect>; 2],             // 上一次渲染时两栏的行所在的区域，用于鼠标点击
+    focus: Pane,                       // 当前操作的一栏
+    prompt: Option<(Pending, Prompt)>, // 正在输入的操作
+    follow: Option<String>,            // 下次收到播放列表时要选中的名称
+    actions: Vec<TuiAction>,           // 等待应用程序执行的操作
+    style: TuiStyle,                   // TUI 样式（颜色、对齐方式等）
+    header: Style,                     // 栏标题的样式
+    focused: Style,                    // 当前栏标题的样式
+    dimmed: Style,                     // 次要信息（格式、时长）的样式
+    missing_style: Style,              // 文件不存在的条目的样式
+    selected_style: Style,             // 当前栏选中行的样式
+    inactive_selected: Style,          // 其他栏选中行的样式
+}
+
+impl Default for PlaylistsTui {
+    /// 创建一个默认的 `PlaylistsTui` 实例。
+    fn default() -> Self {
+        let mut style = TuiStyle::default();
+        style.set_alignment(Alignment::Center);
+        style.set_modifier(Modifier::empty());
+        let mut playlists = Self {
+            playlists: Vec::new(),
+            playlist: None,
+            missing: Vec::new(),
+            selected: 0,
+            entry: 0,
+            offsets: Default::default(),
+            rows: Default::default(),
+            focus: Pane::Playlists,
+            prompt: None,
+            follow: None,
+            actions: Vec::new(),
+            style,
+            header: Style::default(),
+            focused: Style::default(),
+            dimmed: Style::default(),
+            missing_style: Style::default(),
+            selected_style: Style::default(),
+            inactive_selected: Style::default(),
+        };
+        playlists.apply_theme(&TuiTheme::current());
+        playlists
+    }
+}
+
+impl PlaylistsTui {
+    /// 获取选中的播放列表文件。
+    fn selected_path(&self) -> Option<&PathBuf> {
+        self.playlists.get(self.selected)
+    }
+
+    /// 获取已读取的选中播放列表。
+    fn loaded(&self) -> Option<&Playlist> {
+        self.playlist
+            .as_ref()
+            .filter(|p| Some(&p.path) == self.selected_path())
+    }
+
+    /// 获取已读取的选中播放列表的条目。
+    fn entries(&self) -> &[PlaylistEntry] {
+        self.loaded().map_or(&[], |p| &p.entries)
+    }
+
+    /// 更新播放列表文件，尽量保持原来的选中项，并请求读取选中的播放列表。
+    fn set_playlists(&mut self, playlists: &[PathBuf]) {
+        let previous = self.selected_path().cloned();
+        self.playlists = playlists.to_vec();
+        let follow = self.follow.take();
+        self.selected = follow
+            .and_then(|name| {
+                self.playlists.iter().position(|path| {
+                    path.file_name()
+                        .is_some_and(|n| n.to_string_lossy() == name)
+                        || playlist_name(path) == name
+                })
+            })
+            .or_else(|| previous.and_then(|p| self.playlists.iter().position(|q| *q == p)))
+            .unwrap_or(self.selected)
+            .min(self.playlists.len().saturating_sub(1));
+        if self.playlists.is_empty() {
+            self.focus = Pane::Playlists;
+        }
+        self.load_selected();
+    }
+
+    /// 选中的播放列表还没有读取时请求应用程序读取。
+    fn load_selected(&mut self) {
+        if self.loaded().is_none()
+            && let Some(path) = self.selected_path()
+        {
+            self.actions.push(TuiAction::LoadPlaylist(path.clone()));
+        }
+    }
+
+    /// 更新播放列表的内容，不是选中的播放列表时忽略。
+    fn set_playlist(&mut self, playlist: Playlist, missing: &[usize]) {
+        if Some(&playlist.path) != self.selected_path() {
+            return;
+        }
+        let reloaded = self
+            .playlist
+            .as_ref()
+            .is_some_and(|p| p.path == playlist.path);
+        self.entry = if reloaded {
+            self.entry.min(playlist.entries.len().saturating_sub(1))
+        } else {
+            0
+        };
+        self.playlist = Some(playlist);
+        self.missing = missing.to_vec();
+        if self.entries().is_empty() {
+            self.focus = Pane::Playlists;
+        }
+    }
+
+    /// 在当前栏中移动选中项，向右进入条目栏，向左回到播放列表栏。
+    fn pick(&mut self, direction: Direction) {
+        match (self.focus, direction) {
+            (Pane::Playlists, Direction::Up) if self.selected > 0 => {
+                self.selected -= 1;
+                self.load_selected();
+            }
+            (Pane::Playlists, Direction::Down) if self.selected + 1 < self.playlists.len() => {
+                self.selected += 1;
+                self.load_selected();
+            }
+            (Pane::Playlists, Direction::Right) if !self.entries().is_empty() => {
+                self.focus = Pane::Entries;
+            }
+            (Pane::Entries, Direction::Up) => self.entry = self.entry.saturating_sub(1),
+            (Pane::Entries, Direction::Down) if self.entry + 1 < self.entries().len() => {
+                self.entry += 1;
+            }
+            (Pane::Entries, Direction::Left) => self.focus = Pane::Playlists,
+            _ => (),
+        }
+    }
+
+    /// 处理鼠标操作：鼠标所在的栏成为当前栏，点击选中一项，双击播放（与 Enter 相同），
+    /// 滚动滚轮移动选中项。
+    fn mouse(&mut self, mouse: Mouse) -> EventResult {
+        for pane in [Pane::Playlists, Pane::Entries] {
+            let len = match pane {
+                Pane::Playlists => self.playlists.len(),
+                Pane::Entries => self.entries().len(),
+            };
+            let (rows, offset) = (
+                self.rows[pane as usize].get(),
+                self.offsets[pane as usize].get(),
+            );
+            let Some(action) = list_mouse(mouse, rows, offset, len) else {
+                continue;
+            };
+            self.focus = pane;
+            match action {
+                ListMouse::Select(index) | ListMouse::Play(index) => match pane {
+                    Pane::Playlists if index != self.selected => {
+                        self.selected = index;
+                        self.load_selected();
+                    }
+                    Pane::Playlists => (),
+                    Pane::Entries => self.entry = index,
+                },
+                ListMouse::Scroll(direction) => self.pick(direction),
+            }
+            if let ListMouse::Play(_) = action {
+                let mode = match pane {
+                    Pane::Playlists => EnqueueMode::Replace,
+                    Pane::Entries => EnqueueMode::Play,
+                };
+                self.enqueue(mode);
+            }
+            return EventResult::Handled;
+        }
+        EventResult::Ignored
+    }
+
+    /// 请求应用程序把选中的播放列表或条目加入队列，文件不存在的条目会被跳过。
+    fn enqueue(&mut self, mode: EnqueueMode) {
+        let indices: Vec<usize> = match self.focus {
+            Pane::Playlists => (0..self.entries().len()).collect(),
+            Pane::Entries => vec![self.entry],
+        };
+        let paths: Vec<PathBuf> = indices
+            .into_iter()
+            .filter(|i| !self.missing.contains(i))
+            .filter_map(|i| self.entries().get(i).map(|e| e.path.clone()))
+            .collect();
+        if !paths.is_empty() {
+            self.actions.push(TuiAction::Enqueue(paths, mode));
+        }
+    }
+
+    /// 修改选中的播放列表并请求应用程序保存。
+    fn edit(&mut self, edit: impl FnOnce(&mut Vec<PlaylistEntry>, &mut usize) -> bool) {
+        let Some(playlist) = self
+            .playlist
+            .as_mut()
+            .filter(|p| Some(&p.path) == self.playlists.get(self.selected))
+        else {
+            return;
+        };
+        if edit(&mut playlist.entries, &mut self.entry) {
+            self.actions.push(TuiAction::SavePlaylist(playlist.clone()));
+        }
+    }
+
+    /// 删除选中的条目，或在播放列表栏中请求确认删除播放列表。
+    fn delete(&mut self) {
+        match self.focus {
+            Pane::Playlists => {
+                if let Some(path) = self.selected_path().cloned() {
+                    let label = format!("Delete {}? (y/n) ", playlist_name(&path));
+                    self.prompt = Some((Pending::Delete(path), Prompt::new(label, "")));
+                }
+            }
+            Pane::Entries => {
+                let index = self.entry;
+                self.edit(|entries, selected| {
+                    if index >= entries.len() {
+                        return false;
+                    }
+                    entries.remove(index);
+                    *selected = index.min(entries.len().saturating_sub(1));
+                    true
+                });
+                // 删除后的条目索引发生变化，等待重新读取时更新
+                self.missing.retain(|&i| i != index);
+                self.missing
+                    .iter_mut()
+                    .filter(|i| **i > index)
+                    .for_each(|i| *i -= 1);
+                if self.entries().is_empty() {
+                    self.focus = Pane::Playlists;
+                }
+            }
+        }
+    }
+
+    /// 将选中的条目上移或下移。
+    fn move_entry(&mut self, direction: Direction) {
+        if self.focus != Pane::Entries {
+            return;
+        }
+        let index = self.entry;
+        let target = match direction {
+            Direction::Up => index.checked_sub(1),
+            Direction::Down => Some(index + 1),
+            Direction::Left | Direction::Right => None,
+        };
+        let Some(target) = target.filter(|&t| t < self.entries().len()) else {
+            return;
+        };
+        self.edit(|entries, selected| {
+            entries.swap(index, target);
+            *selected = target;
+            true
+        });
+        for i in &mut self.missing {
+            if *i == index {
+                *i = target;
+            } else if *i == target {
+                *i = index;
+            }
+        }
+    }
+
+    /// 开始输入。
+    fn start_prompt(&mut self, pending: Pending) {
+        let prompt = match &pending {
+            Pending::Create => Prompt::new("New playlist: ", ""),
+            Pending::Rename(path) => Prompt::new("Rename to: ", playlist_name(path)),
+            Pending::SaveQueue => Prompt::new("Save queue as: ", ""),
+            Pending::Delete(_) => return,
+        };
+        self.prompt = Some((pending, prompt));
+    }
+
+    /// 处理输入框中的按键，确认时请求应用程序执行对应的操作。
+    fn text_input(&mut self, input: TextInput) {
+        let Some((pending, prompt)) = &mut self.prompt else {
+            return;
+        };
+        // 确认删除只需要一个按键
+        if let Pending::Delete(path) = pending {
+            if input == TextInput::Char('y') || input == TextInput::Char('Y') {
+                self.actions.push(TuiAction::DeletePlaylist(path.clone()));
+            }
+            self.prompt = None;
+            return;
+        }
+        let text = match prompt.input(input) {
+            PromptResult::Editing => return,
+            PromptResult::Cancel => {
+                self.prompt = None;
+                return;
+            }
+            PromptResult::Submit(text) => text.trim().to_string(),
+        };
+        let Some((pending, _)) = self.prompt.take() else {
+            return;
+        };
+        if text.is_empty() {
+            return;
+        }
+        self.follow = Some(text.clone());
+        self.actions.push(match pending {
+            Pending::Create => TuiAction::CreatePlaylist(text),
+            Pending::Rename(path) => TuiAction::RenamePlaylist(path, text),
+            Pending::SaveQueue => TuiAction::SaveQueueAsPlaylist(text),
+            Pending::Delete(_) => unreachable!("删除已在上面处理"),
+        });
+    }
+
+    /// 生成播放列表栏中的一行。
+    fn playlist_row(&self, path: &Path) -> Row<'_> {
+        let format = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        Row::new([
+            TableCell::from(playlist_name(path).into_owned()),
+            TableCell::from(format).style(self.dimmed),
+        ])
+    }
+
+    /// 生成条目栏中的一行。
+    fn entry_row(&self, index: usize) -> Row<'_> {
+        let entry = &self.entries()[index];
+        let title = match &entry.title {
+            Some(title) => Cow::Borrowed(title.as_str()),
+            None => entry
+                .path
+                .file_name()
+                .map_or(Cow::Borrowed(""), |name| name.to_string_lossy()),
+        };
+        let row = Row::new([
+            TableCell::from(format!("{:>2}", index + 1)).style(self.dimmed),
+            TableCell::from(title),
+            TableCell::from(entry.duration.map(format_duration).unwrap_or_default())
+                .style(self.dimmed),
+        ]);
+        if self.missing.contains(&index) {
+            row.style(self.missing_style)
+        } else {
+            row
+        }
+    }
+
+    /// 渲染一栏，只为可见的行生成内容。
+    fn render_pane(&self, frame: &mut Frame, pane: Pane, rect: Rect) {
+        let is_focused = pane == self.focus;
+        let title = match pane {
+            Pane::Playlists => "Playlists".to_string(),
+            Pane::Entries if self.missing.is_empty() => "Tracks".to_string(),
+            Pane::Entries => format!("Tracks · {} missing", self.missing.len()),
+        };
+        let block = Block::new().title(title).title_style(if is_focused {
+            self.focused
+        } else {
+            self.header
+        });
+        let inner = block.inner(rect);
+        frame.render_widget(block, rect);
+
+        let (len, selected, widths) = match pane {
+            Pane::Playlists => (
+                self.playlists.len(),
+                self.selected,
+                vec![Constraint::Fill(1), Constraint::Length(4)],
+            ),
+            Pane::Entries => (
+                self.entries().len(),
+                self.entry,
+                vec![
+                    Constraint::Length(2),
+                    Constraint::Fill(1),
+                    Constraint::Length(8),
+                ],
+            ),
+        };
+        self.rows[pane as usize].set(inner);
+        let height = inner.height as usize;
+        let offset = scroll_offset(&self.offsets[pane as usize], selected, len, height);
+        let end = (offset + height).min(len);
+        let rows: Vec<Row> = match pane {
+            Pane::Playlists => self.playlists[offset..end]
+                .iter()
+                .map(|path| self.playlist_row(path))
+                .collect(),
+            Pane::Entries => (offset..end).map(|i| self.entry_row(i)).collect(),
+        };
+        let table = Table::new(rows, widths)
+            .style(self.tui_style())
+            .row_highlight_style(if is_focused {
+                self.selected_style
+            } else {
+                self.inactive_selected
+            });
+        let mut state = TableState::default().with_selected((len > 0).then(|| selected - offset));
+        frame.render_stateful_widget(table, inner, &mut state);
+    }
+}
+
+impl RenderTui for PlaylistsTui {
+    /// 并排渲染播放列表和选中播放列表的内容，正在输入时在底部显示输入框。
+    fn render(&self, frame: &mut Frame, rect: Rect) {
+        let [main, prompt_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(u16::from(self.prompt.is_some())),
+        ])
+        .areas(rect);
+
+        if self.playlists.is_empty() {
+            let hint = Paragraph::new("No playlists · n: new playlist · S: save queue")
+                .style(self.tui_style())
+                .alignment(self.tui_alignment());
+            frame.render_widget(hint, main);
+        } else {
+            let [left, right] =
+                Layout::horizontal([Constraint::Percentage(35), Constraint::Fill(1)])
+                    .spacing(1)
+                    .areas(main);
+            self.render_pane(frame, Pane::Playlists, left);
+            self.render_pane(frame, Pane::Entries, right);
+        }
+
+        if let Some((_, prompt)) = &self.prompt {
+            let line = Paragraph::new(prompt.line(self.focused)).style(self.tui_style());
+            frame.render_widget(line, prompt_area);
+        }
+    }
+
+    fn as_event(&self) -> Option<&dyn TuiEventHandle> {
+        Some(self)
+    }
+
+    fn as_event_mut(&mut self) -> Option<&mut dyn TuiEventHandle> {
+        Some(self)
+    }
+
+    /// 重新设置页面的样式。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_tui_theme(theme);
+        self.header = Style::default().fg(theme.fg_dark);
+        self.focused = Style::default().fg(theme.blue).add_modifier(Modifier::BOLD);
+        self.dimmed = Style::default().fg(theme.fg_dark);
+        self.missing_style = Style::default()
+            .fg(theme.dark3)
+            .add_modifier(Modifier::CROSSED_OUT);
+        self.selected_style = Style::default().bg(theme.bg_highlight);
+        self.inactive_selected = Style::default().bg(theme.bg_dark);
+    }
+}
+
+impl TuiEventHandle for PlaylistsTui {
+    /// 处理 TUI 事件。
+    fn event_handle(&mut self, event: TuiEnent) -> EventResult {
+        match event {
+            TuiEnent::Playlists(playlists) => self.set_playlists(&playlists),
+            TuiEnent::PlaylistLoaded(playlist, missing) => {
+                self.set_playlist(playlist.into_owned(), &missing)
+            }
+            TuiEnent::TextInput(input) => self.text_input(input),
+            // 正在输入时忽略其他输入事件
+            _ if self.prompt.is_some() => (),
+            TuiEnent::Picker(direction) => self.pick(direction),
+            TuiEnent::Parent => self.focus = Pane::Playlists,
+            TuiEnent::PlaySelected if self.focus == Pane::Playlists => {
+                self.enqueue(EnqueueMode::Replace)
+            }
+            TuiEnent::PlaySelected => self.enqueue(EnqueueMode::Play),
+            TuiEnent::AddSelected => self.enqueue(EnqueueMode::Append),
+            TuiEnent::ReplaceWithSelected => self.enqueue(EnqueueMode::Replace),
+            TuiEnent::DeleteSelected => self.delete(),
+            TuiEnent::MoveSelected(direction) => self.move_entry(direction),
+            TuiEnent::Create => self.start_prompt(Pending::Create),
+            TuiEnent::Rename => {
+                if let Some(path) = self.selected_path().cloned() {
+                    self.start_prompt(Pending::Rename(path));
+                }
+            }
+            TuiEnent::SaveQueue => self.start_prompt(Pending::SaveQueue),
+            TuiEnent::Mouse(mouse) => return self.mouse(mouse),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Handled
+    }
+
+    /// 在两栏之间移动焦点，与左右方向键相同。
+    fn shift_focus(&mut self, direction: Direction) -> bool {
+        let focus = self.focus;
+        if matches!(direction, Direction::Left | Direction::Right) {
+            self.pick(direction);
+        }
+        self.focus != focus
+    }
+
+    /// 焦点移入时从播放列表栏，或者从有内容的条目栏开始。
+    fn enter_focus(&mut self, direction: Direction) {
+        self.focus = match direction {
+            Direction::Left if !self.entries().is_empty() => Pane::Entries,
+            _ => Pane::Playlists,
+        };
+    }
+
+    /// 正在输入名称或确认删除时返回 `true`。
+    fn is_editing(&self) -> bool {
+        self.prompt.is_some()
+    }
+
+    /// 取出等待应用程序执行的操作。
+    fn take_actions(&mut self) -> Vec<TuiAction> {
+        std::mem::take(&mut self.actions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MouseKind;
+    use ratatui::{Terminal, backend::TestBackend};
+    use std::time::Duration;
+
+    fn paths(paths: &[&str]) -> Vec<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    fn playlist(path: &str, entries: &[&str]) -> Playlist {
+        Playlist {
+            path: PathBuf::from(path),
+            entries: entries
+                .iter()
+                .map(|e| PlaylistEntry {
+                    duration: Some(Duration::from_secs(90)),
+                    ..PlaylistEntry::new(e)
+                })
+                .collect(),
+        }
+    }
+
+    /// 模拟应用程序：处理 `LoadPlaylist` 请求，其余操作原样返回。
+    fn respond(tui: &mut PlaylistsTui) -> Vec<TuiAction> {
+        let mut other = Vec::new();
+        for action in tui.take_actions() {
+            match action {
+                TuiAction::LoadPlaylist(path) => {
+                    let playlist = match path.to_str().unwrap() {
+                        "/p/A.m3u8" => {
+                            playlist("/p/A.m3u8", &["/m/1.flac", "/m/2.flac", "/m/3.flac"])
+                        }
+                        _ => playlist(path.to_str().unwrap(), &[]),
+                    };
+                    tui.event_handle(TuiEnent::PlaylistLoaded(
+                        Cow::Owned(playlist),
+                        Cow::Owned(vec![1]),
+                    ));
+                }
+                action => other.push(action),
+            }
+        }
+        other
+    }
+
+    fn playlists_tui() -> PlaylistsTui {
+        let mut tui = PlaylistsTui::default();
+        let list = paths(&["/p/A.m3u8", "/p/B.pls"]);
+        tui.event_handle(TuiEnent::Playlists(Cow::Borrowed(&list)));
+        assert!(respond(&mut tui).is_empty());
+        tui
+    }
+
+    fn type_text(tui: &mut PlaylistsTui, text: &str) {
+        for c in text.chars() {
+            tui.event_handle(TuiEnent::TextInput(TextInput::Char(c)));
+        }
+    }
+
+    #[test]
+    fn test_playlists_tui_load_and_enqueue() {
+        let mut tui = playlists_tui();
+        assert_eq!(tui.entries().len(), 3);
+
+        // 播放列表栏中 Enter 替换队列，文件不存在的条目被跳过
+        tui.event_handle(TuiEnent::PlaySelected);
+        tui.event_handle(TuiEnent::AddSelected);
+        tui.event_handle(TuiEnent::Picker(Direction::Right));
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        tui.event_handle(TuiEnent::PlaySelected);
+        assert_eq!(
+            respond(&mut tui),
+            vec![
+                TuiAction::Enqueue(paths(&["/m/1.flac", "/m/3.flac"]), EnqueueMode::Replace),
+                TuiAction::Enqueue(paths(&["/m/1.flac", "/m/3.flac"]), EnqueueMode::Append),
+                TuiAction::Enqueue(paths(&["/m/3.flac"]), EnqueueMode::Play),
+            ]
+        );
+
+        // 切换到另一个播放列表时请求读取
+        tui.event_handle(TuiEnent::Picker(Direction::Left));
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        assert_eq!(
+            tui.take_actions(),
+            vec![TuiAction::LoadPlaylist("/p/B.pls".into())]
+        );
+    }
+
+    #[test]
+    fn test_playlists_tui_edit_entries() {
+        let mut tui = playlists_tui();
+        tui.event_handle(TuiEnent::Picker(Direction::Right));
+        tui.event_handle(TuiEnent::MoveSelected(Direction::Down));
+        assert_eq!(tui.entry, 1);
+        assert_eq!(tui.missing, vec![0], "不存在的条目随之移动");
+        tui.event_handle(TuiEnent::DeleteSelected);
+        let actions = respond(&mut tui);
+        assert_eq!(
+            actions,
+            vec![
+                TuiAction::SavePlaylist(playlist(
+                    "/p/A.m3u8",
+                    &["/m/2.flac", "/m/1.flac", "/m/3.flac"]
+                )),
+                TuiAction::SavePlaylist(playlist("/p/A.m3u8", &["/m/2.flac", "/m/3.flac"])),
+            ]
+        );
+        assert_eq!(tui.entry, 1);
+    }
+
+    #[test]
+    fn test_playlists_tui_prompts() {
+        let mut tui = playlists_tui();
+        tui.event_handle(TuiEnent::Create);
+        assert!(tui.is_editing());
+        type_text(&mut tui, "Mix");
+        tui.event_handle(TuiEnent::PlaySelected);
+        tui.event_handle(TuiEnent::TextInput(TextInput::Submit));
+        assert!(!tui.is_editing());
+
+        tui.event_handle(TuiEnent::Rename);
+        assert_eq!(tui.prompt.as_ref().unwrap().1.text(), "A");
+        tui.event_handle(TuiEnent::TextInput(TextInput::Backspace));
+        type_text(&mut tui, "Best");
+        tui.event_handle(TuiEnent::TextInput(TextInput::Submit));
+
+        tui.event_handle(TuiEnent::SaveQueue);
+        type_text(&mut tui, "Later");
+        tui.event_handle(TuiEnent::TextInput(TextInput::Cancel));
+
+        tui.event_handle(TuiEnent::DeleteSelected);
+        type_text(&mut tui, "n");
+        tui.event_handle(TuiEnent::DeleteSelected);
+        type_text(&mut tui, "y");
+        assert_eq!(
+            tui.take_actions(),
+            vec![
+                TuiAction::CreatePlaylist("Mix".to_string()),
+                TuiAction::RenamePlaylist("/p/A.m3u8".into(), "Best".to_string()),
+                TuiAction::DeletePlaylist("/p/A.m3u8".into()),
+            ]
+        );
+
+        // 新的播放列表出现后选中它
+        tui.follow = Some("Mix".to_string());
+        let list = paths(&["/p/A.m3u8", "/p/B.pls", "/p/Mix.m3u8"]);
+        tui.event_handle(TuiEnent::Playlists(Cow::Borrowed(&list)));
+        assert_eq!(tui.selected, 2);
+    }
+
+    #[test]
+    fn test_playlists_tui_focus_path() {
+        let mut tui = playlists_tui();
+        assert!(tui.shift_focus(Direction::Right));
+        assert_eq!(tui.focus, Pane::Entries);
+        assert!(!tui.shift_focus(Direction::Right));
+        assert!(tui.shift_focus(Direction::Left));
+        assert!(!tui.shift_focus(Direction::Left));
+
+        tui.enter_focus(Direction::Left);
+        assert_eq!(tui.focus, Pane::Entries);
+        tui.enter_focus(Direction::Right);
+        assert_eq!(tui.focus, Pane::Playlists);
+    }
+
+    #[test]
+    fn test_playlists_tui_mouse() {
+        let mut terminal = Terminal::new(TestBackend::new(60, 6)).unwrap();
+        let mut tui = playlists_tui();
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+        let mouse = |kind, column, row| TuiEnent::Mouse(Mouse { kind, column, row });
+
+        // 右栏从第 22 列开始，第 0 行是标题
+        tui.event_handle(mouse(MouseKind::DoubleClick, 30, 3));
+        assert_eq!(tui.focus, Pane::Entries);
+        assert_eq!(
+            respond(&mut tui),
+            vec![TuiAction::Enqueue(paths(&["/m/3.flac"]), EnqueueMode::Play)]
+        );
+
+        tui.event_handle(mouse(MouseKind::Click, 1, 2));
+        assert_eq!(tui.focus, Pane::Playlists);
+        assert_eq!(tui.selected, 1);
+        assert_eq!(
+            tui.take_actions(),
+            vec![TuiAction::LoadPlaylist("/p/B.pls".into())]
+        );
+    }
+
+    #[test]
+    fn test_playlists_tui_render() {
+        let backend = TestBackend::new(60, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut tui = playlists_tui();
+        tui.event_handle(TuiEnent::Create);
+        type_text(&mut tui, "New");
+
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| -> String { (0..60).map(|x| buffer[(x, y)].symbol()).collect() };
+        assert!(line(0).starts_with("Playlists"));
+        assert!(line(0).contains("Tracks · 1 missing"));
+        assert!(line(1).starts_with("A") && line(1).contains("m3u8"));
+        // 第二个条目的文件不存在
+        let x = line(2).find("2.flac").unwrap() as u16;
+        assert!(buffer[(x, 2)].modifier.contains(Modifier::CROSSED_OUT));
+        assert!(!buffer[(x, 1)].modifier.contains(Modifier::CROSSED_OUT));
+        assert!(line(5).starts_with("New playlist: New"));
+
+        let empty = PlaylistsTui::default();
+        terminal.draw(|f| empty.render(f, f.area())).unwrap();
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(content.contains("No playlists"));
+    }
+}
@@ -0,0 +1,85 @@
+//! `Prompt` 模块，页面底部的单行文本输入框。
+//!
+//! 页面在需要输入名称或确认操作时创建 `Prompt`，把收到的 `TuiEnent::TextInput` 交给它处理，
+//! 并在 `TuiEventHandle::is_editing` 中报告输入状态，使应用程序把字符键作为文本发送。
+
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::types::TextInput;
+
+/// 处理一次按键后输入框的状态。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum PromptResult {
+    /// 仍在输入
+    Editing,
+    /// 确认输入，附带输入的文本
+    Submit(String),
+    /// 取消输入
+    Cancel,
+}
+
+/// 单行文本输入框。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Prompt {
+    label: String, // 输入框前的提示文字
+    text: String,  // 已输入的文本
+}
+
+impl Prompt {
+    /// 创建一个输入框，`text` 是初始文本（例如重命名时的原名称）。
+    pub(crate) fn new(label: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            text: text.into(),
+        }
+    }
+
+    /// 获取已输入的文本。
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// 处理一次按键。
+    pub(crate) fn input(&mut self, input: TextInput) -> PromptResult {
+        match input {
+            TextInput::Char(c) => self.text.push(c),
+            TextInput::Backspace => {
+                self.text.pop();
+            }
+            TextInput::Submit => return PromptResult::Submit(self.text.clone()),
+            TextInput::Cancel => return PromptResult::Cancel,
+        }
+        PromptResult::Editing
+    }
+
+    /// 生成输入框的一行，末尾显示光标。
+    pub(crate) fn line(&self, style: Style) -> Line<'_> {
+        Line::from(vec![
+            Span::styled(self.label.as_str(), style.add_modifier(Modifier::BOLD)),
+            Span::styled(self.text.as_str(), style),
+            Span::styled("▏", style),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_input() {
+        let mut prompt = Prompt::new("Name: ", "ab");
+        assert_eq!(prompt.input(TextInput::Backspace), PromptResult::Editing);
+        assert_eq!(prompt.input(TextInput::Char('ü')), PromptResult::Editing);
+        assert_eq!(prompt.text(), "aü");
+        assert_eq!(
+            prompt.input(TextInput::Submit),
+            PromptResult::Submit("aü".to_string())
+        );
+        assert_eq!(prompt.input(TextInput::Cancel), PromptResult::Cancel);
+        assert_eq!(prompt.line(Style::default()).to_string(), "Name: aü▏");
+    }
+}
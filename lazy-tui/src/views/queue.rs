@@ -0,0 +1,451 @@
+//! `QueueTui` 模块，播放队列页面。
+//!
+//! 以表格显示队列中的曲目，高亮正在播放的曲目，并支持选择、播放、删除、移动和清空。
+//! 队列本身由应用程序持有，页面按 `TuiEnent::Queue` 发送的变化维护自己的副本，
+//! 所有修改都通过 `TuiAction` 请求应用程序执行。
+
+use std::cell::Cell;
+
+// 从 lazy_core 中导入曲目、TuiStyle 结构体和 HasTuiStyle trait
+use lazy_core::{
+    structs::TuiStyle,
+    theme::TuiTheme,
+    track::Track,
+    traits::{HasTuiStyle, HasTuiStyleSetter},
+};
+// 导入宏，用于自动派生 trait
+use lazy_macro::DeriveHasTuiStyle;
+// 从 ratatui 中导入所需的组件和布局
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Cell as TableCell, Paragraph, Row, Table, TableState},
+};
+
+// 从当前 crate 中导入所需的 traits 和类型
+use crate::{
+    traits::{RenderTui, TuiEventHandle},
+    types::{Direction, EventResult, Mouse, QueueChange, TuiAction, TuiEnent},
+    views::{ListMouse, format_duration, list_mouse, scroll_offset},
+};
+
+/// `QueueTui` 是播放队列页面。
+#[derive(DeriveHasTuiStyle)]
+pub struct QueueTui {
+    tracks: Vec<Track>,      // 播放队列中的曲目
+    current: Option<usize>,  // 正在播放的曲目的索引
+    selected: usize,         // 选中曲目的索引
+    offset: Cell<usize>,     // 第一行可见曲目的索引，渲染时根据选中项调整
+    rows: Cell<Rect>,        // 上一次渲染时表格的行（不含表头）所在的区域，用于鼠标点击
+    actions: Vec<TuiAction>, // 等待应用程序执行的操作
+    style: TuiStyle,         // TUI 样式（颜色、对齐方式等）
+    header: Style,           // 表头样式
+    playing: Style,          // 正在播放的曲目的样式
+    selected_style: Style,   // 选中行的样式
+}
+
+impl Default for QueueTui {
+    /// 创建一个默认的 `QueueTui` 实例。
+    fn default() -> Self {
+        let mut style = TuiStyle::default();
+        style.set_alignment(Alignment::Right);
+        style.set_modifier(Modifier::empty());
+        let mut queue = Self {
+            tracks: Vec::new(),
+            current: None,
+            selected: 0,
+            offset: Cell::new(0),
+            rows: Cell::new(Rect::default()),
+            actions: Vec::new(),
+            style,
+            header: Style::default(),
+            playing: Style::default(),
+            selected_style: Style::default(),
+        };
+        queue.apply_theme(&TuiTheme::current());
+        queue
+    }
+}
+
+impl QueueTui {
+    /// 表格各列的标题。
+    const HEADER: [&str; 5] = ["#", "Title", "Artist", "Album", "Duration"];
+
+    /// 获取选中曲目的索引。
+    #[cfg(test)]
+    pub(crate) fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// 按应用程序发送的变化更新播放队列，选中项超出新队列的范围时移到最后一首。
+    fn apply_change(&mut self, change: QueueChange) {
+        let len = self.tracks.len();
+        match change {
+            QueueChange::Appended(tracks) => self.tracks.extend_from_slice(&tracks),
+            QueueChange::Removed(index) if index < len => {
+                self.tracks.remove(index);
+            }
+            QueueChange::Moved(from, to) if from < len && to < len => {
+                let track = self.tracks.remove(from);
+                self.tracks.insert(to, track);
+            }
+            QueueChange::Updated(tracks) => {
+                for (index, track) in tracks.iter() {
+                    if let Some(slot) = self.tracks.get_mut(*index) {
+                        slot.clone_from(track);
+                    }
+                }
+            }
+            QueueChange::Cleared => self.tracks.clear(),
+            QueueChange::Current(current) => self.current = current,
+            QueueChange::Removed(_) | QueueChange::Moved(..) => (),
+        }
+        self.selected = self.selected.min(self.tracks.len().saturating_sub(1));
+    }
+
+    /// 向上或向下移动选中项。
+    fn pick(&mut self, direction: Direction) {
+        match direction {
+            Direction::Up => self.selected = self.selected.saturating_sub(1),
+            Direction::Down if self.selected + 1 < self.tracks.len() => self.selected += 1,
+            _ => (),
+        }
+    }
+
+    /// 请求应用程序移动选中的曲目，选中项跟随曲目移动。
+    fn move_selected(&mut self, direction: Direction) {
+        let target = match direction {
+            Direction::Up => self.selected.checked_sub(1),
+            Direction::Down => Some(self.selected + 1).filter(|&i| i < self.tracks.len()),
+            Direction::Left | Direction::Right => None,
+        };
+        if let Some(target) = target {
+            self.actions
+                .push(TuiAction::MoveQueueIndex(self.selected, target));
+            self.selected = target;
+        }
+    }
+
+    /// 在队列不为空时请求应用程序对选中曲目执行操作。
+    fn request(&mut self, action: fn(usize) -> TuiAction) {
+        if !self.tracks.is_empty() {
+            self.actions.push(action(self.selected));
+        }
+    }
+
+    /// 处理鼠标操作：点击选中曲目，双击播放曲目，滚动滚轮移动选中项。
+    fn mouse(&mut self, mouse: Mouse) -> EventResult {
+        let len = self.tracks.len();
+        let Some(action) = list_mouse(mouse, self.rows.get(), self.offset.get(), len) else {
+            return EventResult::Ignored;
+        };
+        match action {
+            ListMouse::Select(index) => self.selected = index,
+            ListMouse::Play(index) => {
+                self.selected = index;
+                self.request(TuiAction::PlayQueueIndex);
+            }
+            ListMouse::Scroll(direction) => self.pick(direction),
+        }
+        EventResult::Handled
+    }
+
+    /// 生成表格中的一行。
+    fn row(&self, index: usize) -> Row<'_> {
+        let track = &self.tracks[index];
+        let row = Row::new([
+            TableCell::from((index + 1).to_string()),
+            TableCell::from(track.display_title()),
+            TableCell::from(track.artist.as_deref().unwrap_or_default()),
+            TableCell::from(track.album.as_deref().unwrap_or_default()),
+            TableCell::from(track.duration.map(format_duration).unwrap_or_default()),
+        ]);
+        if self.current == Some(index) {
+            row.style(self.playing)
+        } else {
+            row
+        }
+    }
+
+    /// 生成底部的统计信息，例如 `12 tracks · 45:12`。
+    fn footer(&self) -> String {
+        format!(
+            "{} tracks · {}",
+            self.tracks.len(),
+            format_duration(self.tracks.iter().filter_map(|t| t.duration).sum())
+        )
+    }
+}
+
+impl RenderTui for QueueTui {
+    /// 渲染播放队列表格和底部的总时长。
+    fn render(&self, frame: &mut Frame, rect: Rect) {
+        let [table_area, footer_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(rect);
+
+        // 只为可见的行生成表格内容，队列很长时也不会拖慢渲染
+        let height = table_area.height.saturating_sub(1) as usize;
+        let offset = scroll_offset(&self.offset, self.selected, self.tracks.len(), height);
+        let end = (offset + height).min(self.tracks.len());
+        let rows = (offset..end).map(|i| self.row(i));
+        self.rows.set(Rect {
+            y: table_area.y + 1,
+            height: table_area.height.saturating_sub(1),
+            ..table_area
+        });
+
+        let number_width = self.tracks.len().max(1).to_string().len() as u16;
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(number_width),
+                Constraint::Fill(3),
+                Constraint::Fill(2),
+                Constraint::Fill(2),
+                Constraint::Length(8),
+            ],
+        )
+        .header(Row::new(Self::HEADER).style(self.header))
+        .style(self.tui_style())
+        .row_highlight_style(self.selected_style);
+
+        let mut state = TableState::default()
+            .with_selected((!self.tracks.is_empty()).then(|| self.selected - offset));
+        frame.render_stateful_widget(table, table_area, &mut state);
+
+        let footer = Paragraph::new(self.footer())
+            .style(self.tui_style())
+            .alignment(self.tui_alignment());
+        frame.render_widget(footer, footer_area);
+    }
+
+    fn as_event_mut(&mut self) -> Option<&mut dyn TuiEventHandle> {
+        Some(self)
+    }
+
+    /// 重新设置页面的样式，正在播放的曲目使用绿色。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_tui_theme(theme);
+        self.header = Style::default()
+            .fg(theme.fg_dark)
+            .add_modifier(Modifier::BOLD);
+        self.playing = Style::default()
+            .fg(theme.green)
+            .add_modifier(Modifier::BOLD);
+        self.selected_style = Style::default().bg(theme.bg_highlight);
+    }
+}
+
+impl TuiEventHandle for QueueTui {
+    /// 处理 TUI 事件。
+    fn event_handle(&mut self, event: TuiEnent) -> EventResult {
+        match event {
+            TuiEnent::Queue(change) => self.apply_change(change),
+            TuiEnent::Picker(direction) => self.pick(direction),
+            TuiEnent::PlaySelected => self.request(TuiAction::PlayQueueIndex),
+            TuiEnent::DeleteSelected => self.request(TuiAction::RemoveQueueIndex),
+            TuiEnent::MoveSelected(direction) => self.move_selected(direction),
+            TuiEnent::Clear if !self.tracks.is_empty() => self.actions.push(TuiAction::ClearQueue),
+            TuiEnent::Mouse(mouse) => return self.mouse(mouse),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Handled
+    }
+
+    /// 取出等待应用程序执行的操作。
+    fn take_actions(&mut self) -> Vec<TuiAction> {
+        std::mem::take(&mut self.actions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MouseKind;
+    use lazy_core::{queue::Queue, types::PlaybackMode};
+    use ratatui::{Terminal, backend::TestBackend};
+    use std::{borrow::Cow, time::Duration};
+
+    fn tracks(len: usize) -> Vec<Track> {
+        (0..len)
+            .map(|i| Track {
+                title: Some(format!("Song {i}")),
+                artist: Some("Artist".to_string()),
+                duration: Some(Duration::from_secs(60)),
+                ..Track::new(format!("{i}.flac"))
+            })
+            .collect()
+    }
+
+    fn send(tui: &mut QueueTui, change: QueueChange) {
+        tui.event_handle(TuiEnent::Queue(change));
+    }
+
+    fn queue_tui(len: usize) -> QueueTui {
+        let mut tui = QueueTui::default();
+        send(&mut tui, QueueChange::Appended(Cow::Owned(tracks(len))));
+        tui
+    }
+
+    #[test]
+    fn test_queue_tui_picker_and_actions() {
+        let mut tui = queue_tui(3);
+        tui.event_handle(TuiEnent::Picker(Direction::Up));
+        assert_eq!(tui.selected(), 0);
+        for _ in 0..5 {
+            tui.event_handle(TuiEnent::Picker(Direction::Down));
+        }
+        assert_eq!(tui.selected(), 2, "选中项不会超出队列");
+
+        tui.event_handle(TuiEnent::PlaySelected);
+        tui.event_handle(TuiEnent::MoveSelected(Direction::Down));
+        tui.event_handle(TuiEnent::MoveSelected(Direction::Up));
+        tui.event_handle(TuiEnent::DeleteSelected);
+        tui.event_handle(TuiEnent::Clear);
+        assert_eq!(
+            tui.take_actions(),
+            vec![
+                TuiAction::PlayQueueIndex(2),
+                TuiAction::MoveQueueIndex(2, 1),
+                TuiAction::RemoveQueueIndex(1),
+                TuiAction::ClearQueue,
+            ]
+        );
+        assert!(tui.take_actions().is_empty());
+    }
+
+    #[test]
+    fn test_queue_tui_clamps_selection() {
+        let mut tui = queue_tui(5);
+        for _ in 0..4 {
+            tui.event_handle(TuiEnent::Picker(Direction::Down));
+        }
+        for index in (2..5).rev() {
+            send(&mut tui, QueueChange::Removed(index));
+        }
+        assert_eq!(tui.selected(), 1);
+
+        send(&mut tui, QueueChange::Cleared);
+        tui.event_handle(TuiEnent::PlaySelected);
+        tui.event_handle(TuiEnent::DeleteSelected);
+        tui.event_handle(TuiEnent::Clear);
+        assert!(tui.take_actions().is_empty(), "空队列不产生操作");
+    }
+
+    #[test]
+    fn test_queue_tui_render() {
+        let backend = TestBackend::new(60, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut tui = queue_tui(10);
+        send(&mut tui, QueueChange::Current(Some(1)));
+        for _ in 0..6 {
+            tui.event_handle(TuiEnent::Picker(Direction::Down));
+        }
+
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| -> String { (0..60).map(|x| buffer[(x, y)].symbol()).collect() };
+        assert!(line(0).starts_with("#"));
+        // 可见 4 行，选中第 7 首时滚动到第 4 首
+        assert!(line(1).contains("Song 3"));
+        assert!(line(4).contains("Song 6"));
+        assert!(line(5).trim_end().ends_with("10 tracks · 10:00"));
+        assert_eq!(tui.offset.get(), 3);
+    }
+
+    #[test]
+    fn test_queue_tui_mouse() {
+        let mut terminal = Terminal::new(TestBackend::new(60, 6)).unwrap();
+        let mut tui = queue_tui(10);
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+        let mouse = |kind, row| {
+            TuiEnent::Mouse(Mouse {
+                kind,
+                column: 5,
+                row,
+            })
+        };
+
+        // 第 0 行是表头，第 3 行是第 3 首
+        tui.event_handle(mouse(MouseKind::Click, 3));
+        assert_eq!(tui.selected(), 2);
+        tui.event_handle(mouse(MouseKind::Click, 0));
+        tui.event_handle(mouse(MouseKind::ScrollDown, 1));
+        assert_eq!(tui.selected(), 3);
+        assert!(tui.take_actions().is_empty());
+
+        tui.event_handle(mouse(MouseKind::DoubleClick, 1));
+        assert_eq!(tui.selected(), 0);
+        assert_eq!(tui.take_actions(), vec![TuiAction::PlayQueueIndex(0)]);
+    }
+
+    #[test]
+    fn test_queue_tui_highlights_playing_track() {
+        let backend = TestBackend::new(60, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut tui = queue_tui(3);
+        send(&mut tui, QueueChange::Current(Some(2)));
+
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer[(3, 3)].fg, tui.playing.fg.unwrap());
+        assert_ne!(buffer[(3, 2)].fg, tui.playing.fg.unwrap());
+    }
+
+    #[test]
+    fn test_queue_tui_follows_queue_changes() {
+        let mut queue = Queue::new();
+        let mut tui = QueueTui::default();
+        let assert_same = |tui: &QueueTui, queue: &Queue| {
+            assert_eq!(tui.tracks, queue.tracks());
+            assert_eq!(tui.current, queue.current_index());
+        };
+
+        queue.extend(tracks(5));
+        send(
+            &mut tui,
+            QueueChange::Appended(Cow::Borrowed(queue.tracks())),
+        );
+        queue.select(3);
+        send(&mut tui, QueueChange::Current(queue.current_index()));
+        assert_same(&tui, &queue);
+
+        queue.move_track(3, 0);
+        send(&mut tui, QueueChange::Moved(3, 0));
+        queue.remove(2);
+        send(&mut tui, QueueChange::Removed(2));
+        send(&mut tui, QueueChange::Current(queue.current_index()));
+        assert_same(&tui, &queue);
+
+        // 消费模式下切换到下一首会移除当前曲目
+        queue.set_mode(PlaybackMode::Consume);
+        queue.next_track();
+        send(&mut tui, QueueChange::Removed(0));
+        send(&mut tui, QueueChange::Current(queue.current_index()));
+        assert_same(&tui, &queue);
+
+        let renamed = Track {
+            title: Some("Renamed".to_string()),
+            ..queue.tracks()[1].clone()
+        };
+        let updated = queue.refresh(|path| (path == renamed.path).then(|| renamed.clone()));
+        assert_eq!(updated, [1]);
+        send(
+            &mut tui,
+            QueueChange::Updated(Cow::Owned(vec![(1, renamed)])),
+        );
+        assert_same(&tui, &queue);
+
+        // 越界的变化被忽略
+        send(&mut tui, QueueChange::Removed(9));
+        send(&mut tui, QueueChange::Moved(0, 9));
+        assert_same(&tui, &queue);
+        queue.clear();
+        send(&mut tui, QueueChange::Cleared);
+        send(&mut tui, QueueChange::Current(None));
+        assert_same(&tui, &queue);
+    }
+}
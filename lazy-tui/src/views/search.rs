@@ -0,0 +1,479 @@
+//! `SearchTui` 模块，搜索页。
+//!
+//! 顶部是搜索框，每输入一个字符都会请求应用程序在后台搜索媒体库，结果按相关度列在下方。
+//! 搜索文本支持 `artist:`、`album:`、`genre:` 和 `year:1990..1999` 等字段筛选，
+//! 文本无法解析时在底部显示错误并保留上一次的结果。
+
+use std::cell::Cell;
+
+// 从 lazy_core 中导入曲目、TuiStyle 结构体和 HasTuiStyle trait
+use lazy_core::{
+    structs::TuiStyle,
+    theme::TuiTheme,
+    track::Track,
+    traits::{HasTuiStyle, HasTuiStyleSetter},
+};
+// 从 lazy_library 中导入搜索文本的解析和搜索结果
+use lazy_library::search::{SearchQuery, SearchResults};
+// 导入宏，用于自动派生 trait
+use lazy_macro::DeriveHasTuiStyle;
+// 从 ratatui 中导入所需的组件和布局
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Cell as TableCell, Paragraph, Row, Table, TableState},
+};
+
+// 从当前 crate 中导入所需的 traits 和类型
+use crate::{
+    traits::{RenderTui, TuiEventHandle},
+    types::{
+        Direction, EnqueueMode, EventResult, Mouse, MouseKind, TextInput, TuiAction, TuiEnent,
+    },
+    views::{ListMouse, Prompt, PromptResult, format_duration, list_mouse, scroll_offset},
+};
+
+/// 搜索框前的提示文字
+const LABEL: &str = "Search: ";
+
+/// 没有输入时底部显示的提示
+const HINT: &str = "artist: album: genre: year:1990..1999 · Esc: results · /: edit";
+
+/// `SearchTui` 是搜索页。
+#[derive(DeriveHasTuiStyle)]
+pub struct SearchTui {
+    prompt: Prompt,          // 搜索框
+    editing: bool,           // 是否正在输入搜索文本
+    query: String,           // 最近一次请求搜索的文本
+    searching: bool,         // 是否在等待搜索结果
+    error: Option<String>,   // 搜索文本的解析错误
+    results: SearchResults,  // 最近一次的搜索结果
+    selected: usize,         // 选中结果的索引
+    offset: Cell<usize>,     // 结果列表的滚动位置
+    prompt_area: Cell<Rect>, // 上一次渲染时搜索框所在的区域，用于鼠标点击
+    rows: Cell<Rect>,        // 上一次渲染时结果列表的行（不含表头）所在的区域
+    actions: Vec<TuiAction>, // 等待应用程序执行的操作
+    style: TuiStyle,         // TUI 样式（颜色、对齐方式等）
+    header: Style,           // 表头的样式
+    focused: Style,          // 正在输入时搜索框的样式
+    dimmed: Style,           // 次要信息（专辑、年份、时长、提示）的样式
+    error_style: Style,      // 解析错误的样式
+    selected_style: Style,   // 选中行的样式
+}
+
+impl Default for SearchTui {
+    /// 创建一个默认的 `SearchTui` 实例，打开页面即可输入。
+    fn default() -> Self {
+        let mut style = TuiStyle::default();
+        style.set_alignment(Alignment::Left);
+        style.set_modifier(Modifier::empty());
+        let mut search = Self {
+            prompt: Prompt::new(LABEL, ""),
+            editing: true,
+            query: String::new(),
+            searching: false,
+            error: None,
+            results: SearchResults::default(),
+            selected: 0,
+            offset: Cell::new(0),
+            prompt_area: Cell::new(Rect::default()),
+            rows: Cell::new(Rect::default()),
+            actions: Vec::new(),
+            style,
+            header: Style::default(),
+            focused: Style::default(),
+            dimmed: Style::default(),
+            error_style: Style::default(),
+            selected_style: Style::default(),
+        };
+        search.apply_theme(&TuiTheme::current());
+        search
+    }
+}
+
+impl SearchTui {
+    /// 处理搜索框中的按键，文本变化后请求搜索。
+    fn text_input(&mut self, input: TextInput) {
+        if !self.editing {
+            return;
+        }
+        match self.prompt.input(input) {
+            PromptResult::Editing => self.request_search(),
+            // 确认或 Esc 都保留搜索文本，转到结果列表
+            PromptResult::Submit(_) | PromptResult::Cancel => self.editing = false,
+        }
+    }
+
+    /// 解析搜索文本，可以解析且与上一次不同时请求应用程序搜索。
+    fn request_search(&mut self) {
+        let text = self.prompt.text().trim();
+        match SearchQuery::parse(text) {
+            Ok(query) => {
+                self.error = None;
+                if text != self.query {
+                    self.query = text.to_string();
+                    self.searching = !query.is_empty();
+                    self.actions.push(TuiAction::Search(self.query.clone()));
+                }
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    /// 更新搜索结果，忽略已被新的搜索取代的结果。
+    fn set_results(&mut self, results: SearchResults) {
+        if results.query != self.query {
+            return;
+        }
+        self.results = results;
+        self.searching = false;
+        self.selected = 0;
+        self.offset.set(0);
+    }
+
+    /// 移动选中的结果。
+    fn pick(&mut self, direction: Direction) {
+        let len = self.results.tracks.len();
+        match direction {
+            Direction::Up => self.selected = self.selected.saturating_sub(1),
+            Direction::Down if self.selected + 1 < len => self.selected += 1,
+            _ => (),
+        }
+    }
+
+    /// 将选中的曲目加入播放队列。
+    fn enqueue(&mut self, mode: EnqueueMode) {
+        if let Some(track) = self.results.tracks.get(self.selected) {
+            self.actions
+                .push(TuiAction::Enqueue(vec![track.path.clone()], mode));
+        }
+    }
+
+    /// 处理鼠标操作：点击搜索框开始输入，点击结果选中并转到结果列表，双击播放，滚动滚轮移动选中项。
+    fn mouse(&mut self, mouse: Mouse) -> EventResult {
+        if mouse.kind == MouseKind::Click && mouse.is_in(self.prompt_area.get()) {
+            self.editing = true;
+            return EventResult::Handled;
+        }
+        let len = self.results.tracks.len();
+        let Some(action) = list_mouse(mouse, self.rows.get(), self.offset.get(), len) else {
+            return EventResult::Ignored;
+        };
+        match action {
+            ListMouse::Select(index) => {
+                self.selected = index;
+                self.editing = false;
+            }
+            ListMouse::Play(index) => {
+                self.selected = index;
+                self.enqueue(EnqueueMode::Play);
+            }
+            ListMouse::Scroll(direction) => self.pick(direction),
+        }
+        EventResult::Handled
+    }
+
+    /// 生成结果列表中的一行。
+    fn track_row<'a>(&self, track: &'a Track) -> Row<'a> {
+        Row::new([
+            TableCell::from(track.display_title()),
+            TableCell::from(track.artist.as_deref().unwrap_or_default()),
+            TableCell::from(track.album.as_deref().unwrap_or_default()).style(self.dimmed),
+            TableCell::from(track.year.map(|y| y.to_string()).unwrap_or_default())
+                .style(self.dimmed),
+            TableCell::from(track.duration.map(format_duration).unwrap_or_default())
+                .style(self.dimmed),
+        ])
+    }
+
+    /// 生成底部的状态行：解析错误、搜索进度、结果数量或使用提示。
+    fn status(&self) -> Paragraph<'_> {
+        let (text, style) = if let Some(error) = &self.error {
+            (error.clone(), self.error_style)
+        } else if self.searching {
+            ("Searching…".to_string(), self.dimmed)
+        } else if self.query.is_empty() {
+            (HINT.to_string(), self.dimmed)
+        } else if self.results.total > self.results.tracks.len() {
+            let (shown, total) = (self.results.tracks.len(), self.results.total);
+            (format!("{shown} of {total} results"), self.dimmed)
+        } else {
+            (format!("{} results", self.results.total), self.dimmed)
+        };
+        Paragraph::new(text).style(style)
+    }
+
+    /// 渲染结果列表，只为可见的行生成内容。
+    fn render_results(&self, frame: &mut Frame, rect: Rect) {
+        let tracks = &self.results.tracks;
+        let height = rect.height.saturating_sub(1) as usize; // 减去表头
+        self.rows.set(Rect {
+            y: rect.y + 1,
+            height: rect.height.saturating_sub(1),
+            ..rect
+        });
+        let offset = scroll_offset(&self.offset, self.selected, tracks.len(), height);
+        let end = (offset + height).min(tracks.len());
+        let rows: Vec<Row> = tracks[offset..end]
+            .iter()
+            .map(|track| self.track_row(track))
+            .collect();
+        let header = Row::new(["Title", "Artist", "Album", "Year", "Time"]).style(self.header);
+        let widths = [
+            Constraint::Fill(3),
+            Constraint::Fill(2),
+            Constraint::Fill(2),
+            Constraint::Length(4),
+            Constraint::Length(8),
+        ];
+        let table = Table::new(rows, widths)
+            .header(header)
+            .column_spacing(1)
+            .style(self.tui_style())
+            .row_highlight_style(self.selected_style);
+        let selected = (!tracks.is_empty()).then(|| self.selected - offset);
+        let mut state = TableState::default().with_selected(selected);
+        frame.render_stateful_widget(table, rect, &mut state);
+    }
+}
+
+impl RenderTui for SearchTui {
+    /// 渲染搜索框、结果列表和状态行。
+    fn render(&self, frame: &mut Frame, rect: Rect) {
+        let [prompt_area, results_area, status_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(rect);
+
+        let prompt_style = if self.editing {
+            self.focused
+        } else {
+            self.dimmed
+        };
+        let prompt = Paragraph::new(self.prompt.line(prompt_style)).style(self.tui_style());
+        frame.render_widget(prompt, prompt_area);
+        self.prompt_area.set(prompt_area);
+        self.render_results(frame, results_area);
+        frame.render_widget(self.status(), status_area);
+    }
+
+    fn as_event(&self) -> Option<&dyn TuiEventHandle> {
+        Some(self)
+    }
+
+    fn as_event_mut(&mut self) -> Option<&mut dyn TuiEventHandle> {
+        Some(self)
+    }
+
+    /// 重新设置页面的样式。
+    fn apply_theme(&mut self, theme: &TuiTheme) {
+        self.apply_tui_theme(theme);
+        self.header = Style::default()
+            .fg(theme.fg_dark)
+            .add_modifier(Modifier::BOLD);
+        self.focused = Style::default().fg(theme.blue).add_modifier(Modifier::BOLD);
+        self.dimmed = Style::default().fg(theme.fg_dark);
+        self.error_style = Style::default().fg(theme.red);
+        self.selected_style = Style::default().bg(theme.bg_highlight);
+    }
+}
+
+impl TuiEventHandle for SearchTui {
+    /// 处理 TUI 事件。
+    fn event_handle(&mut self, event: TuiEnent) -> EventResult {
+        match event {
+            TuiEnent::SearchResults(results) => self.set_results(results.into_owned()),
+            TuiEnent::TextInput(input) => self.text_input(input),
+            TuiEnent::Find => self.editing = true,
+            // 输入时也可以用方向键移动选中的结果
+            TuiEnent::Picker(direction) => self.pick(direction),
+            TuiEnent::PlaySelected => self.enqueue(EnqueueMode::Play),
+            TuiEnent::AddSelected => self.enqueue(EnqueueMode::Append),
+            TuiEnent::ReplaceWithSelected => self.enqueue(EnqueueMode::Replace),
+            TuiEnent::Mouse(mouse) => return self.mouse(mouse),
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Handled
+    }
+
+    /// 正在输入搜索文本时返回 `true`。
+    fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    /// 取出等待应用程序执行的操作。
+    fn take_actions(&mut self) -> Vec<TuiAction> {
+        std::mem::take(&mut self.actions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend};
+    use std::{borrow::Cow, time::Duration};
+
+    fn type_text(tui: &mut SearchTui, text: &str) {
+        for c in text.chars() {
+            tui.event_handle(TuiEnent::TextInput(TextInput::Char(c)));
+        }
+    }
+
+    fn results(query: &str, titles: &[&str], total: usize) -> SearchResults {
+        SearchResults {
+            query: query.to_string(),
+            tracks: titles
+                .iter()
+                .map(|title| Track {
+                    title: Some(title.to_string()),
+                    artist: Some("Artist".to_string()),
+                    year: Some(1999),
+                    duration: Some(Duration::from_secs(61)),
+                    ..Track::new(format!("/m/{title}.flac"))
+                })
+                .collect(),
+            total,
+        }
+    }
+
+    #[test]
+    fn test_search_tui_requests_search() {
+        let mut tui = SearchTui::default();
+        assert!(tui.is_editing());
+        type_text(&mut tui, "ab");
+        assert_eq!(
+            tui.take_actions(),
+            vec![
+                TuiAction::Search("a".to_string()),
+                TuiAction::Search("ab".to_string())
+            ]
+        );
+        assert!(tui.searching);
+
+        // 无法解析的文本只显示错误，不请求搜索
+        type_text(&mut tui, " year:x");
+        assert_eq!(tui.take_actions().len(), 4, "year: 解析成功前仍是普通的词");
+        assert!(tui.error.as_deref().unwrap().contains("invalid year"));
+        tui.event_handle(TuiEnent::TextInput(TextInput::Backspace));
+        tui.event_handle(TuiEnent::TextInput(TextInput::Backspace));
+        assert_eq!(tui.error, None);
+        assert_eq!(tui.take_actions(), vec![]);
+
+        // 只显示最新一次搜索的结果
+        tui.event_handle(TuiEnent::SearchResults(Cow::Owned(results(
+            "ab y",
+            &["X"],
+            1,
+        ))));
+        assert!(tui.results.tracks.is_empty());
+        tui.event_handle(TuiEnent::SearchResults(Cow::Owned(results(
+            "ab year",
+            &["A", "B"],
+            2,
+        ))));
+        assert_eq!(tui.results.tracks.len(), 2);
+        assert!(!tui.searching);
+    }
+
+    #[test]
+    fn test_search_tui_enqueue() {
+        let mut tui = SearchTui::default();
+        type_text(&mut tui, "a");
+        tui.take_actions();
+        tui.event_handle(TuiEnent::SearchResults(Cow::Owned(results(
+            "a",
+            &["A", "B", "C"],
+            3,
+        ))));
+
+        // 输入时方向键移动选中项，Esc 转到结果列表
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        tui.event_handle(TuiEnent::TextInput(TextInput::Cancel));
+        assert!(!tui.is_editing());
+        tui.event_handle(TuiEnent::Picker(Direction::Down));
+        tui.event_handle(TuiEnent::PlaySelected);
+        tui.event_handle(TuiEnent::AddSelected);
+        tui.event_handle(TuiEnent::ReplaceWithSelected);
+        assert_eq!(
+            tui.take_actions(),
+            [EnqueueMode::Play, EnqueueMode::Append, EnqueueMode::Replace]
+                .map(|mode| TuiAction::Enqueue(vec!["/m/C.flac".into()], mode))
+        );
+
+        // `/` 重新开始输入，结果列表中的按键不会改变搜索文本
+        tui.event_handle(TuiEnent::TextInput(TextInput::Char('x')));
+        assert_eq!(tui.prompt.text(), "a");
+        tui.event_handle(TuiEnent::Find);
+        assert!(tui.is_editing());
+    }
+
+    #[test]
+    fn test_search_tui_mouse() {
+        let mut terminal = Terminal::new(TestBackend::new(60, 6)).unwrap();
+        let mut tui = SearchTui::default();
+        type_text(&mut tui, "a");
+        tui.take_actions();
+        tui.event_handle(TuiEnent::SearchResults(Cow::Owned(results(
+            "a",
+            &["A", "B", "C"],
+            3,
+        ))));
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+        let mouse = |kind, row| {
+            TuiEnent::Mouse(Mouse {
+                kind,
+                column: 1,
+                row,
+            })
+        };
+
+        // 第 0 行是搜索框，第 1 行是表头
+        tui.event_handle(mouse(MouseKind::Click, 3));
+        assert_eq!(tui.selected, 1);
+        assert!(!tui.is_editing());
+        tui.event_handle(mouse(MouseKind::DoubleClick, 4));
+        assert_eq!(
+            tui.take_actions(),
+            vec![TuiAction::Enqueue(
+                vec!["/m/C.flac".into()],
+                EnqueueMode::Play
+            )]
+        );
+        tui.event_handle(mouse(MouseKind::Click, 0));
+        assert!(tui.is_editing());
+    }
+
+    #[test]
+    fn test_search_tui_render() {
+        let backend = TestBackend::new(60, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut tui = SearchTui::default();
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(content.contains("artist: album:"));
+
+        type_text(&mut tui, "a");
+        tui.event_handle(TuiEnent::SearchResults(Cow::Owned(results(
+            "a",
+            &["A", "B", "C"],
+            10,
+        ))));
+        terminal.draw(|f| tui.render(f, f.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        let line = |y: u16| -> String { (0..60).map(|x| buffer[(x, y)].symbol()).collect() };
+        assert!(line(0).starts_with("Search: a▏"));
+        assert!(line(1).starts_with("Title") && line(1).contains("Year"));
+        assert!(line(2).starts_with("A") && line(2).contains("1999") && line(2).contains("01:01"));
+        assert!(line(3).starts_with("B"));
+        assert!(line(4).starts_with("3 of 10 results"));
+    }
+}